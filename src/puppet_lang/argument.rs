@@ -4,6 +4,8 @@ use serde::Serialize;
 pub struct Argument<EXTRA> {
     pub type_spec: Option<super::typing::TypeSpecification<EXTRA>>,
     pub name: String,
+    /// *$rest, collects all remaining values. Allowed for the last argument only.
+    pub is_captures_rest: bool,
     pub default: Option<super::expression::Expression<EXTRA>>,
     pub comment: Vec<crate::puppet_lang::comment::Comment<EXTRA>>,
     pub extra: EXTRA,
@@ -12,6 +12,8 @@ pub enum BuiltinVariant<EXTRA> {
     Undef,
     Return(Box<Option<Expression<EXTRA>>>),
     Template(Many1<EXTRA>),
+    Epp(Many1<EXTRA>),
+    InlineEpp(Many1<EXTRA>),
     Tag(Many1<EXTRA>),
     Require(Many1<EXTRA>),
     Include(Many1<EXTRA>),
@@ -37,13 +37,25 @@ pub struct FunctionCall<EXTRA> {
 
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct Float<EXTRA> {
-    pub value: f32,
+    pub value: f64,
+    /// Number as it was written in source
+    pub lexeme: String,
     pub extra: EXTRA,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum IntegerRadix {
+    Decimal,
+    Octal,
+    Hexadecimal,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct Integer<EXTRA> {
     pub value: i64,
+    pub radix: IntegerRadix,
+    /// Number as it was written in source
+    pub lexeme: String,
     pub extra: EXTRA,
 }
 
@@ -96,6 +108,14 @@ pub struct Array<EXTRA> {
     pub extra: EXTRA,
 }
 
+/// Reference to resources, e.g. File['/etc/motd'] or Class['nginx']
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ResourceReference<EXTRA> {
+    pub name: Vec<String>,
+    pub titles: Vec<Expression<EXTRA>>,
+    pub extra: EXTRA,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum TermVariant<EXTRA> {
     String(crate::puppet_lang::string::StringExpr<EXTRA>),
@@ -110,6 +130,7 @@ pub enum TermVariant<EXTRA> {
     RegexpGroupID(RegexpGroupID<EXTRA>),
     Sensitive(Sensitive<EXTRA>),
     TypeSpecitifaction(crate::puppet_lang::typing::TypeSpecification<EXTRA>),
+    ResourceReference(ResourceReference<EXTRA>),
     Regexp(Regexp<EXTRA>),
 }
 
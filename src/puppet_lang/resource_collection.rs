@@ -35,6 +35,12 @@ pub struct SearchExpression<EXTRA> {
 pub struct ResourceCollection<EXTRA> {
     pub type_specification: crate::puppet_lang::typing::TypeSpecification<EXTRA>,
     pub search_expression: Option<SearchExpression<EXTRA>>,
+    /// Collector of exported resources, Type <<| |>>
+    pub is_exported: bool,
+    /// Attribute overrides, Type <| |> { ... }
+    pub overrides: Option<
+        crate::puppet_lang::List<EXTRA, crate::puppet_lang::statement::ResourceAttribute<EXTRA>>,
+    >,
     pub extra: EXTRA,
     pub comment: Vec<crate::puppet_lang::comment::Comment<EXTRA>>,
 }
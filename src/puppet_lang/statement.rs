@@ -9,6 +9,13 @@ pub enum ResourceAttributeVariant<EXTRA> {
             Expression<EXTRA>,
         ),
     ),
+    /// name +> value
+    Append(
+        (
+            crate::puppet_lang::string::Literal<EXTRA>,
+            Expression<EXTRA>,
+        ),
+    ),
     Group(crate::puppet_lang::expression::Term<EXTRA>),
 }
 
@@ -25,11 +32,20 @@ pub struct Resource<EXTRA> {
     pub extra: EXTRA,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum ResourceVisibility {
+    Normal,
+    /// @type { ... }
+    Virtual,
+    /// @@type { ... }
+    Exported,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct ResourceSet<EXTRA> {
     pub name: LowerIdentifier<EXTRA>,
     pub list: crate::puppet_lang::List<EXTRA, Resource<EXTRA>>,
-    pub is_virtual: bool,
+    pub visibility: ResourceVisibility,
     pub extra: EXTRA,
     pub comment: Vec<crate::puppet_lang::comment::Comment<EXTRA>>,
 }
@@ -70,7 +86,7 @@ pub struct RelationType<EXTRA> {
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum RelationEltVariant<EXTRA> {
     ResourceSet(ResourceSet<EXTRA>),
-    ResourceCollection(crate::puppet_lang::resource_collection::ResourceCollection<EXTRA>),
+    ResourceCollection(Box<crate::puppet_lang::resource_collection::ResourceCollection<EXTRA>>),
 }
 
 impl<EXTRA> crate::puppet_lang::ExtraGetter<EXTRA> for RelationEltVariant<EXTRA> {
@@ -130,6 +146,25 @@ pub struct ResourceDefaults<EXTRA> {
     pub extra: EXTRA,
 }
 
+/// File['/etc/motd'] { mode => '0600' }
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ResourceOverride<EXTRA> {
+    pub reference: crate::puppet_lang::expression::ResourceReference<EXTRA>,
+    pub attributes: crate::puppet_lang::List<EXTRA, ResourceAttribute<EXTRA>>,
+    pub extra: EXTRA,
+}
+
+/// Statement which failed to parse. Source text is kept as is.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SyntaxError<EXTRA> {
+    pub message: String,
+    pub url: Option<String>,
+    /// Where exactly parser failed
+    pub location: EXTRA,
+    pub data: String,
+    pub extra: EXTRA,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum StatementVariant<EXTRA> {
     Expression(crate::puppet_lang::expression::Expression<EXTRA>),
@@ -139,6 +174,8 @@ pub enum StatementVariant<EXTRA> {
     Case(Case<EXTRA>),
     Toplevel(crate::puppet_lang::toplevel::Toplevel<EXTRA>),
     ResourceDefaults(ResourceDefaults<EXTRA>),
+    ResourceOverride(ResourceOverride<EXTRA>),
+    SyntaxError(SyntaxError<EXTRA>),
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize)]
@@ -157,6 +194,8 @@ impl<EXTRA> crate::puppet_lang::ExtraGetter<EXTRA> for Statement<EXTRA> {
             StatementVariant::Case(v) => &v.extra,
             StatementVariant::Toplevel(v) => &v.extra,
             StatementVariant::ResourceDefaults(v) => &v.extra,
+            StatementVariant::ResourceOverride(v) => &v.extra,
+            StatementVariant::SyntaxError(v) => &v.extra,
         }
     }
 }
@@ -30,10 +30,27 @@ pub enum DoubleQuotedFragment<EXTRA> {
     Expression(Expression<EXTRA>),
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Heredoc<EXTRA> {
+    pub tag: String,
+    /// Syntax hint, e.g. "json" for @(END:json)
+    pub syntax: Option<String>,
+    /// Enabled escapes. Empty string means all escapes are enabled, e.g. @(END/)
+    pub escapes: Option<String>,
+    /// Tag was double quoted, interpolation is enabled
+    pub interpolated: bool,
+    /// Width of the left margin stripped with '|' marker
+    pub margin: Option<usize>,
+    /// Trailing newline is trimmed with '-' marker
+    pub trim_newline: bool,
+    pub data: Vec<DoubleQuotedFragment<EXTRA>>,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum StringVariant<EXTRA> {
     SingleQuoted(Vec<StringFragment<EXTRA>>),
     DoubleQuoted(Vec<DoubleQuotedFragment<EXTRA>>),
+    Heredoc(Heredoc<EXTRA>),
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize)]
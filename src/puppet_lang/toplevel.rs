@@ -54,6 +54,21 @@ pub struct FunctionDef<EXTRA> {
     pub extra: EXTRA,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum NodeMatcher<EXTRA> {
+    Name(crate::puppet_lang::string::StringExpr<EXTRA>),
+    Regexp(crate::puppet_lang::expression::Regexp<EXTRA>),
+    Default(crate::puppet_lang::expression::Default<EXTRA>),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Node<EXTRA> {
+    pub matchers: Vec<NodeMatcher<EXTRA>>,
+    pub inherits: Option<NodeMatcher<EXTRA>>,
+    pub body: crate::puppet_lang::List<EXTRA, crate::puppet_lang::statement::Statement<EXTRA>>,
+    pub extra: EXTRA,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum ToplevelVariant<EXTRA> {
     Class(Class<EXTRA>),
@@ -61,6 +76,7 @@ pub enum ToplevelVariant<EXTRA> {
     Plan(Plan<EXTRA>),
     TypeDef(TypeDef<EXTRA>),
     FunctionDef(FunctionDef<EXTRA>),
+    Node(Node<EXTRA>),
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize)]
@@ -155,6 +155,138 @@ pub struct ExternalType<EXTRA> {
     pub extra: EXTRA,
 }
 
+/// Type[T]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeType<EXTRA> {
+    pub inner: Option<Box<TypeSpecification<EXTRA>>>,
+    pub extra: EXTRA,
+}
+
+/// Class['name', ...]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeClass<EXTRA> {
+    pub titles: Vec<crate::puppet_lang::expression::Expression<EXTRA>>,
+    pub extra: EXTRA,
+}
+
+/// Resource[type, 'title', ...]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeResource<EXTRA> {
+    pub resource_type: Option<Box<crate::puppet_lang::expression::Expression<EXTRA>>>,
+    pub titles: Vec<crate::puppet_lang::expression::Expression<EXTRA>>,
+    pub extra: EXTRA,
+}
+
+/// Callable[params..., min, max, block] or Callable[[params..., min, max, block], return_type]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeCallable<EXTRA> {
+    pub params: Vec<TypeSpecification<EXTRA>>,
+    pub min: Option<crate::puppet_lang::expression::Usize<EXTRA>>,
+    pub max: Option<crate::puppet_lang::expression::Usize<EXTRA>>,
+    pub block: Option<Box<TypeSpecification<EXTRA>>>,
+    pub return_type: Option<Box<TypeSpecification<EXTRA>>>,
+    pub extra: EXTRA,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeIterable<EXTRA> {
+    pub inner: Option<Box<TypeSpecification<EXTRA>>>,
+    pub extra: EXTRA,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeIterator<EXTRA> {
+    pub inner: Option<Box<TypeSpecification<EXTRA>>>,
+    pub extra: EXTRA,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum TypeNotUndefVariant<EXTRA> {
+    TypeSpecification(Box<TypeSpecification<EXTRA>>),
+    Term(Box<crate::puppet_lang::expression::Term<EXTRA>>),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeNotUndef<EXTRA> {
+    pub value: Option<TypeNotUndefVariant<EXTRA>>,
+    pub extra: EXTRA,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Scalar<EXTRA> {
+    pub extra: EXTRA,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Data<EXTRA> {
+    pub extra: EXTRA,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeCollection<EXTRA> {
+    pub min: Option<crate::puppet_lang::expression::Usize<EXTRA>>,
+    pub max: Option<crate::puppet_lang::expression::Usize<EXTRA>>,
+    pub extra: EXTRA,
+}
+
+/// SemVer['>=1.0.0 <2.0.0', ...]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeSemVer<EXTRA> {
+    pub ranges: Vec<crate::puppet_lang::expression::Expression<EXTRA>>,
+    pub extra: EXTRA,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SemVerRange<EXTRA> {
+    pub extra: EXTRA,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeTimespan<EXTRA> {
+    pub min: Option<Box<crate::puppet_lang::expression::Term<EXTRA>>>,
+    pub max: Option<Box<crate::puppet_lang::expression::Term<EXTRA>>>,
+    pub extra: EXTRA,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeTimestamp<EXTRA> {
+    pub min: Option<Box<crate::puppet_lang::expression::Term<EXTRA>>>,
+    pub max: Option<Box<crate::puppet_lang::expression::Term<EXTRA>>>,
+    pub extra: EXTRA,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Binary<EXTRA> {
+    pub extra: EXTRA,
+}
+
+/// Init[T, args...]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeInit<EXTRA> {
+    pub inner: Option<Box<TypeSpecification<EXTRA>>>,
+    pub arguments: Vec<crate::puppet_lang::expression::Expression<EXTRA>>,
+    pub extra: EXTRA,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Deferred<EXTRA> {
+    pub extra: EXTRA,
+}
+
+/// Runtime['ruby', 'ClassName']
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeRuntime<EXTRA> {
+    pub arguments: Vec<crate::puppet_lang::expression::Expression<EXTRA>>,
+    pub extra: EXTRA,
+}
+
+/// Object[{ attributes => ... }]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TypeObject<EXTRA> {
+    pub arguments: Vec<crate::puppet_lang::expression::Expression<EXTRA>>,
+    pub extra: EXTRA,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum TypeSpecificationVariant<EXTRA> {
     Float(TypeFloat<EXTRA>),
@@ -175,6 +307,25 @@ pub enum TypeSpecificationVariant<EXTRA> {
     ExternalType(ExternalType<EXTRA>),
     Sensitive(TypeSensitive<EXTRA>),
     Tuple(TypeTuple<EXTRA>),
+    Type(TypeType<EXTRA>),
+    Class(TypeClass<EXTRA>),
+    Resource(TypeResource<EXTRA>),
+    Callable(TypeCallable<EXTRA>),
+    Iterable(TypeIterable<EXTRA>),
+    Iterator(TypeIterator<EXTRA>),
+    NotUndef(TypeNotUndef<EXTRA>),
+    Scalar(Scalar<EXTRA>),
+    Data(Data<EXTRA>),
+    Collection(TypeCollection<EXTRA>),
+    SemVer(TypeSemVer<EXTRA>),
+    SemVerRange(SemVerRange<EXTRA>),
+    Timespan(TypeTimespan<EXTRA>),
+    Timestamp(TypeTimestamp<EXTRA>),
+    Binary(Binary<EXTRA>),
+    Init(TypeInit<EXTRA>),
+    Deferred(Deferred<EXTRA>),
+    Runtime(TypeRuntime<EXTRA>),
+    Object(TypeObject<EXTRA>),
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize)]
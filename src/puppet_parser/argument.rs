@@ -5,9 +5,24 @@ use nom::{
     sequence::{pair, preceded, tuple},
 };
 
-pub fn parse(input: Span) -> IResult<crate::puppet_lang::argument::Argument<Range>> {
+/// Capturing rest of values with '*' is allowed only when `allow_captures_rest` is set, i.e. for
+/// parameters of lambdas and functions
+pub fn parse<'a>(
+    allow_captures_rest: bool,
+) -> impl FnMut(Span<'a>) -> IResult<'a, crate::puppet_lang::argument::Argument<Range>> {
+    let parse_captures_rest = move |input: Span<'a>| {
+        if !allow_captures_rest && tag::<_, _, ParseError>("*")(input).is_ok() {
+            return ParseError::fatal(
+                "Only parameters of lambdas and functions can capture rest of values".to_string(),
+                input,
+            );
+        }
+        opt(tag("*"))(input)
+    };
+
     let parser = tuple((
         super::common::space0_delimimited(opt(crate::puppet_parser::typing::parse_type_specification)),
+        parse_captures_rest,
         tag("$"),
         ParseError::protect(
             |_| "Invalid variable name".to_owned(),
@@ -24,10 +39,11 @@ pub fn parse(input: Span) -> IResult<crate::puppet_lang::argument::Argument<Rang
 
     map(
         pair(capture_comment, parser),
-        move |(comment, (type_spec, dollar_sign, name, default))| {
-            let start_range = match &type_spec {
-                None => Range::from((dollar_sign, dollar_sign)),
-                Some(v) => v.extra.clone(),
+        move |(comment, (type_spec, captures_rest, dollar_sign, name, default))| {
+            let start_range = match (&type_spec, &captures_rest) {
+                (Some(v), _) => v.extra.clone(),
+                (None, Some(asterisk)) => Range::from((asterisk, asterisk)),
+                (None, None) => Range::from((dollar_sign, dollar_sign)),
             };
             let end_range = match &default {
                 None => Range::from((name, name)),
@@ -37,17 +53,18 @@ pub fn parse(input: Span) -> IResult<crate::puppet_lang::argument::Argument<Rang
                 type_spec,
                 extra: Range::from((&start_range, &end_range)),
                 name: name.to_string(),
+                is_captures_rest: captures_rest.is_some(),
                 default,
                 comment,
             }
         },
-    )(input)
+    )
 }
 
 // #[test]
 // fn test_argument() {
 //     assert_eq!(
-//         Argument::parse(Span::new("Any $v   =  1")).unwrap().1,
+//         Argument::parse(Span::new_extra("Any $v   =  1", &Default::default())).unwrap().1,
 //         Marked {
 //             line: 1,
 //             column: 1,
@@ -70,7 +87,7 @@ pub fn parse(input: Span) -> IResult<crate::puppet_lang::argument::Argument<Rang
 //     assert!(tuple((
 //         super::common::space0_delimimited(opt(super::typing::TypeSpecification::parse)),
 //         tag("$")
-//     ))(Span::new("Hash[String, String] $aaa"))
+//     ))(Span::new_extra("Hash[String, String] $aaa", &Default::default()))
 //     .is_ok());
-//     assert!(Argument::parse(Span::new("Hash[String, String] $aaa")).is_ok());
+//     assert!(Argument::parse(Span::new_extra("Hash[String, String] $aaa", &Default::default())).is_ok());
 // }
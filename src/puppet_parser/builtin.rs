@@ -237,6 +237,36 @@ fn parse_template(input: Span) -> IResult<Expression<Range>> {
     )(input)
 }
 
+fn parse_epp(input: Span) -> IResult<Expression<Range>> {
+    builtin_many1(
+        "epp",
+        |(comment, _kw, ((args, range), lambda), accessor)| Expression {
+            value: ExpressionVariant::BuiltinFunction(BuiltinVariant::Epp(builtin::Many1 {
+                lambda,
+                args,
+            })),
+            extra: range,
+            comment,
+            accessor,
+        },
+    )(input)
+}
+
+fn parse_inline_epp(input: Span) -> IResult<Expression<Range>> {
+    builtin_many1(
+        "inline_epp",
+        |(comment, _kw, ((args, range), lambda), accessor)| Expression {
+            value: ExpressionVariant::BuiltinFunction(BuiltinVariant::InlineEpp(builtin::Many1 {
+                lambda,
+                args,
+            })),
+            extra: range,
+            comment,
+            accessor,
+        },
+    )(input)
+}
+
 fn parse_tag(input: Span) -> IResult<Expression<Range>> {
     builtin_many1(
         "tag",
@@ -316,6 +346,8 @@ pub fn parse_builtin(input: Span) -> IResult<Expression<Range>> {
         parse_undef,
         parse_return,
         parse_template,
+        parse_epp,
+        parse_inline_epp,
         parse_tag,
         parse_require,
         parse_include,
@@ -327,7 +359,9 @@ pub fn parse_builtin(input: Span) -> IResult<Expression<Range>> {
 #[test]
 fn test_undef() {
     assert_eq!(
-        parse_builtin(Span::new("undef")).unwrap().1,
+        parse_builtin(Span::new_extra("undef", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Expression {
             accessor: None,
             comment: vec![],
@@ -339,7 +373,9 @@ fn test_undef() {
     );
 
     assert_eq!(
-        parse_builtin(Span::new("undef()")).unwrap().1,
+        parse_builtin(Span::new_extra("undef()", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Expression {
             accessor: None,
             comment: vec![],
@@ -354,7 +390,9 @@ fn test_undef() {
 #[test]
 fn test_return() {
     assert_eq!(
-        parse_builtin(Span::new("return")).unwrap().1,
+        parse_builtin(Span::new_extra("return", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Expression {
             accessor: None,
             comment: vec![],
@@ -366,7 +404,9 @@ fn test_return() {
     );
 
     assert_eq!(
-        parse_builtin(Span::new("return(100)")).unwrap().1,
+        parse_builtin(Span::new_extra("return(100)", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Expression {
             accessor: None,
             comment: vec![],
@@ -378,6 +418,8 @@ fn test_return() {
                                 value: crate::puppet_lang::expression::TermVariant::Integer(
                                     crate::puppet_lang::expression::Integer {
                                         value: 100,
+                                        radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                        lexeme: "100".to_string(),
                                         extra: Range::new(7, 1, 8, 9, 1, 10),
                                     }
                                 ),
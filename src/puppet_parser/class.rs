@@ -1,14 +1,16 @@
 use crate::puppet_parser::common::{space0_delimimited, spaced_word};
 use crate::puppet_parser::{range::Range, IResult, ParseError, Span};
 use nom::{
+    branch::alt,
     bytes::complete::tag,
-    combinator::{map, opt},
+    combinator::{map, opt, recognize},
+    multi::separated_list1,
     sequence::{pair, preceded, tuple},
 };
 use crate::puppet_lang::{
     argument::Argument,
     identifier::LowerIdentifier,
-    toplevel::{Class, Definition, Plan},
+    toplevel::{Class, Definition, Node, NodeMatcher, Plan},
 };
 
 type Header = (
@@ -16,10 +18,13 @@ type Header = (
     crate::puppet_lang::List<Range, Argument<Range>>,
 );
 
-pub fn parse_header(input: Span) -> IResult<Header> {
+/// Parameters capturing rest of values are allowed only when `allow_captures_rest` is set
+pub fn parse_header<'a>(allow_captures_rest: bool) -> impl FnMut(Span<'a>) -> IResult<'a, Header> {
     let arguments_parser = map(
         opt(crate::puppet_parser::common::round_parens_delimimited(
-            crate::puppet_parser::common::comma_separated_list0_with_last_comment(crate::puppet_parser::argument::parse),
+            crate::puppet_parser::common::comma_separated_list0_with_last_comment(
+                crate::puppet_parser::argument::parse(allow_captures_rest),
+            ),
         )),
         |v: Option<(Span, crate::puppet_lang::List<Range, Argument<Range>>, Span)>| {
             v.map(|v| v.1).unwrap_or_else(crate::puppet_lang::List::default)
@@ -32,7 +37,7 @@ pub fn parse_header(input: Span) -> IResult<Header> {
             crate::puppet_parser::identifier::identifier_with_toplevel,
         ),
         preceded(super::common::separator0, arguments_parser),
-    ))(input)
+    ))
 }
 
 pub fn parse_class(input: Span) -> IResult<Class<Range>> {
@@ -41,7 +46,7 @@ pub fn parse_class(input: Span) -> IResult<Class<Range>> {
             spaced_word("class"),
             space0_delimimited(ParseError::protect(
                 |_| "Failed to parse class header".to_owned(),
-                parse_header,
+                parse_header(false),
             )),
             ParseError::protect(
                 |_| "'{' or 'inherits' expected".to_string(),
@@ -53,7 +58,7 @@ pub fn parse_class(input: Span) -> IResult<Class<Range>> {
                             space0_delimimited(crate::puppet_parser::identifier::identifier_with_toplevel),
                         ),
                     ))),
-                    crate::puppet_parser::statement::parse_statement_block,
+                    crate::puppet_parser::statement::parse_statement_block_with_recovery,
                 ),
             ),
         )),
@@ -75,11 +80,11 @@ pub fn parse_definition(input: Span) -> IResult<Definition<Range>> {
             spaced_word("define"),
             space0_delimimited(ParseError::protect(
                 |_| "Failed to parse definition header".to_owned(),
-                parse_header,
+                parse_header(false),
             )),
             space0_delimimited(ParseError::protect(
                 |_| "'{' expected".to_string(),
-                crate::puppet_parser::statement::parse_statement_block,
+                crate::puppet_parser::statement::parse_statement_block_with_recovery,
             )),
         )),
         |(kw, (identifier, arguments), (_left_bracket, body, right_bracket))| Definition {
@@ -97,11 +102,11 @@ pub fn parse_plan(input: Span) -> IResult<Plan<Range>> {
             spaced_word("plan"),
             space0_delimimited(ParseError::protect(
                 |_| "Failed to parse plan header".to_owned(),
-                parse_header,
+                parse_header(false),
             )),
             space0_delimimited(ParseError::protect(
                 |_| "'{' expected".to_string(),
-                crate::puppet_parser::statement::parse_statement_block,
+                crate::puppet_parser::statement::parse_statement_block_with_recovery,
             )),
         )),
         |(kw, (identifier, arguments), (_left_bracket, body, right_bracket))| Plan {
@@ -113,10 +118,77 @@ pub fn parse_plan(input: Span) -> IResult<Plan<Range>> {
     )(input)
 }
 
+fn parse_node_matcher(input: Span) -> IResult<NodeMatcher<Range>> {
+    // Unquoted host names are allowed to contain dots
+    let hostname = map(
+        recognize(separated_list1(
+            tag("."),
+            crate::puppet_parser::single_quoted::bareword,
+        )),
+        |data: Span| crate::puppet_lang::string::StringExpr {
+            extra: Range::from((data, data)),
+            data: crate::puppet_lang::string::StringVariant::SingleQuoted(vec![
+                crate::puppet_lang::string::StringFragment::Literal(
+                    crate::puppet_lang::string::Literal {
+                        extra: Range::from((data, data)),
+                        data: (*data).to_owned(),
+                    },
+                ),
+            ]),
+        },
+    );
+
+    alt((
+        map(crate::puppet_parser::common::word("default"), |kw| {
+            NodeMatcher::Default(crate::puppet_lang::expression::Default {
+                extra: Range::from((kw, kw)),
+            })
+        }),
+        map(crate::puppet_parser::regex::parse, NodeMatcher::Regexp),
+        map(crate::puppet_parser::double_quoted::parse, NodeMatcher::Name),
+        map(hostname, NodeMatcher::Name),
+        map(crate::puppet_parser::single_quoted::parse, NodeMatcher::Name),
+    ))(input)
+}
+
+pub fn parse_node(input: Span) -> IResult<Node<Range>> {
+    map(
+        tuple((
+            spaced_word("node"),
+            ParseError::protect(
+                |_| "Failed to parse node name".to_owned(),
+                separated_list1(
+                    crate::puppet_parser::common::comma_separator,
+                    space0_delimimited(parse_node_matcher),
+                ),
+            ),
+            ParseError::protect(
+                |_| "'{' or 'inherits' expected".to_string(),
+                pair(
+                    space0_delimimited(opt(preceded(
+                        crate::puppet_parser::common::word("inherits"),
+                        ParseError::protect(
+                            |_| "Failed to parse what node is inherited".to_owned(),
+                            space0_delimimited(parse_node_matcher),
+                        ),
+                    ))),
+                    crate::puppet_parser::statement::parse_statement_block_with_recovery,
+                ),
+            ),
+        )),
+        |(kw, matchers, (inherits, (_left_bracket, body, right_bracket)))| Node {
+            matchers,
+            inherits,
+            body,
+            extra: (kw, right_bracket).into(),
+        },
+    )(input)
+}
+
 #[test]
 fn test_class() {
     assert_eq!(
-        parse_class(Span::new("class  abc::def () {\n  }\n"))
+        parse_class(Span::new_extra("class  abc::def () {\n  }\n", &Default::default()))
             .unwrap()
             .1,
         Class {
@@ -132,21 +204,27 @@ fn test_class() {
         }
     );
 
-    assert!(parse_class(Span::new(
-        "class  ab__c::de11f ( String[1,10] $a, Stdlib::Unixpath $b  ,  $c) inherits aa::bb { }"
+    assert!(parse_class(Span::new_extra(
+        "class  ab__c::de11f ( String[1,10] $a, Stdlib::Unixpath $b  ,  $c) inherits aa::bb { }",
+        &Default::default(),
     ))
     .is_ok());
 
-    assert!(parse_class(Span::new("class a ( $a = ,) {}")).is_err());
+    assert!(parse_class(Span::new_extra("class a ( $a = ,) {}", &Default::default())).is_err());
 
-    assert!(parse_class(Span::new("class a () { &&&&& UNKNOWN((STATEMENT}")).is_err())
+    assert!(parse_class(Span::new_extra(
+        "class a () { &&&&& UNKNOWN((STATEMENT}",
+        &Default::default()
+    ))
+    .is_err())
 }
 
 #[test]
 fn test_body_tag() {
     assert_eq!(
-        parse_class(Span::new(
-            "class  abc::def () {\n tag aaa, 'bbb', \"ccc\" }\n"
+        parse_class(Span::new_extra(
+            "class  abc::def () {\n tag aaa, 'bbb', \"ccc\" }\n",
+            &Default::default(),
         ))
         .unwrap()
         .1,
@@ -264,8 +342,9 @@ fn test_body_tag() {
 #[test]
 fn test_body_require() {
     assert_eq!(
-        parse_class(Span::new(
-            "class  abc::def () {\n require abc::def require zzz }\n"
+        parse_class(Span::new_extra(
+            "class  abc::def () {\n require abc::def require zzz }\n",
+            &Default::default(),
         ))
             .unwrap()
             .1,
@@ -361,3 +440,57 @@ fn test_body_require() {
         }
     );
 }
+
+#[test]
+fn test_node() {
+    assert_eq!(
+        parse_node(Span::new_extra("node default {}", &Default::default()))
+            .unwrap()
+            .1,
+        Node {
+            matchers: vec![NodeMatcher::Default(
+                crate::puppet_lang::expression::Default {
+                    extra: Range::new(5, 1, 6, 11, 1, 12),
+                }
+            )],
+            inherits: None,
+            body: crate::puppet_lang::List::default(),
+            extra: Range::new(0, 1, 1, 14, 1, 15),
+        }
+    );
+
+    let node = parse_node(Span::new_extra(
+        "node 'web01.example.com', /^db\\d+/, www.example.com inherits base {\n  include role\n}",
+        &Default::default(),
+    ))
+    .unwrap()
+    .1;
+    assert_eq!(node.matchers.len(), 3);
+    assert!(matches!(node.matchers[1], NodeMatcher::Regexp(_)));
+    assert!(matches!(node.inherits, Some(NodeMatcher::Name(_))));
+    assert_eq!(node.body.value.len(), 1);
+
+    assert!(parse_node(Span::new_extra("node {}", &Default::default())).is_err());
+}
+
+#[test]
+fn test_captures_rest() {
+    assert!(matches!(
+        parse_class(Span::new_extra(
+            "class foo (*$rest) {}",
+            &Default::default()
+        )),
+        Err(nom::Err::Failure(_))
+    ));
+    assert!(matches!(
+        parse_definition(Span::new_extra(
+            "define bar (*$rest) {}",
+            &Default::default()
+        )),
+        Err(nom::Err::Failure(_))
+    ));
+    let (_, function) =
+        crate::puppet_parser::toplevel::parse_functiondef(Span::new_extra("function baz (*$rest) {}", &Default::default()))
+            .unwrap();
+    assert!(function.arguments.value[0].is_captures_rest);
+}
@@ -1,7 +1,7 @@
 use nom::{
     branch::alt,
     bytes::complete::{is_not, tag, take_until},
-    character::complete::{anychar, multispace0, multispace1, newline},
+    character::complete::{anychar, newline, one_of, space0},
     combinator::{eof, map, opt, peek, recognize, value, verify},
     multi::{many0, separated_list0, separated_list1},
     sequence::{delimited, pair, preceded, terminated, tuple},
@@ -10,6 +10,22 @@ use nom::{
 
 use crate::puppet_parser::{range::Range, IResult, ParseError, Span};
 
+/// Same as nom's multispace0, but also skips bodies of heredocs which headers are followed by
+/// other tokens on the same line
+pub fn multispace0(input: Span) -> IResult<Span> {
+    let (mut rest, _) = space0(input)?;
+    while let Ok((next, _)) = one_of::<_, _, ParseError>("\r\n")(rest) {
+        let next = crate::puppet_parser::heredoc::skip_body(next).unwrap_or(next);
+        rest = space0(next)?.0;
+    }
+    let len = rest.location_offset() - input.location_offset();
+    Ok((rest, nom::Slice::slice(&input, ..len)))
+}
+
+pub fn multispace1(input: Span) -> IResult<Span> {
+    verify(multispace0, |v: &Span| !v.is_empty())(input)
+}
+
 pub fn shell_comment(input: Span) -> IResult<(Span, Span, Span)> {
     tuple((
         tag("#"),
@@ -44,7 +60,7 @@ pub fn list_with_last_comment<'a, O, F>(
     parser: F,
 ) -> impl FnMut(Span<'a>) -> IResult<crate::puppet_lang::List<Range, O>>
 where
-    F: Parser<Span<'a>, Vec<O>, ParseError<'a>>,
+    F: Parser<Span<'a>, Vec<O>, ParseError>,
     O: Clone,
 {
     map(pair(parser, capture_comment), |(value, last_comment)| {
@@ -59,7 +75,7 @@ pub fn comma_separated_list0_with_last_comment<'a, O, F>(
     parser: F,
 ) -> impl FnMut(Span<'a>) -> IResult<crate::puppet_lang::List<Range, O>>
 where
-    F: Parser<Span<'a>, O, ParseError<'a>>,
+    F: Parser<Span<'a>, O, ParseError>,
     O: Clone,
 {
     list_with_last_comment(terminated(
@@ -72,7 +88,7 @@ pub fn comma_separated_list1_with_last_comment<'a, O, F>(
     parser: F,
 ) -> impl FnMut(Span<'a>) -> IResult<crate::puppet_lang::List<Range, O>>
 where
-    F: Parser<Span<'a>, O, ParseError<'a>>,
+    F: Parser<Span<'a>, O, ParseError>,
     O: Clone,
 {
     list_with_last_comment(terminated(
@@ -83,7 +99,10 @@ where
 
 #[test]
 fn test_comment() {
-    let (_, res) = shell_comment.parse(Span::new("# hello world\n")).unwrap();
+    let state = crate::puppet_parser::ParserState::default();
+    let (_, res) = shell_comment
+        .parse(Span::new_extra("# hello world\n", &state))
+        .unwrap();
     assert_eq!(*res.1, " hello world")
 }
 
@@ -97,7 +116,7 @@ pub fn separator0(input: Span) -> IResult<()> {
 
 pub fn space0_delimimited<'a, O, F>(parser: F) -> impl FnMut(Span<'a>) -> IResult<O>
 where
-    F: Parser<Span<'a>, O, ParseError<'a>>,
+    F: Parser<Span<'a>, O, ParseError>,
     O: Clone,
 {
     delimited(separator0, parser, separator0)
@@ -105,7 +124,7 @@ where
 
 pub fn space1_delimimited<'a, O, F>(parser: F) -> impl FnMut(Span<'a>) -> IResult<O>
 where
-    F: Parser<Span<'a>, O, ParseError<'a>>,
+    F: Parser<Span<'a>, O, ParseError>,
     O: Clone,
 {
     delimited(separator1, parser, separator1)
@@ -239,7 +258,7 @@ pub fn round_brackets_comma_separated0<'a, O, F>(
     parser: F,
 ) -> impl FnMut(Span<'a>) -> IResult<(Span<'a>, Vec<O>, Span<'a>)>
 where
-    F: Parser<Span<'a>, O, ParseError<'a>>,
+    F: Parser<Span<'a>, O, ParseError>,
     O: Clone,
 {
     round_parens_delimimited(terminated(
@@ -253,7 +272,7 @@ pub fn round_brackets_comma_separated1<'a, O, F>(
     parser: F,
 ) -> impl FnMut(Span<'a>) -> IResult<(Span<'a>, Vec<O>, Span<'a>)>
 where
-    F: Parser<Span<'a>, O, ParseError<'a>>,
+    F: Parser<Span<'a>, O, ParseError>,
     O: Clone,
 {
     round_parens_delimimited(terminated(
@@ -285,7 +304,7 @@ pub fn square_brackets_comma_separated0<'a, O, F>(
     parser: F,
 ) -> impl FnMut(Span<'a>) -> IResult<(Span<'a>, Vec<O>, Span<'a>)>
 where
-    F: Parser<Span<'a>, O, ParseError<'a>>,
+    F: Parser<Span<'a>, O, ParseError>,
     O: Clone,
 {
     square_brackets_delimimited(
@@ -303,7 +322,7 @@ pub fn square_brackets_comma_separated1<'a, O, F>(
     parser: F,
 ) -> impl FnMut(Span<'a>) -> IResult<(Span<'a>, Vec<O>, Span<'a>)>
 where
-    F: Parser<Span<'a>, O, ParseError<'a>>,
+    F: Parser<Span<'a>, O, ParseError>,
     O: Clone,
 {
     square_brackets_delimimited(
@@ -321,7 +340,7 @@ pub fn curly_brackets_comma_separated0<'a, O, F>(
     parser: F,
 ) -> impl FnMut(Span<'a>) -> IResult<(Span<'a>, Vec<O>, Span<'a>)>
 where
-    F: Parser<Span<'a>, O, ParseError<'a>>,
+    F: Parser<Span<'a>, O, ParseError>,
     O: Clone,
 {
     curly_brackets_delimimited(
@@ -338,7 +357,7 @@ pub fn pipes_comma_separated0<'a, O, F>(
     parser: F,
 ) -> impl FnMut(Span<'a>) -> IResult<(Span<'a>, Vec<O>, Span<'a>)>
 where
-    F: Parser<Span<'a>, O, ParseError<'a>>,
+    F: Parser<Span<'a>, O, ParseError>,
     O: Clone,
 {
     pipes_delimimited(terminated(
@@ -354,7 +373,7 @@ pub fn fold_many0_with_const_init<'a, F, G, O, R>(
     g: G,
 ) -> impl FnMut(Span<'a>) -> IResult<R>
 where
-    F: nom::Parser<Span<'a>, O, ParseError<'a>>,
+    F: nom::Parser<Span<'a>, O, ParseError>,
     G: Fn(R, O) -> R,
     R: Clone,
 {
@@ -392,8 +411,9 @@ where
 
 #[test]
 fn test_round_brackets_comma_separated0() {
+    let state = crate::puppet_parser::ParserState::default();
     assert_eq!(
-        round_brackets_comma_separated0(tag("a"))(Span::new("( a,a ,a, a,)"))
+        round_brackets_comma_separated0(tag("a"))(Span::new_extra("( a,a ,a, a,)", &state))
             .unwrap()
             .1
              .1
@@ -402,10 +422,11 @@ fn test_round_brackets_comma_separated0() {
             .collect::<Vec<_>>(),
         vec!["a", "a", "a", "a"]
     );
+    let state = crate::puppet_parser::ParserState::default();
     assert_eq!(
-        round_brackets_comma_separated0(round_brackets_comma_separated0(tag("a")))(Span::new(
-            "( (a) , (a) ,(   a   ), ( a ) )"
-        ))
+        round_brackets_comma_separated0(round_brackets_comma_separated0(tag("a")))(
+            Span::new_extra("( (a) , (a) ,(   a   ), ( a ) )", &state)
+        )
         .unwrap()
         .1
          .1
@@ -413,13 +434,14 @@ fn test_round_brackets_comma_separated0() {
         .map(|v| v.1.into_iter().map(|v| *v).collect::<Vec<_>>())
         .collect::<Vec<_>>(),
         vec![vec!["a"], vec!["a"], vec!["a"], vec!["a"]]
-    )
+    );
 }
 
 #[test]
 fn test_square_brackets_comma_separated0() {
+    let state = crate::puppet_parser::ParserState::default();
     assert_eq!(
-        square_brackets_comma_separated0(true, tag("a"))(Span::new("[a]"))
+        square_brackets_comma_separated0(true, tag("a"))(Span::new_extra("[a]", &state))
             .unwrap()
             .1
              .1
@@ -428,8 +450,9 @@ fn test_square_brackets_comma_separated0() {
             .collect::<Vec<_>>(),
         vec!["a"]
     );
+    let state = crate::puppet_parser::ParserState::default();
     assert_eq!(
-        square_brackets_comma_separated0(true, tag("a"))(Span::new("[a,]"))
+        square_brackets_comma_separated0(true, tag("a"))(Span::new_extra("[a,]", &state))
             .unwrap()
             .1
              .1
@@ -437,5 +460,5 @@ fn test_square_brackets_comma_separated0() {
             .map(|v| *v)
             .collect::<Vec<_>>(),
         vec!["a"]
-    )
+    );
 }
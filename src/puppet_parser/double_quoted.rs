@@ -21,7 +21,7 @@ fn parse_literal(input: Span) -> IResult<StringFragment<Range>> {
     )(input)
 }
 
-fn parse_interpolation(input: Span) -> IResult<DoubleQuotedFragment<Range>> {
+pub fn parse_interpolation(input: Span) -> IResult<DoubleQuotedFragment<Range>> {
     let parser_variable = || {
         map(
             pair(
@@ -129,14 +129,18 @@ pub fn parse(input: Span) -> IResult<StringExpr<Range>> {
 #[test]
 fn test_simple() {
     assert_eq!(
-        parse(Span::new("\"\"")).unwrap().1,
+        parse(Span::new_extra("\"\"", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::string::StringExpr {
             data: crate::puppet_lang::string::StringVariant::DoubleQuoted(vec![]),
             extra: Range::new(0, 1, 1, 1, 1, 2)
         }
     );
     assert_eq!(
-        parse(Span::new("\"a\"")).unwrap().1,
+        parse(Span::new_extra("\"a\"", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::string::StringExpr {
             data: crate::puppet_lang::string::StringVariant::DoubleQuoted(vec![
                 DoubleQuotedFragment::StringFragment(
@@ -152,7 +156,9 @@ fn test_simple() {
         }
     );
     assert_eq!(
-        parse(Span::new("\"\\\"\"")).unwrap().1,
+        parse(Span::new_extra("\"\\\"\"", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::string::StringExpr {
             data: crate::puppet_lang::string::StringVariant::DoubleQuoted(vec![
                 DoubleQuotedFragment::StringFragment(
@@ -172,7 +178,9 @@ fn test_simple() {
 #[test]
 fn test_no_interpolation() {
     assert_eq!(
-        parse(Span::new("\"$\"")).unwrap().1,
+        parse(Span::new_extra("\"$\"", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::string::StringExpr {
             data: crate::puppet_lang::string::StringVariant::DoubleQuoted(vec![
                 DoubleQuotedFragment::StringFragment(
@@ -188,7 +196,9 @@ fn test_no_interpolation() {
         }
     );
     assert_eq!(
-        parse(Span::new("\"$(\"")).unwrap().1,
+        parse(Span::new_extra("\"$(\"", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::string::StringExpr {
             data: crate::puppet_lang::string::StringVariant::DoubleQuoted(vec![
                 DoubleQuotedFragment::StringFragment(
@@ -216,7 +226,9 @@ fn test_no_interpolation() {
 #[test]
 fn test_interpolatad_variable() {
     assert_eq!(
-        parse(Span::new("\"${varname}\"")).unwrap().1,
+        parse(Span::new_extra("\"${varname}\"", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::string::StringExpr {
             data: crate::puppet_lang::string::StringVariant::DoubleQuoted(vec![
                 crate::puppet_lang::string::DoubleQuotedFragment::Expression(
@@ -254,7 +266,9 @@ fn test_interpolatad_variable() {
 #[test]
 fn test_interpolatad_expression() {
     assert_eq!(
-        parse(Span::new("\"${funcall()}\"")).unwrap().1,
+        parse(Span::new_extra("\"${funcall()}\"", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::string::StringExpr {
             data: crate::puppet_lang::string::StringVariant::DoubleQuoted(vec![
                 crate::puppet_lang::string::DoubleQuotedFragment::Expression(
@@ -17,7 +17,7 @@ use crate::puppet_parser::range::Range;
 use crate::puppet_parser::term::parse_term;
 use crate::puppet_parser::{IResult, ParseError, Span};
 
-use nom::{branch::alt, combinator::map};
+use nom::{branch::alt, combinator::map, Slice};
 
 pub fn parse_accessor(input: Span) -> IResult<Option<Accessor<Range>>> {
     opt(map(
@@ -175,24 +175,32 @@ pub fn parse_string_expr(input: Span) -> IResult<crate::puppet_lang::expression:
 }
 
 pub fn parse_lambda(input: Span) -> IResult<crate::puppet_lang::expression::Lambda<Range>> {
-    map(
-        pair(
-            crate::puppet_parser::common::pipes_delimimited(
-                crate::puppet_parser::common::comma_separated_list0_with_last_comment(crate::puppet_parser::argument::parse),
-            ),
-            space0_delimimited(ParseError::protect(
-                |_| "'{' expected".to_string(),
-                crate::puppet_parser::statement::parse_statement_block,
-            )),
+    let (tail, (left_pipe, args, _right_pipe)) = crate::puppet_parser::common::pipes_delimimited(
+        crate::puppet_parser::common::comma_separated_list0_with_last_comment(
+            crate::puppet_parser::argument::parse(true),
         ),
-        |((left_pipe, args, _right_pipe), (_left_curly, body, right_curly))| {
-            crate::puppet_lang::expression::Lambda {
-                args,
-                body,
-                extra: (left_pipe, right_curly).into(),
-            }
+    )(input)?;
+
+    if let Some(arg) = args.value.iter().rev().skip(1).find(|v| v.is_captures_rest) {
+        return ParseError::fatal(
+            "Only the last parameter can capture rest of values".to_string(),
+            input.slice(arg.extra.start().offset() - input.location_offset()..),
+        );
+    }
+
+    let (tail, (_left_curly, body, right_curly)) = space0_delimimited(ParseError::protect(
+        |_| "'{' expected".to_string(),
+        crate::puppet_parser::statement::parse_statement_block,
+    ))(tail)?;
+
+    Ok((
+        tail,
+        crate::puppet_lang::expression::Lambda {
+            args,
+            body,
+            extra: (left_pipe, right_curly).into(),
         },
-    )(input)
+    ))
 }
 
 fn parse_funcall(input: Span) -> IResult<crate::puppet_lang::expression::Expression<Range>> {
@@ -840,7 +848,9 @@ pub fn parse_expression(input: Span) -> IResult<crate::puppet_lang::expression::
 #[test]
 fn test_multiply() {
     assert_eq!(
-        parse_expression(Span::new("2*3")).unwrap().1,
+        parse_expression(Span::new_extra("2*3", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Expression {
             accessor: None,
             comment: vec![],
@@ -854,6 +864,8 @@ fn test_multiply() {
                                 crate::puppet_lang::expression::Integer {
                                     extra: Range::new(0, 1, 1, 0, 1, 1),
                                     value: 2,
+                                    radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                    lexeme: "2".to_string(),
                                 }
                             ),
                             extra: Range::new(0, 1, 1, 0, 1, 1)
@@ -870,6 +882,8 @@ fn test_multiply() {
                                 crate::puppet_lang::expression::Integer {
                                     extra: Range::new(2, 1, 3, 2, 1, 3),
                                     value: 3,
+                                    radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                    lexeme: "3".to_string(),
                                 }
                             ),
                             extra: Range::new(2, 1, 3, 2, 1, 3)
@@ -891,7 +905,7 @@ fn test_operators_precendence() {
     use crate::puppet_lang::expression::Term;
     use crate::puppet_lang::expression::TermVariant;
     assert_eq!(
-        parse_expression(Span::new("(1 +2) * 3* 4 - 10")).unwrap().1,
+        parse_expression(Span::new_extra("(1 +2) * 3* 4 - 10", &Default::default())).unwrap().1,
         Expression {
             accessor: None,
             comment: vec![],
@@ -915,6 +929,8 @@ fn test_operators_precendence() {
                                                 value: ExpressionVariant::Term(Term {
                                                     value: TermVariant::Integer(Integer {
                                                         value: 1,
+                                                        radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                                        lexeme: "1".to_string(),
                                                         extra: Range::new(1, 1, 2, 1, 1, 2)
                                                     }),
                                                     extra: Range::new(1, 1, 2, 1, 1, 2)
@@ -927,6 +943,8 @@ fn test_operators_precendence() {
                                                 value: ExpressionVariant::Term(Term {
                                                     value: TermVariant::Integer(Integer {
                                                         value: 2,
+                                                        radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                                        lexeme: "2".to_string(),
                                                         extra: Range::new(4, 1, 5, 4, 1, 5)
                                                     }),
                                                     extra: Range::new(4, 1, 5, 4, 1, 5)
@@ -952,6 +970,8 @@ fn test_operators_precendence() {
                                     value: ExpressionVariant::Term(Term {
                                         value: TermVariant::Integer(Integer {
                                             value: 3,
+                                            radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                            lexeme: "3".to_string(),
                                             extra: Range::new(9, 1, 10, 9, 1, 10)
                                         }),
                                         extra: Range::new(9, 1, 10, 9, 1, 10)
@@ -964,6 +984,8 @@ fn test_operators_precendence() {
                                     value: ExpressionVariant::Term(Term {
                                         value: TermVariant::Integer(Integer {
                                             value: 4,
+                                            radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                            lexeme: "4".to_string(),
                                             extra: Range::new(12, 1, 13, 12, 1, 13)
                                         }),
                                         extra: Range::new(12, 1, 13, 12, 1, 13)
@@ -982,6 +1004,8 @@ fn test_operators_precendence() {
                     value: ExpressionVariant::Term(Term {
                         value: TermVariant::Integer(Integer {
                             value: 10,
+                            radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                            lexeme: "10".to_string(),
                             extra: Range::new(16, 1, 17, 17, 1, 18)
                         }),
                         extra: Range::new(16, 1, 17, 17, 1, 18)
@@ -997,7 +1021,7 @@ fn test_operators_precendence() {
 #[test]
 fn test_function_call() {
     assert_eq!(
-        parse_funcall(Span::new("lookup('ask8s::docker::gpu_nvidia')"))
+        parse_funcall(Span::new_extra("lookup('ask8s::docker::gpu_nvidia')", &Default::default()))
             .unwrap()
             .1,
         crate::puppet_lang::expression::Expression {
@@ -1045,7 +1069,7 @@ fn test_function_call() {
 #[test]
 fn test_in_with_parens() {
     assert_eq!(
-        parse_expression(Span::new("(1 in $a)")).unwrap().1,
+        parse_expression(Span::new_extra("(1 in $a)", &Default::default())).unwrap().1,
         crate::puppet_lang::expression::Expression {
                     accessor: None,
             comment: vec![],
@@ -1064,6 +1088,8 @@ fn test_in_with_parens() {
                                             value: crate::puppet_lang::expression::TermVariant::Integer(
                                                 crate::puppet_lang::expression::Integer {
                                                     value: 1,
+                                                    radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                                    lexeme: "1".to_string(),
                                                     extra: Range::new(1,1,2, 1, 1, 2)
                                                 }
                                             ),
@@ -1110,7 +1136,7 @@ fn test_in_with_parens() {
 #[test]
 fn test_accessor() {
     assert_eq!(
-        parse_expression(Span::new("$a[ 1 ]['z']")).unwrap().1,
+        parse_expression(Span::new_extra("$a[ 1 ]['z']", &Default::default())).unwrap().1,
         crate::puppet_lang::expression::Expression {
             extra: Range::new(0, 1, 1, 11, 1, 12),
             accessor: Some(crate::puppet_lang::expression::Accessor {
@@ -1124,6 +1150,8 @@ fn test_accessor() {
                                     crate::puppet_lang::expression::Integer {
                                         extra: Range::new(4, 1, 5, 4, 1, 5),
                                         value: 1,
+                                        radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                        lexeme: "1".to_string(),
                                     }
                                 ),
                                 extra: Range::new(4, 1, 5, 4, 1, 5)
@@ -1177,3 +1205,29 @@ fn test_accessor() {
         }
     )
 }
+
+#[test]
+fn test_lambda() {
+    let (_, lambda) = parse_lambda(Span::new_extra(
+        "|String $a, Integer $b = 1, *$rest| { }",
+        &Default::default(),
+    ))
+    .unwrap();
+    let args: Vec<_> = lambda
+        .args
+        .value
+        .iter()
+        .map(|v| (v.name.as_str(), v.type_spec.is_some(), v.default.is_some(), v.is_captures_rest))
+        .collect();
+    assert_eq!(
+        args,
+        vec![
+            ("a", true, false, false),
+            ("b", true, true, false),
+            ("rest", false, false, true)
+        ]
+    );
+    assert_eq!(lambda.args.value[2].extra, Range::new(28, 1, 29, 33, 1, 34));
+
+    assert!(parse_lambda(Span::new_extra("|*$rest, $a| { }", &Default::default())).is_err());
+}
@@ -0,0 +1,450 @@
+use crate::puppet_lang::string::{
+    DoubleQuotedFragment, Escaped, Heredoc, Literal, StringExpr, StringFragment, StringVariant,
+};
+use crate::puppet_parser::{range::Range, IResult, ParseError, Span};
+use nom::branch::alt;
+use nom::bytes::complete::{is_not, tag, take, take_while, take_while1};
+use nom::character::complete::{anychar, char, line_ending, not_line_ending, space0};
+use nom::combinator::{map, opt, recognize, verify};
+use nom::sequence::{delimited, pair, preceded, tuple};
+use nom::Slice;
+
+const ALL_ESCAPES: &str = "nrtsuL$";
+
+/// Offset right after the closing tag of the last heredoc which header precedes `header` and
+/// which body starts at `line`
+fn pending_body_end(line: &Span, header: usize) -> Option<usize> {
+    let line_offset = line.location_offset();
+    line.extra
+        .heredoc_bodies
+        .borrow()
+        .range((line_offset, 0)..(line_offset, header))
+        .next_back()
+        .map(|(_, end)| *end)
+}
+
+/// Skips bodies of heredocs started on the previous line, returns input right after the last
+/// closing tag
+pub fn skip_body(input: Span) -> Option<Span> {
+    let end = pending_body_end(&input, usize::MAX)?;
+    Some(input.slice(end - input.location_offset()..))
+}
+
+fn is_escape_enabled(escapes: &Option<String>, c: char) -> bool {
+    match escapes {
+        None => false,
+        Some(_) if c == '\\' => true,
+        Some(list) if list.is_empty() => ALL_ESCAPES.contains(c),
+        Some(list) => list.contains(c),
+    }
+}
+
+fn parse_escaped<'a>(
+    input: Span<'a>,
+    escapes: &Option<String>,
+) -> IResult<'a, StringFragment<Range>> {
+    if is_escape_enabled(escapes, 'u') {
+        if let Ok(r) = crate::puppet_parser::single_quoted::parse_unicode(input) {
+            return Ok(r);
+        }
+    }
+
+    let (input, escape_tag) = tag("\\")(input)?;
+
+    if is_escape_enabled(escapes, 'L') {
+        if let Ok((input, newline)) = line_ending::<_, ParseError>(input) {
+            return Ok((
+                input,
+                StringFragment::Escaped(Escaped {
+                    data: '\n',
+                    extra: Range::from((escape_tag, newline)),
+                }),
+            ));
+        }
+    }
+
+    map(
+        verify(recognize(anychar), |data: &Span| {
+            let c = data.chars().next().unwrap();
+            c != 'u' && c != 'L' && is_escape_enabled(escapes, c)
+        }),
+        move |data: Span| {
+            StringFragment::Escaped(Escaped {
+                data: data.chars().next().unwrap(),
+                extra: Range::from((escape_tag, data)),
+            })
+        },
+    )(input)
+}
+
+fn parse_literal<'a>(
+    input: Span<'a>,
+    escapes: &Option<String>,
+    interpolated: bool,
+) -> IResult<'a, StringFragment<Range>> {
+    let is_special = |c: char| (escapes.is_some() && c == '\\') || (interpolated && c == '$');
+    map(
+        alt((
+            take_while1(move |c: char| !is_special(c)),
+            // Backslash or dollar sign which is not an escape or interpolation
+            recognize(anychar),
+        )),
+        |data: Span| {
+            StringFragment::Literal(Literal {
+                extra: Range::from((data, data)),
+                data: data.to_string(),
+            })
+        },
+    )(input)
+}
+
+fn parse_body_line<'a>(
+    mut input: Span<'a>,
+    escapes: &Option<String>,
+    interpolated: bool,
+) -> IResult<'a, Vec<DoubleQuotedFragment<Range>>> {
+    let mut list = Vec::new();
+    while !input.is_empty() {
+        if escapes.is_some() {
+            if let Ok((rest, fragment)) = parse_escaped(input, escapes) {
+                list.push(DoubleQuotedFragment::StringFragment(fragment));
+                input = rest;
+                continue;
+            }
+        }
+        if interpolated {
+            if let Ok((rest, fragment)) =
+                crate::puppet_parser::double_quoted::parse_interpolation(input)
+            {
+                list.push(fragment);
+                input = rest;
+                continue;
+            }
+        }
+        let (rest, fragment) = parse_literal(input, escapes, interpolated)?;
+        list.push(DoubleQuotedFragment::StringFragment(fragment));
+        input = rest;
+    }
+    Ok((input, list))
+}
+
+/// Checks if line is a closing tag. Returns margin width and trim flag.
+fn parse_end_tag(line: &str, heredoc_tag: &str) -> Option<(Option<usize>, bool)> {
+    let line = line.trim_end();
+    let rest = line.trim_start_matches([' ', '\t']);
+    let (margin, rest) = match rest.strip_prefix('|') {
+        Some(r) => (
+            Some(line.len() - rest.len()),
+            r.trim_start_matches([' ', '\t']),
+        ),
+        None => (None, rest),
+    };
+    let (trim_newline, rest) = match rest.strip_prefix('-') {
+        Some(r) => (true, r.trim_start_matches([' ', '\t'])),
+        None => (false, rest),
+    };
+
+    if rest == heredoc_tag {
+        Some((margin, trim_newline))
+    } else {
+        None
+    }
+}
+
+pub fn parse(input: Span) -> IResult<StringExpr<Range>> {
+    let (input, (left_tag, (heredoc_tag, interpolated), syntax, escapes, _)) = tuple((
+        tag("@("),
+        ParseError::protect(
+            |_| "Invalid heredoc tag".to_string(),
+            alt((
+                map(
+                    delimited(char('"'), is_not("\"\n"), char('"')),
+                    |v: Span| (v.trim().to_string(), true),
+                ),
+                map(is_not(":/)\n"), |v: Span| (v.trim().to_string(), false)),
+            )),
+        ),
+        opt(preceded(
+            char(':'),
+            take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.'),
+        )),
+        opt(preceded(
+            char('/'),
+            take_while(|c: char| ALL_ESCAPES.contains(c)),
+        )),
+        ParseError::protect(
+            |_| "Closing ')' of heredoc header expected".to_string(),
+            char(')'),
+        ),
+    ))(input)?;
+
+    let closing_tag_not_found = || {
+        ParseError::fatal(
+            format!("Heredoc closing tag {:?} not found", heredoc_tag),
+            left_tag,
+        )
+    };
+
+    let header_rest = input;
+    let (mut input, next_line) = match pair(space0, line_ending::<_, ParseError>)(header_rest) {
+        Ok((input, _)) => (input, None),
+        Err(_) => {
+            // Header is followed by other tokens, body starts on the next line right after
+            // bodies of preceding heredocs of the same line
+            let next_line = match pair(not_line_ending, line_ending::<_, ParseError>)(header_rest) {
+                Ok((next_line, _)) => next_line,
+                Err(_) => return closing_tag_not_found(),
+            };
+            let input = match pending_body_end(&next_line, left_tag.location_offset()) {
+                Some(end) => {
+                    let previous = next_line.slice(end - next_line.location_offset()..);
+                    match line_ending::<_, ParseError>(previous) {
+                        Ok((input, _)) => input,
+                        Err(_) => return closing_tag_not_found(),
+                    }
+                }
+                None => next_line,
+            };
+            (input, Some(next_line))
+        }
+    };
+
+    let escapes = escapes.map(|v: Span| v.to_string());
+
+    let mut lines = Vec::new();
+    let (end_tag, margin, trim_newline) = loop {
+        if input.is_empty() {
+            return closing_tag_not_found();
+        }
+        let (rest, line) = recognize(pair(not_line_ending, opt(line_ending)))(input)?;
+        if let Some((margin, trim_newline)) = parse_end_tag(&line, &heredoc_tag) {
+            let (rest, end_tag) = not_line_ending(input)?;
+            input = rest;
+            break (end_tag, margin, trim_newline);
+        }
+        lines.push(line);
+        input = rest;
+    };
+
+    let mut data = Vec::new();
+    for line in lines {
+        let width = line
+            .chars()
+            .take(margin.unwrap_or(0))
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .count();
+        let (line, _) = take::<_, _, ParseError>(width)(line)?;
+        let (_, mut fragments) = parse_body_line(line, &escapes, interpolated)?;
+        data.append(&mut fragments);
+    }
+
+    if trim_newline {
+        if let Some(DoubleQuotedFragment::StringFragment(StringFragment::Literal(literal))) =
+            data.last_mut()
+        {
+            if let Some(v) = literal.data.strip_suffix('\n') {
+                literal.data = v.strip_suffix('\r').unwrap_or(v).to_string();
+            }
+            if literal.data.is_empty() {
+                let _ = data.pop();
+            }
+        }
+    }
+
+    let input = match next_line {
+        Some(next_line) => {
+            let _ = input.extra.heredoc_bodies.borrow_mut().insert(
+                (next_line.location_offset(), left_tag.location_offset()),
+                input.location_offset(),
+            );
+            header_rest
+        }
+        None => input,
+    };
+
+    Ok((
+        input,
+        StringExpr {
+            data: StringVariant::Heredoc(Heredoc {
+                tag: heredoc_tag,
+                syntax: syntax.map(|v| v.to_string()),
+                escapes,
+                interpolated,
+                margin,
+                trim_newline,
+                data,
+            }),
+            extra: Range::from((left_tag, end_tag)),
+        },
+    ))
+}
+
+#[test]
+fn test_simple() {
+    assert_eq!(
+        parse(Span::new_extra("@(END)\nhello\nEND", &Default::default()))
+            .unwrap()
+            .1,
+        StringExpr {
+            data: StringVariant::Heredoc(Heredoc {
+                tag: "END".to_owned(),
+                syntax: None,
+                escapes: None,
+                interpolated: false,
+                margin: None,
+                trim_newline: false,
+                data: vec![DoubleQuotedFragment::StringFragment(
+                    StringFragment::Literal(Literal {
+                        data: "hello\n".to_owned(),
+                        extra: Range::new(7, 2, 1, 12, 2, 6)
+                    })
+                )]
+            }),
+            extra: Range::new(0, 1, 1, 15, 3, 3)
+        }
+    );
+}
+
+#[test]
+fn test_margin() {
+    let state = crate::puppet_parser::ParserState::default();
+    let (rest, res) = parse(Span::new_extra(
+        "@(\"END\":json/t)\n    a\\t$x\n      b\n    |- END\nrest",
+        &state,
+    ))
+    .unwrap();
+    assert_eq!(*rest, "\nrest");
+    let heredoc = match res.data {
+        StringVariant::Heredoc(v) => v,
+        _ => panic!("heredoc expected"),
+    };
+    assert_eq!(heredoc.tag, "END");
+    assert_eq!(heredoc.syntax, Some("json".to_owned()));
+    assert_eq!(heredoc.escapes, Some("t".to_owned()));
+    assert!(heredoc.interpolated);
+    assert_eq!(heredoc.margin, Some(4));
+    assert!(heredoc.trim_newline);
+    assert_eq!(
+        crate::puppet_tool::string::raw_content(&StringExpr {
+            data: StringVariant::Heredoc(heredoc),
+            extra: res.extra
+        }),
+        "a\\t\n  b"
+    );
+}
+
+#[test]
+fn test_escapes() {
+    let (_, res) = parse(Span::new_extra(
+        "@(END/L)\na\\\nb\\n\\\\\nEND\n",
+        &Default::default(),
+    ))
+    .unwrap();
+    assert_eq!(
+        crate::puppet_tool::string::constant_value(&res),
+        Some("ab\\n\\\n".to_owned())
+    );
+    let (_, res) = parse(Span::new_extra(
+        "@(END/)\n\\t\\s$x\nEND\n",
+        &Default::default(),
+    ))
+    .unwrap();
+    assert_eq!(
+        crate::puppet_tool::string::constant_value(&res),
+        Some("\t $x\n".to_owned())
+    );
+    let (_, res) = parse(Span::new_extra(
+        "@(\"END\")\n${x}\n-END\n",
+        &Default::default(),
+    ))
+    .unwrap();
+    assert_eq!(crate::puppet_tool::string::constant_value(&res), None);
+}
+
+#[test]
+fn test_errors() {
+    assert!(parse(Span::new_extra("@(END)\nhello\n", &Default::default())).is_err());
+    assert!(parse(Span::new_extra("@(END), 1\nhello\n", &Default::default())).is_err());
+}
+
+#[test]
+fn test_tokens_after_header() {
+    use crate::puppet_lang::expression::{ExpressionVariant, Term, TermVariant};
+
+    let state = crate::puppet_parser::ParserState::default();
+    let (rest, res) = crate::puppet_parser::statement::parse_statement_list(Span::new_extra(
+        "file { '/tmp/x': content => @(END), mode => '0644' }\nhello\nEND\nnotice(@(A), @(B))\na\nA\nb\n-B\n",
+        &state,
+    ))
+    .unwrap();
+    assert_eq!(*rest, "");
+    assert_eq!(res.value.len(), 2);
+
+    let state = crate::puppet_parser::ParserState::default();
+    let (rest, res) = crate::puppet_parser::expression::parse_expression(Span::new_extra(
+        "f(@(END))\nhello\nEND\nrest",
+        &state,
+    ))
+    .unwrap();
+    assert_eq!(*rest, "rest");
+    let arg = match res.value {
+        ExpressionVariant::FunctionCall(mut v) => v.args.remove(0),
+        v => panic!("function call expected, got {:?}", v),
+    };
+    let heredoc = match arg.value {
+        ExpressionVariant::Term(Term {
+            value: TermVariant::String(v),
+            ..
+        }) => v,
+        v => panic!("string expected, got {:?}", v),
+    };
+    assert_eq!(
+        crate::puppet_tool::string::constant_value(&heredoc),
+        Some("hello\n".to_owned())
+    );
+}
+
+#[test]
+fn test_state_of_input() {
+    // Offsets of headers are the same, but bodies differ in length
+    for (input, body) in [
+        (
+            "f(@(A), 1)
+first
+A
+rest",
+            "first\n",
+        ),
+        (
+            "f(@(A), 2)
+second body
+A
+rest",
+            "second body\n",
+        ),
+    ] {
+        let state = crate::puppet_parser::ParserState::default();
+        let (rest, res) =
+            crate::puppet_parser::expression::parse_expression(Span::new_extra(input, &state))
+                .unwrap();
+        assert_eq!(*rest, "rest");
+        let heredoc = match res.value {
+            crate::puppet_lang::expression::ExpressionVariant::FunctionCall(mut v) => {
+                match v.args.remove(0).value {
+                    crate::puppet_lang::expression::ExpressionVariant::Term(
+                        crate::puppet_lang::expression::Term {
+                            value: crate::puppet_lang::expression::TermVariant::String(v),
+                            ..
+                        },
+                    ) => v,
+                    v => panic!("string expected, got {:?}", v),
+                }
+            }
+            v => panic!("function call expected, got {:?}", v),
+        };
+        assert_eq!(
+            crate::puppet_tool::string::constant_value(&heredoc),
+            Some(body.to_owned())
+        );
+    }
+}
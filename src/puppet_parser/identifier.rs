@@ -100,7 +100,9 @@ pub fn identifier_with_toplevel(input: Span) -> IResult<LowerIdentifier<Range>>
 #[test]
 fn test_identifier_with_toplevel() {
     assert_eq!(
-        identifier_with_toplevel(Span::new("::asd")).unwrap().1,
+        identifier_with_toplevel(Span::new_extra("::asd", &Default::default()))
+            .unwrap()
+            .1,
         LowerIdentifier {
             name: vec!["asd".to_owned()],
             is_toplevel: true,
@@ -108,7 +110,9 @@ fn test_identifier_with_toplevel() {
         }
     );
     assert_eq!(
-        identifier_with_toplevel(Span::new("asd")).unwrap().1,
+        identifier_with_toplevel(Span::new_extra("asd", &Default::default()))
+            .unwrap()
+            .1,
         LowerIdentifier {
             name: vec!["asd".to_owned()],
             is_toplevel: false,
@@ -116,7 +120,9 @@ fn test_identifier_with_toplevel() {
         }
     );
     assert_eq!(
-        identifier_with_toplevel(Span::new("asd::def")).unwrap().1,
+        identifier_with_toplevel(Span::new_extra("asd::def", &Default::default()))
+            .unwrap()
+            .1,
         LowerIdentifier {
             name: vec!["asd".to_owned(), "def".to_owned()],
             is_toplevel: false,
@@ -3,6 +3,7 @@ pub mod builtin;
 pub mod class;
 pub mod common;
 pub mod double_quoted;
+pub mod heredoc;
 pub mod expression;
 pub mod identifier;
 pub mod range;
@@ -16,31 +17,40 @@ pub mod typing;
 
 use nom_locate::LocatedSpan;
 
-pub type Span<'a> = LocatedSpan<&'a str>;
+/// State shared by all fragments of input while it is parsed
+#[derive(Debug, Default)]
+pub struct ParserState {
+    /// Bodies of heredocs which headers are followed by other tokens on the same line. Key is
+    /// offset of the line next to the header and offset of the header, value is offset right
+    /// after the closing tag.
+    heredoc_bodies: std::cell::RefCell<std::collections::BTreeMap<(usize, usize), usize>>,
+}
+
+pub type Span<'a> = LocatedSpan<&'a str, &'a ParserState>;
 
 #[derive(Debug, PartialEq)]
-pub struct ParseError<'a> {
-    span: Span<'a>,
+pub struct ParseError {
+    location: range::Location,
     message: Option<String>,
     url: Option<String>,
 }
 
-impl<'a> ParseError<'a> {
-    pub fn new(message: String, span: Span<'a>, url: Option<String>) -> Self {
+impl ParseError {
+    pub fn new(message: String, span: Span, url: Option<String>) -> Self {
         Self {
-            span,
+            location: span.into(),
             message: Some(message),
             url,
         }
     }
 
-    pub fn protect_with_url<O, M, F>(
+    pub fn protect_with_url<'a, O, M, F>(
         mut message_generator: M,
         mut parser: F,
     ) -> impl FnMut(Span<'a>) -> IResult<O>
     where
         M: FnMut(Span<'a>) -> (String, &str) + Copy,
-        F: nom::Parser<Span<'a>, O, ParseError<'a>>,
+        F: nom::Parser<Span<'a>, O, ParseError>,
         O: Clone,
     {
         move |input: Span| {
@@ -54,13 +64,13 @@ impl<'a> ParseError<'a> {
         }
     }
 
-    pub fn protect<O, M, F>(
+    pub fn protect<'a, O, M, F>(
         mut message_generator: M,
         mut parser: F,
     ) -> impl FnMut(Span<'a>) -> IResult<O>
     where
         M: FnMut(Span<'a>) -> String + Copy,
-        F: nom::Parser<Span<'a>, O, ParseError<'a>>,
+        F: nom::Parser<Span<'a>, O, ParseError>,
         O: Clone,
     {
         move |input: Span| {
@@ -78,15 +88,15 @@ impl<'a> ParseError<'a> {
         }
     }
 
-    pub fn fatal<O>(message: String, span: Span<'a>) -> IResult<O>
+    pub fn fatal<O>(message: String, span: Span) -> IResult<O>
     where
         O: Clone,
     {
         Err(nom::Err::Failure(ParseError::new(message, span, None)))
     }
 
-    pub fn span(&self) -> &Span<'a> {
-        &self.span
+    pub fn location(&self) -> &range::Location {
+        &self.location
     }
 
     pub fn message(&self) -> &Option<String> {
@@ -98,7 +108,7 @@ impl<'a> ParseError<'a> {
     }
 }
 
-impl<'a> std::fmt::Display for ParseError<'a> {
+impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(message) = &self.message {
             write!(f, "{}", message)?
@@ -106,14 +116,14 @@ impl<'a> std::fmt::Display for ParseError<'a> {
         write!(
             f,
             " at line {} column {}",
-            self.span.location_line(),
-            self.span.get_utf8_column()
+            self.location.line(),
+            self.location.column()
         )
     }
 }
 
 // That's what makes it nom-compatible.
-impl<'a> nom::error::ParseError<Span<'a>> for ParseError<'a> {
+impl<'a> nom::error::ParseError<Span<'a>> for ParseError {
     fn from_error_kind(input: Span<'a>, kind: nom::error::ErrorKind) -> Self {
         Self::new(format!("parse error {:?}", kind), input, None)
     }
@@ -127,20 +137,18 @@ impl<'a> nom::error::ParseError<Span<'a>> for ParseError<'a> {
     }
 }
 
-impl<'a> nom::error::FromExternalError<LocatedSpan<&'a str>, std::num::ParseIntError>
-    for ParseError<'a>
-{
+impl<'a> nom::error::FromExternalError<Span<'a>, std::num::ParseIntError> for ParseError {
     fn from_external_error(
-        span: LocatedSpan<&'a str>,
+        span: Span<'a>,
         _kind: nom::error::ErrorKind,
         e: std::num::ParseIntError,
     ) -> Self {
         Self {
-            span,
+            location: span.into(),
             message: Some(format!("{}", e)),
             url: None,
         }
     }
 }
 
-pub type IResult<'a, O> = nom::IResult<Span<'a>, O, ParseError<'a>>;
+pub type IResult<'a, O> = nom::IResult<Span<'a>, O, ParseError>;
@@ -62,6 +62,14 @@ impl Range {
     pub fn end(&self) -> &Location {
         &self.end
     }
+
+    /// Zero-width range at the beginning of span, which can be empty at EOF
+    pub fn at(span: Span) -> Self {
+        Self {
+            start: span.into(),
+            end: span.into(),
+        }
+    }
 }
 
 impl<'a> From<(Span<'a>, &Range)> for Range {
@@ -238,6 +246,7 @@ impl<'a> From<&crate::puppet_lang::expression::TermVariant<Range>> for Range {
             crate::puppet_lang::expression::TermVariant::RegexpGroupID(v) => v.extra.clone(),
             crate::puppet_lang::expression::TermVariant::Sensitive(v) => v.extra.clone(),
             crate::puppet_lang::expression::TermVariant::TypeSpecitifaction(v) => v.extra.clone(),
+            crate::puppet_lang::expression::TermVariant::ResourceReference(v) => v.extra.clone(),
             crate::puppet_lang::expression::TermVariant::Regexp(v) => v.extra.clone(),
         }
     }
@@ -264,6 +273,25 @@ impl<'a> From<&crate::puppet_lang::typing::TypeSpecificationVariant<Range>> for
             crate::puppet_lang::typing::TypeSpecificationVariant::ExternalType(v) => v.extra.clone(),
             crate::puppet_lang::typing::TypeSpecificationVariant::Sensitive(v) => v.extra.clone(),
             crate::puppet_lang::typing::TypeSpecificationVariant::Tuple(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Type(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Class(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Resource(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Callable(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Iterable(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Iterator(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::NotUndef(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Scalar(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Data(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Collection(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::SemVer(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::SemVerRange(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Timespan(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Timestamp(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Binary(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Init(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Deferred(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Runtime(v) => v.extra.clone(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Object(v) => v.extra.clone(),
         }
     }
 }
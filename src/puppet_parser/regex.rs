@@ -49,28 +49,34 @@ pub fn parse(input: Span) -> IResult<crate::puppet_lang::expression::Regexp<Rang
 #[test]
 fn test() {
     assert_eq!(
-        parse(Span::new("//")).unwrap().1,
+        parse(Span::new_extra("//", &Default::default())).unwrap().1,
         crate::puppet_lang::expression::Regexp {
             data: "".to_owned(),
             extra: Range::new(0, 1, 1, 1, 1, 2)
         }
     );
     assert_eq!(
-        parse(Span::new("/aaa/")).unwrap().1,
+        parse(Span::new_extra("/aaa/", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Regexp {
             data: "aaa".to_owned(),
             extra: Range::new(0, 1, 1, 4, 1, 5)
         }
     );
     assert_eq!(
-        parse(Span::new("/\\//")).unwrap().1,
+        parse(Span::new_extra("/\\//", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Regexp {
             data: "\\/".to_owned(),
             extra: Range::new(0, 1, 1, 3, 1, 4)
         }
     );
     assert_eq!(
-        parse(Span::new("/\\d/")).unwrap().1,
+        parse(Span::new_extra("/\\d/", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Regexp {
             data: "\\d".to_owned(),
             extra: Range::new(0, 1, 1, 3, 1, 4)
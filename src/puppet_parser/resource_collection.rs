@@ -97,32 +97,78 @@ fn parse_search_expression(
 pub fn parse_resource_collection(
     input: Span,
 ) -> IResult<crate::puppet_lang::resource_collection::ResourceCollection<Range>> {
-    let parser = tuple((
+    let mut parser = tuple((
         capture_comment,
         crate::puppet_parser::typing::parse_type_specification,
-        opt(tuple((
-            space0_delimimited(tag("<|")),
-            opt(parse_search_expression),
-            space0_delimimited(tag("|>")),
+        opt(alt((
+            map(
+                tuple((
+                    space0_delimimited(tag("<<|")),
+                    opt(parse_search_expression),
+                    space0_delimimited(tag("|>>")),
+                )),
+                |(left_tag, search_expression, right_tag)| {
+                    (left_tag, search_expression, right_tag, true)
+                },
+            ),
+            map(
+                tuple((
+                    space0_delimimited(tag("<|")),
+                    opt(parse_search_expression),
+                    space0_delimimited(tag("|>")),
+                )),
+                |(left_tag, search_expression, right_tag)| {
+                    (left_tag, search_expression, right_tag, false)
+                },
+            ),
         ))),
     ));
 
-    map(
-        parser,
-        |(comment, type_specification, search_expression)| {
-            let (search_expression, end_range) = match search_expression {
-                Some((_left_tag, search_expression, right_tag)) => {
-                    (Some(search_expression), Range::from((right_tag, right_tag)))
-                }
-                None => (None, type_specification.extra.clone()),
-            };
+    let (input, (comment, type_specification, search_expression)) = parser(input)?;
 
+    let (search_expression, is_exported, end_range) = match search_expression {
+        Some((_left_tag, search_expression, right_tag, is_exported)) => (
+            search_expression,
+            is_exported,
+            Range::from((right_tag, right_tag)),
+        ),
+        None => return Ok((
+            input,
             crate::puppet_lang::resource_collection::ResourceCollection {
-                extra: Range::from((&type_specification.extra, &end_range)),
+                extra: type_specification.extra.clone(),
                 type_specification,
-                search_expression: search_expression.flatten(),
+                search_expression: None,
+                is_exported: false,
+                overrides: None,
                 comment,
-            }
+            },
+        )),
+    };
+
+    // Block of attribute overrides is allowed only after collector
+    let (input, overrides) = opt(space0_delimimited(
+        crate::puppet_parser::common::curly_brackets_delimimited(
+            false,
+            crate::puppet_parser::statement::parse_resource_attributes(true),
+        ),
+    ))(input)?;
+
+    let (overrides, end_range) = match overrides {
+        Some((_left_curly, overrides, right_curly)) => {
+            (Some(overrides), Range::from((right_curly, right_curly)))
+        }
+        None => (None, end_range),
+    };
+
+    Ok((
+        input,
+        crate::puppet_lang::resource_collection::ResourceCollection {
+            extra: Range::from((&type_specification.extra, &end_range)),
+            type_specification,
+            search_expression,
+            is_exported,
+            overrides,
+            comment,
         },
-    )(input)
+    ))
 }
@@ -107,14 +107,16 @@ pub fn parse(input: Span) -> IResult<crate::puppet_lang::string::StringExpr<Rang
 #[test]
 fn test() {
     assert_eq!(
-        parse(Span::new("''")).unwrap().1,
+        parse(Span::new_extra("''", &Default::default())).unwrap().1,
         crate::puppet_lang::string::StringExpr {
             data: crate::puppet_lang::string::StringVariant::SingleQuoted(Vec::new()),
             extra: Range::new(0, 1, 1, 1, 1, 2)
         }
     );
     assert_eq!(
-        parse(Span::new("'a'")).unwrap().1,
+        parse(Span::new_extra("'a'", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::string::StringExpr {
             data: crate::puppet_lang::string::StringVariant::SingleQuoted(vec![
                 crate::puppet_lang::string::StringFragment::Literal(crate::puppet_lang::string::Literal {
@@ -126,7 +128,9 @@ fn test() {
         }
     );
     assert_eq!(
-        parse(Span::new("'\\''")).unwrap().1,
+        parse(Span::new_extra("'\\''", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::string::StringExpr {
             data: crate::puppet_lang::string::StringVariant::SingleQuoted(vec![
                 crate::puppet_lang::string::StringFragment::Escaped(Escaped {
@@ -138,7 +142,9 @@ fn test() {
         }
     );
     assert_eq!(
-        parse(Span::new("bARE-WORD_")).unwrap().1,
+        parse(Span::new_extra("bARE-WORD_", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::string::StringExpr {
             data: crate::puppet_lang::string::StringVariant::SingleQuoted(vec![
                 crate::puppet_lang::string::StringFragment::Literal(crate::puppet_lang::string::Literal {
@@ -151,7 +157,9 @@ fn test() {
     );
 
     assert_eq!(
-        parse(Span::new("bAREWORD-")).unwrap().1,
+        parse(Span::new_extra("bAREWORD-", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::string::StringExpr {
             data: crate::puppet_lang::string::StringVariant::SingleQuoted(vec![
                 crate::puppet_lang::string::StringFragment::Literal(crate::puppet_lang::string::Literal {
@@ -162,9 +170,9 @@ fn test() {
             extra: Range::new(0, 1, 1, 7, 1, 8)
         }
     );
-    assert!(parse(Span::new("-")).is_err());
+    assert!(parse(Span::new_extra("-", &Default::default())).is_err());
 
     // Puppet bug. According to specification, it CANNOT be a bare-word
     // https://puppet.com/docs/puppet/7/lang_data_string.html#lang_data_string_bare_words
-    assert!(parse(Span::new("BEDA")).is_ok());
+    assert!(parse(Span::new_extra("BEDA", &Default::default())).is_ok());
 }
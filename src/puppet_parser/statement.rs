@@ -1,6 +1,7 @@
 use crate::puppet_lang::statement::{Statement, StatementVariant};
 use crate::puppet_lang::ExtraGetter;
 use nom::character::complete::alphanumeric1;
+use nom::bytes::complete::take;
 use nom::combinator::recognize;
 use nom::multi::many1;
 use nom::{
@@ -39,19 +40,50 @@ pub fn parse_resource_attribute_name(
     )(input)
 }
 
-fn parse_resource(input: Span) -> IResult<crate::puppet_lang::statement::Resource<Range>> {
+/// Appending with '+>' is allowed only when `allow_append` is set, i.e. in resource overrides and
+/// collectors
+pub fn parse_resource_attributes<'a>(
+    allow_append: bool,
+) -> impl FnMut(
+    Span<'a>,
+) -> IResult<
+    'a,
+    crate::puppet_lang::List<Range, crate::puppet_lang::statement::ResourceAttribute<Range>>,
+> {
+    let parse_operator = move |input: Span<'a>| {
+        if !allow_append && tag::<_, _, ParseError>("+>")(input).is_ok() {
+            return ParseError::fatal(
+                "'+>' is allowed only in resource overrides and collectors".to_string(),
+                input,
+            );
+        }
+        ParseError::protect(
+            move |_| {
+                if allow_append {
+                    "'=>' or '+>' is expected".to_string()
+                } else {
+                    "'=>' is expected".to_string()
+                }
+            },
+            alt((tag("=>"), tag("+>"))),
+        )(input)
+    };
+
     let parse_attribute = map(
         pair(
             space0_delimimited(parse_resource_attribute_name),
-            preceded(
-                ParseError::protect(|_| "'=>' is expected".to_string(), tag("=>")),
+            pair(
+                parse_operator,
                 space0_delimimited(ParseError::protect(
                     |_| "Argument value is expected".to_string(),
                     crate::puppet_parser::expression::parse_expression,
                 )),
             ),
         ),
-        crate::puppet_lang::statement::ResourceAttributeVariant::Name,
+        |(name, (op, value))| match *op {
+            "+>" => crate::puppet_lang::statement::ResourceAttributeVariant::Append((name, value)),
+            _ => crate::puppet_lang::statement::ResourceAttributeVariant::Name((name, value)),
+        },
     );
 
     let parse_attribute_group = map(
@@ -68,15 +100,16 @@ fn parse_resource(input: Span) -> IResult<crate::puppet_lang::statement::Resourc
         |(_, term)| crate::puppet_lang::statement::ResourceAttributeVariant::Group(term),
     );
 
-    let parse_attributes =
-        crate::puppet_parser::common::comma_separated_list0_with_last_comment(map(
-            pair(
-                capture_comment,
-                alt((parse_attribute, parse_attribute_group)),
-            ),
-            |(comment, value)| crate::puppet_lang::statement::ResourceAttribute { value, comment },
-        ));
+    crate::puppet_parser::common::comma_separated_list0_with_last_comment(map(
+        pair(
+            capture_comment,
+            alt((parse_attribute, parse_attribute_group)),
+        ),
+        |(comment, value)| crate::puppet_lang::statement::ResourceAttribute { value, comment },
+    ))
+}
 
+fn parse_resource(input: Span) -> IResult<crate::puppet_lang::statement::Resource<Range>> {
     let mut parser = map(
         tuple((
             space0_delimimited(alt((
@@ -84,12 +117,16 @@ fn parse_resource(input: Span) -> IResult<crate::puppet_lang::statement::Resourc
                 crate::puppet_parser::expression::parse_string_expr,
                 crate::puppet_parser::expression::parse_expression,
             ))),
-            preceded(tag(":"), space0_delimimited(parse_attributes)),
+            preceded(
+                tag(":"),
+                space0_delimimited(parse_resource_attributes(false)),
+            ),
         )),
         |(title, attributes)| {
             let last_range = match attributes.value.last() {
                 Some(v) => match &v.value {
-                    crate::puppet_lang::statement::ResourceAttributeVariant::Name((_, v)) => {
+                    crate::puppet_lang::statement::ResourceAttributeVariant::Name((_, v))
+                    | crate::puppet_lang::statement::ResourceAttributeVariant::Append((_, v)) => {
                         v.extra.clone()
                     }
                     crate::puppet_lang::statement::ResourceAttributeVariant::Group(v) => {
@@ -113,7 +150,7 @@ fn parse_resource_set(input: Span) -> IResult<crate::puppet_lang::statement::Res
     let parser = tuple((
         capture_comment,
         space0_delimimited(pair(
-            opt(tag("@")),
+            opt(alt((tag("@@"), tag("@")))),
             crate::puppet_parser::identifier::anycase_identifier_with_ns,
         )),
         space0_delimimited(crate::puppet_parser::common::curly_brackets_delimimited(
@@ -127,13 +164,18 @@ fn parse_resource_set(input: Span) -> IResult<crate::puppet_lang::statement::Res
 
     map(
         parser,
-        |(comment, (virtual_tag, name), (_left_curly, list, right_curly))| {
-            let start_range = match virtual_tag {
+        |(comment, (visibility_tag, name), (_left_curly, list, right_curly))| {
+            let start_range = match visibility_tag {
                 Some(v) => Range::from((v, v)),
                 None => name.extra.clone(),
             };
+            let visibility = match visibility_tag.as_deref() {
+                None => crate::puppet_lang::statement::ResourceVisibility::Normal,
+                Some(&"@") => crate::puppet_lang::statement::ResourceVisibility::Virtual,
+                Some(_) => crate::puppet_lang::statement::ResourceVisibility::Exported,
+            };
             crate::puppet_lang::statement::ResourceSet {
-                is_virtual: virtual_tag.is_some(),
+                visibility,
                 extra: Range::from((&start_range, right_curly)),
                 name,
                 list,
@@ -184,7 +226,11 @@ fn parse_relation(input: Span) -> IResult<crate::puppet_lang::statement::Relatio
                     ),
                     map(
                         crate::puppet_parser::resource_collection::parse_resource_collection,
-                        crate::puppet_lang::statement::RelationEltVariant::ResourceCollection,
+                        |v| {
+                            crate::puppet_lang::statement::RelationEltVariant::ResourceCollection(
+                                Box::new(v),
+                            )
+                        },
                     ),
                 ))),
             ),
@@ -201,7 +247,11 @@ fn parse_relation(input: Span) -> IResult<crate::puppet_lang::statement::Relatio
                 ),
                 map(
                     crate::puppet_parser::resource_collection::parse_resource_collection,
-                    crate::puppet_lang::statement::RelationEltVariant::ResourceCollection,
+                    |v| {
+                        crate::puppet_lang::statement::RelationEltVariant::ResourceCollection(
+                            Box::new(v),
+                        )
+                    },
                 ),
             )),
             |elt| crate::puppet_lang::statement::RelationElt {
@@ -454,11 +504,33 @@ fn parse_resource_defaults(
     )(input)
 }
 
+fn parse_resource_override(
+    input: Span,
+) -> IResult<crate::puppet_lang::statement::ResourceOverride<Range>> {
+    map(
+        pair(
+            crate::puppet_parser::term::parse_resource_reference,
+            space0_delimimited(curly_brackets_delimimited(
+                false,
+                parse_resource_attributes(true),
+            )),
+        ),
+        |(reference, (_left_curly, attributes, right_curly))| {
+            crate::puppet_lang::statement::ResourceOverride {
+                extra: Range::from((&reference.extra, right_curly)),
+                reference,
+                attributes,
+            }
+        },
+    )(input)
+}
+
 fn parse_statement_variant(input: Span) -> IResult<StatementVariant<Range>> {
     alt((
         parse_if_else,
         parse_unless,
         parse_case,
+        map(parse_resource_override, StatementVariant::ResourceOverride),
         map(parse_relation, StatementVariant::RelationList),
         map(parse_resource_defaults, StatementVariant::ResourceDefaults),
         map(
@@ -502,7 +574,326 @@ pub fn parse_statement_block(
     ))(input)
 }
 
+/// Finds where broken statement ends. That's the end of line where all brackets opened by the
+/// statement are closed (not before the failure location) or unbalanced closing bracket.
+fn skip_broken_statement<'a>(input: Span<'a>, error_location: &Span<'a>) -> IResult<'a, Span<'a>> {
+    let error_offset = error_location
+        .location_offset()
+        .saturating_sub(input.location_offset());
+
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut in_comment = false;
+    let mut end = input.len();
+    for (idx, c) in input.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false
+            } else if c == '\\' {
+                escaped = true
+            } else if c == q {
+                quote = None
+            }
+            continue;
+        }
+        if in_comment && c != '\n' {
+            continue;
+        }
+        match c {
+            '\'' | '"' => quote = Some(c),
+            '#' => in_comment = true,
+            '{' | '[' | '(' => depth += 1,
+            '}' | ']' | ')' if depth == 0 => {
+                // Closing bracket of the outer block. Consume it only if nothing else is left.
+                end = if idx == 0 { c.len_utf8() } else { idx };
+                break;
+            }
+            '}' | ']' | ')' => depth -= 1,
+            '\n' => {
+                in_comment = false;
+                if depth == 0 && idx >= error_offset {
+                    end = idx + 1;
+                    break;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    take(end)(input)
+}
+
+/// Same as parse_statement_list, but statements which failed to parse are kept as SyntaxError
+/// nodes and parsing continues from the next statement. Stops at EOF or, if not on the toplevel,
+/// before closing '}'.
+pub fn parse_statement_list_with_recovery(
+    is_toplevel: bool,
+) -> impl FnMut(Span) -> IResult<crate::puppet_lang::List<Range, Statement<Range>>> {
+    move |mut input| {
+        let mut value = Vec::new();
+        loop {
+            let (rest, comment) = capture_comment(input)?;
+            if rest.is_empty() || (!is_toplevel && rest.starts_with('}')) {
+                return Ok((
+                    rest,
+                    crate::puppet_lang::List {
+                        value,
+                        last_comment: comment,
+                    },
+                ));
+            }
+
+            match terminated(parse_statement_variant, opt(space0_delimimited(tag(";"))))(rest) {
+                Ok((tail, statement)) => {
+                    value.push(Statement {
+                        value: statement,
+                        comment,
+                    });
+                    input = tail
+                }
+                Err(err) => {
+                    let (message, url, location) = match err {
+                        nom::Err::Failure(err) => (
+                            err.message().clone().unwrap_or_default(),
+                            err.url().clone(),
+                            nom::Slice::slice(
+                                &rest,
+                                err.location().offset() - rest.location_offset()..,
+                            ),
+                        ),
+                        // Not a statement at all, messages of such errors are useless
+                        _ if is_toplevel => ("Statement is expected".to_string(), None, rest),
+                        _ => (
+                            "Closing '}' or statement is expected".to_string(),
+                            None,
+                            rest,
+                        ),
+                    };
+                    let (tail, data) = skip_broken_statement(rest, &location)?;
+                    let range = |span: Span| {
+                        if span.is_empty() {
+                            Range::at(span)
+                        } else {
+                            Range::from((span, span))
+                        }
+                    };
+                    value.push(Statement {
+                        value: StatementVariant::SyntaxError(
+                            crate::puppet_lang::statement::SyntaxError {
+                                message,
+                                url,
+                                location: range(location),
+                                data: data.to_string(),
+                                extra: range(data),
+                            },
+                        ),
+                        comment,
+                    });
+                    input = tail
+                }
+            }
+        }
+    }
+}
+
+/// Same as parse_statement_block, but with recovery from syntax errors in statements
+pub fn parse_statement_block_with_recovery(
+    input: Span,
+) -> IResult<(
+    Span,
+    crate::puppet_lang::List<Range, Statement<Range>>,
+    Span,
+)> {
+    tuple((
+        tag("{"),
+        parse_statement_list_with_recovery(false),
+        ParseError::protect(
+            |_| "Closing '}' or statement is expected".to_string(),
+            space0_delimimited(tag("}")),
+        ),
+    ))(input)
+}
+
 #[test]
 fn test_selector() {
-    assert!(parse_statement_block(Span::new("{ if $z { $a ? { default => 0, } } }")).is_ok())
+    assert!(parse_statement_block(Span::new_extra(
+        "{ if $z { $a ? { default => 0, } } }",
+        &Default::default()
+    ))
+    .is_ok())
+}
+
+#[test]
+fn test_recovery() {
+    let state = crate::puppet_parser::ParserState::default();
+    let (rest, list) = parse_statement_list_with_recovery(true)(Span::new_extra(
+        "$a = 1\nfile { '/x':\n  ensure => ,\n  mode => '0644',\n}\nif $b { $x = }\n}\n$c = 3\n",
+        &state,
+    ))
+    .unwrap();
+    assert!(rest.is_empty());
+
+    let variants: Vec<_> = list
+        .value
+        .iter()
+        .map(|v| match &v.value {
+            StatementVariant::SyntaxError(err) => {
+                Some((err.data.as_str(), err.location.start().line()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        variants,
+        vec![
+            None,
+            Some(("file { '/x':\n  ensure => ,\n  mode => '0644',\n}\n", 3)),
+            Some(("if $b { $x = }\n", 6)),
+            Some(("}", 7)),
+            None
+        ]
+    );
+
+    let state = crate::puppet_parser::ParserState::default();
+    let (rest, (_, list, _)) = parse_statement_block_with_recovery(Span::new_extra(
+        "{\n  if $a { = }\n  $b = 2\n}",
+        &state,
+    ))
+    .unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(list.value.len(), 2);
+    assert!(matches!(
+        list.value.first().unwrap().value,
+        StatementVariant::SyntaxError(_)
+    ));
+}
+
+#[test]
+fn test_append_attribute() {
+    assert!(parse_statement_list(Span::new_extra(
+        "File['/x'] { require +> Package['y'] }\nPackage <| |> { require +> File['/x'] }",
+        &Default::default(),
+    ))
+    .is_ok());
+    let err = parse_statement_list(Span::new_extra(
+        "file { '/x': require +> Package['y'] }",
+        &Default::default(),
+    ))
+    .unwrap_err();
+    match err {
+        nom::Err::Failure(err) => assert_eq!(
+            err.message().as_deref(),
+            Some("'+>' is allowed only in resource overrides and collectors")
+        ),
+        err => panic!("failure expected, got {:?}", err),
+    }
+}
+
+#[test]
+fn test_exported_resources() {
+    use crate::puppet_lang::statement::{RelationEltVariant, ResourceVisibility};
+
+    let state = crate::puppet_parser::ParserState::default();
+    let (rest, list) = parse_statement_list(Span::new_extra(
+        "@@nagios_service { 'a': ensure => present }
+Nagios_service <<| tag == 'x' |>> { notify => Service['nagios'] }
+File <| |>",
+        &state,
+    ))
+    .unwrap();
+    assert_eq!(*rest, "");
+
+    let elts: Vec<_> = list
+        .value
+        .iter()
+        .map(|statement| match &statement.value {
+            StatementVariant::RelationList(v) => {
+                assert!(v.tail.is_none());
+                assert_eq!(v.head.data.value.len(), 1);
+                v.head.data.value.first().unwrap()
+            }
+            v => panic!("relation list expected, got {:?}", v),
+        })
+        .collect();
+    assert_eq!(elts.len(), 3);
+
+    match elts[0] {
+        RelationEltVariant::ResourceSet(v) => {
+            assert_eq!(v.name.name, vec!["nagios_service".to_string()]);
+            assert_eq!(v.visibility, ResourceVisibility::Exported);
+        }
+        v => panic!("resource set expected, got {:?}", v),
+    }
+
+    match elts[1] {
+        RelationEltVariant::ResourceCollection(v) => {
+            assert!(v.is_exported);
+            assert!(v.search_expression.is_some());
+            assert_eq!(v.overrides.as_ref().unwrap().value.len(), 1);
+        }
+        v => panic!("resource collection expected, got {:?}", v),
+    }
+
+    match elts[2] {
+        RelationEltVariant::ResourceCollection(v) => {
+            assert!(!v.is_exported);
+            assert!(v.search_expression.is_none());
+            assert!(v.overrides.is_none());
+        }
+        v => panic!("resource collection expected, got {:?}", v),
+    }
+}
+
+#[test]
+fn test_resource_override() {
+    let state = crate::puppet_parser::ParserState::default();
+    let (rest, list) = parse_statement_list(Span::new_extra(
+        "File['/etc/motd'] { mode => '0600', require +> Package['motd'] }\nClass['nginx'] { port => 80 }",
+        &state,
+    ))
+    .unwrap();
+    assert_eq!(*rest, "");
+
+    let overrides: Vec<_> = list
+        .value
+        .iter()
+        .map(|statement| match &statement.value {
+            StatementVariant::ResourceOverride(v) => (
+                v.reference.name.clone(),
+                v.reference.titles.len(),
+                v.attributes.value.len(),
+                v.extra.start().line(),
+            ),
+            v => panic!("resource override expected, got {:?}", v),
+        })
+        .collect();
+    assert_eq!(
+        overrides,
+        vec![
+            (vec!["File".to_owned()], 1, 2, 1),
+            (vec!["Class".to_owned()], 1, 1, 2)
+        ]
+    );
+}
+
+#[test]
+fn test_recovery_at_eof() {
+    for input in ["class a { $x = 1", "class b { $x = ( }", "if $a {"] {
+        let state = crate::puppet_parser::ParserState::default();
+        let (rest, list) =
+            parse_statement_list_with_recovery(true)(Span::new_extra(input, &state)).unwrap();
+        assert!(rest.is_empty(), "{:?}", input);
+        let errors = crate::puppet_parser::toplevel::syntax_errors(&list.value);
+        assert_eq!(errors.len(), 1, "{:?}", input);
+        assert_eq!(errors[0].data, input);
+        assert_eq!(errors[0].message, "Unexpected EOF");
+        assert_eq!(errors[0].location.start().offset(), input.len());
+        assert_eq!(errors[0].location.end().offset(), input.len());
+        assert!(crate::puppet_parser::toplevel::parse_file(Span::new_extra(
+            input,
+            &Default::default()
+        ))
+        .is_err());
+    }
 }
@@ -1,19 +1,19 @@
 use crate::puppet_lang::expression::MapKV;
 use crate::puppet_lang::string::StringExpr;
 use crate::puppet_parser::common::{
-    capture_comment, curly_brackets_delimimited, round_parens_delimimited, space0_delimimited,
-    square_brackets_delimimited,
+    capture_comment, curly_brackets_delimimited, multispace0, round_parens_delimimited,
+    space0_delimimited, square_brackets_delimimited,
 };
 use crate::puppet_parser::expression::{parse_accessor, parse_expression};
 use crate::puppet_parser::range::Range;
 use crate::puppet_parser::{IResult, ParseError, Span};
-use nom::character::complete::{anychar, multispace0};
-use nom::combinator::{eof, map_res, peek, verify};
+use nom::character::complete::anychar;
+use nom::combinator::{consumed, eof, map_opt, map_res, peek, verify};
 use nom::sequence::{terminated, tuple};
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::digit1,
+    character::complete::{digit1, hex_digit1},
     combinator::{map, opt, recognize},
     sequence::{pair, preceded},
 };
@@ -49,11 +49,25 @@ pub fn parse_regexp_group_id(
     )(input)
 }
 
-pub fn parse_float(input: Span) -> IResult<(f32, Span)> {
-    let number = pair(digit1, pair(alt((tag("e"), tag("E"), tag("."))), digit1));
+fn parse_exponent(input: Span) -> IResult<Span> {
+    recognize(tuple((
+        alt((tag("e"), tag("E"))),
+        opt(alt((tag("-"), tag("+")))),
+        digit1,
+    )))(input)
+}
+
+pub fn parse_float(input: Span) -> IResult<(f64, Span)> {
+    let number = pair(
+        digit1,
+        alt((
+            recognize(pair(pair(tag("."), digit1), opt(parse_exponent))),
+            parse_exponent,
+        )),
+    );
     let (tail, s) = recognize(pair(opt(tag("-")), number))(input)?;
 
-    let f = match s.parse::<f32>() {
+    let f = match s.parse::<f64>() {
         Ok(v) => v,
         Err(err) => return ParseError::fatal(format!("{}", err), input),
     };
@@ -65,16 +79,38 @@ pub fn parse_float_term(input: Span) -> IResult<crate::puppet_lang::expression::
     map(parse_float, |(value, span)| {
         crate::puppet_lang::expression::Float {
             value,
+            lexeme: span.to_string(),
             extra: Range::from((span, span)),
         }
     })(input)
 }
 
-pub fn parse_integer(input: Span) -> IResult<(i64, Span)> {
-    let (tail, s) = recognize(pair(
+pub fn parse_integer(
+    input: Span,
+) -> IResult<(i64, crate::puppet_lang::expression::IntegerRadix, Span)> {
+    let hexadecimal = map(
+        preceded(alt((tag("0x"), tag("0X"))), hex_digit1),
+        |digits| {
+            (
+                crate::puppet_lang::expression::IntegerRadix::Hexadecimal,
+                digits,
+            )
+        },
+    );
+    let octal = map(preceded(tag("0"), digit1), |digits| {
+        (crate::puppet_lang::expression::IntegerRadix::Octal, digits)
+    });
+    let decimal = map(digit1, |digits| {
+        (
+            crate::puppet_lang::expression::IntegerRadix::Decimal,
+            digits,
+        )
+    });
+
+    let (tail, (s, (sign, (radix, digits)))) = consumed(pair(
         opt(tag("-")),
         terminated(
-            digit1,
+            alt((hexadecimal, octal, decimal)),
             alt((
                 map(
                     peek(verify(anychar, |c| *c != 'e' && *c != 'E' && *c != '.')),
@@ -85,18 +121,27 @@ pub fn parse_integer(input: Span) -> IResult<(i64, Span)> {
         ),
     ))(input)?;
 
-    let v = match s.parse::<i64>() {
+    let base = match radix {
+        crate::puppet_lang::expression::IntegerRadix::Decimal => 10,
+        crate::puppet_lang::expression::IntegerRadix::Octal => 8,
+        crate::puppet_lang::expression::IntegerRadix::Hexadecimal => 16,
+    };
+
+    let digits = format!("{}{}", sign.map(|v| *v.fragment()).unwrap_or(""), digits);
+    let v = match i64::from_str_radix(&digits, base) {
         Ok(v) => v,
-        Err(err) => return ParseError::fatal(format!("{}", err), input),
+        Err(err) => return ParseError::fatal(format!("Invalid number {:?}: {}", *s, err), input),
     };
 
-    Ok((tail, (v, s)))
+    Ok((tail, (v, radix, s)))
 }
 
 pub fn parse_integer_term(input: Span) -> IResult<crate::puppet_lang::expression::Integer<Range>> {
-    map(parse_integer, |(value, span)| {
+    map(parse_integer, |(value, radix, span)| {
         crate::puppet_lang::expression::Integer {
             value,
+            radix,
+            lexeme: span.to_string(),
             extra: Range::from((span, span)),
         }
     })(input)
@@ -212,6 +257,7 @@ fn parse_array(input: Span) -> IResult<crate::puppet_lang::expression::TermVaria
 
 pub fn parse_string_variant(input: Span) -> IResult<StringExpr<Range>> {
     alt((
+        crate::puppet_parser::heredoc::parse,
         crate::puppet_parser::double_quoted::parse,
         crate::puppet_parser::single_quoted::parse,
     ))(input)
@@ -273,6 +319,51 @@ pub fn parse_resource_identifier(
     alt((multi_parser, single_with_toplevel_parser))(input)
 }
 
+/// Non-builtin types, Class and Resource with arguments are resource references
+fn resource_reference_of_type(
+    v: &crate::puppet_lang::typing::TypeSpecificationVariant<Range>,
+) -> Option<crate::puppet_lang::expression::ResourceReference<Range>> {
+    match v {
+        crate::puppet_lang::typing::TypeSpecificationVariant::ExternalType(v)
+            if !v.arguments.is_empty() =>
+        {
+            Some(crate::puppet_lang::expression::ResourceReference {
+                name: v.name.clone(),
+                titles: v.arguments.clone(),
+                extra: v.extra.clone(),
+            })
+        }
+        crate::puppet_lang::typing::TypeSpecificationVariant::Class(v) if !v.titles.is_empty() => {
+            Some(crate::puppet_lang::expression::ResourceReference {
+                name: vec!["Class".to_string()],
+                titles: v.titles.clone(),
+                extra: v.extra.clone(),
+            })
+        }
+        crate::puppet_lang::typing::TypeSpecificationVariant::Resource(v) => {
+            let resource_type = v.resource_type.as_ref()?;
+            Some(crate::puppet_lang::expression::ResourceReference {
+                name: vec!["Resource".to_string()],
+                titles: std::iter::once(resource_type.as_ref())
+                    .chain(v.titles.iter())
+                    .cloned()
+                    .collect(),
+                extra: v.extra.clone(),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Parses Type['title', ...]. Any non-builtin type with arguments is a resource reference.
+pub fn parse_resource_reference(
+    input: Span,
+) -> IResult<crate::puppet_lang::expression::ResourceReference<Range>> {
+    map_opt(crate::puppet_parser::typing::parse_type_specification, |v| {
+        resource_reference_of_type(&v.data)
+    })(input)
+}
+
 pub fn parse_term(input: Span) -> IResult<crate::puppet_lang::expression::Term<Range>> {
     let parse_true = map(tag("true"), |kw| {
         crate::puppet_lang::expression::TermVariant::Boolean(
@@ -294,7 +385,12 @@ pub fn parse_term(input: Span) -> IResult<crate::puppet_lang::expression::Term<R
 
     let parse_type_specification = map(
         crate::puppet_parser::typing::parse_type_specification,
-        crate::puppet_lang::expression::TermVariant::TypeSpecitifaction,
+        |v| match resource_reference_of_type(&v.data) {
+            Some(reference) => {
+                crate::puppet_lang::expression::TermVariant::ResourceReference(reference)
+            }
+            None => crate::puppet_lang::expression::TermVariant::TypeSpecitifaction(v),
+        },
     );
 
     let parser = alt((
@@ -344,7 +440,7 @@ pub fn parse_term(input: Span) -> IResult<crate::puppet_lang::expression::Term<R
 #[test]
 fn test_array_of_types() {
     assert_eq!(
-        parse_term(Span::new("[ Class['some_class'] ]")).unwrap().1,
+        parse_term(Span::new_extra("[ Class['some_class'] ]", &Default::default())).unwrap().1,
         crate::puppet_lang::expression::Term {
             value: crate::puppet_lang::expression::TermVariant::Array(crate::puppet_lang::expression::Array {
                 value: crate::puppet_lang::List {
@@ -355,36 +451,29 @@ fn test_array_of_types() {
                             comment: vec![],
                             value: crate::puppet_lang::expression::ExpressionVariant::Term(
                                 crate::puppet_lang::expression::Term {
-                                    value: crate::puppet_lang::expression::TermVariant::TypeSpecitifaction(
-                                        crate::puppet_lang::typing::TypeSpecification {
-                                            comment: vec![],
-                                            data:
-                                            crate::puppet_lang::typing::TypeSpecificationVariant::ExternalType(
-                                                crate::puppet_lang::typing::ExternalType {
-                                                    name: vec!["Class".to_owned()],
-                                                    arguments: vec![
-                                                        crate::puppet_lang::expression::Expression {
-                                                            accessor: None,
-                                                            comment: vec![],
-                                                            value: crate::puppet_lang::expression::ExpressionVariant::Term(
-                                                                crate::puppet_lang::expression::Term {
-                                                                    value: crate::puppet_lang::expression::TermVariant::String(crate::puppet_lang::string::StringExpr {
-                                                                        data: crate::puppet_lang::string::StringVariant::SingleQuoted(vec![
-                                                                            crate::puppet_lang::string::StringFragment::Literal(crate::puppet_lang::string::Literal {
-                                                                                data: "some_class".to_owned(),
-                                                                                extra: Range::new(9, 1, 10, 18, 1, 19)
-                                                                            })
-                                                                        ]),
-                                                                        extra: Range::new(8, 1, 9, 19, 1, 20)
-                                                                    }),
-                                                                    extra: Range::new(8, 1, 9, 19, 1, 20)
-                                                                }),
+                                    value: crate::puppet_lang::expression::TermVariant::ResourceReference(
+                                        crate::puppet_lang::expression::ResourceReference {
+                                            name: vec!["Class".to_owned()],
+                                            titles: vec![
+                                                crate::puppet_lang::expression::Expression {
+                                                    accessor: None,
+                                                    comment: vec![],
+                                                    value: crate::puppet_lang::expression::ExpressionVariant::Term(
+                                                        crate::puppet_lang::expression::Term {
+                                                            value: crate::puppet_lang::expression::TermVariant::String(crate::puppet_lang::string::StringExpr {
+                                                                data: crate::puppet_lang::string::StringVariant::SingleQuoted(vec![
+                                                                    crate::puppet_lang::string::StringFragment::Literal(crate::puppet_lang::string::Literal {
+                                                                        data: "some_class".to_owned(),
+                                                                        extra: Range::new(9, 1, 10, 18, 1, 19)
+                                                                    })
+                                                                ]),
+                                                                extra: Range::new(8, 1, 9, 19, 1, 20)
+                                                            }),
                                                             extra: Range::new(8, 1, 9, 19, 1, 20)
-                                                        }
-                                                    ],
-                                                    extra: Range::new(2, 1, 3, 20, 1, 21)
+                                                        }),
+                                                    extra: Range::new(8, 1, 9, 19, 1, 20)
                                                 }
-                                            ),
+                                            ],
                                             extra: Range::new(2, 1, 3, 20, 1, 21)
                                         }
                                     ),
@@ -401,26 +490,78 @@ fn test_array_of_types() {
     )
 }
 
+#[test]
+fn test_resource_reference() {
+    let state = crate::puppet_parser::ParserState::default();
+    let reference = |input| {
+        parse_resource_reference(Span::new_extra(input, &state))
+            .map(|(rest, v)| (*rest, v.name, v.titles.len()))
+    };
+
+    assert_eq!(
+        reference("File['/a', '/b'] rest").unwrap(),
+        (" rest", vec!["File".to_owned()], 2)
+    );
+    assert_eq!(
+        reference("Foo::Bar['x']").unwrap(),
+        ("", vec!["Foo".to_owned(), "Bar".to_owned()], 1)
+    );
+    assert_eq!(
+        reference("Class['nginx']").unwrap(),
+        ("", vec!["Class".to_owned()], 1)
+    );
+    assert_eq!(
+        reference("Resource[File, '/a']").unwrap(),
+        ("", vec!["Resource".to_owned()], 2)
+    );
+    // Types without titles and builtin types are not references
+    assert!(reference("File").is_err());
+    assert!(reference("Class").is_err());
+    assert!(reference("Optional[String]").is_err());
+
+    assert!(matches!(
+        parse_term(Span::new_extra("Service['nginx']", &state))
+            .unwrap()
+            .1
+            .value,
+        crate::puppet_lang::expression::TermVariant::ResourceReference(_)
+    ));
+    assert!(matches!(
+        parse_term(Span::new_extra("Optional[String]", &state))
+            .unwrap()
+            .1
+            .value,
+        crate::puppet_lang::expression::TermVariant::TypeSpecitifaction(_)
+    ));
+}
+
 #[test]
 fn test_numbers() {
     assert_eq!(
-        parse_term(Span::new("12345")).unwrap().1,
+        parse_term(Span::new_extra("12345", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Term {
             value: crate::puppet_lang::expression::TermVariant::Integer(
                 crate::puppet_lang::expression::Integer {
                     extra: Range::new(0, 1, 1, 4, 1, 5),
-                    value: 12345
+                    value: 12345,
+                    radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                    lexeme: "12345".to_string(),
                 }
             ),
             extra: Range::new(0, 1, 1, 4, 1, 5)
         }
     );
     assert_eq!(
-        parse_term(Span::new("12345.1")).unwrap().1,
+        parse_term(Span::new_extra("12345.1", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Term {
             value: crate::puppet_lang::expression::TermVariant::Float(
                 crate::puppet_lang::expression::Float {
                     value: 12345.1,
+                    lexeme: "12345.1".to_string(),
                     extra: Range::new(0, 1, 1, 6, 1, 7)
                 }
             ),
@@ -428,23 +569,57 @@ fn test_numbers() {
         }
     );
     assert_eq!(
-        parse_term(Span::new("-12345.3")).unwrap().1,
+        parse_term(Span::new_extra("-12345.3", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Term {
             value: crate::puppet_lang::expression::TermVariant::Float(
                 crate::puppet_lang::expression::Float {
                     value: -12345.3,
+                    lexeme: "-12345.3".to_string(),
                     extra: Range::new(0, 1, 1, 7, 1, 8)
                 }
             ),
             extra: Range::new(0, 1, 1, 7, 1, 8)
         }
     );
+
+    let integer = |s| {
+        parse_integer_term(Span::new_extra(s, &Default::default()))
+            .unwrap()
+            .1
+    };
+    assert_eq!(integer("0755").value, 0o755);
+    assert_eq!(
+        integer("0755").radix,
+        crate::puppet_lang::expression::IntegerRadix::Octal
+    );
+    assert_eq!(integer("0x1F").value, 0x1f);
+    assert_eq!(integer("-0X1f").value, -0x1f);
+    assert_eq!(integer("-0X1f").lexeme, "-0X1f");
+    assert_eq!(
+        integer("0").radix,
+        crate::puppet_lang::expression::IntegerRadix::Decimal
+    );
+    assert!(parse_integer_term(Span::new_extra("089", &Default::default())).is_err());
+
+    let float = |s| {
+        parse_float_term(Span::new_extra(s, &Default::default()))
+            .unwrap()
+            .1
+    };
+    assert_eq!(float("1e3").value, 1000.0);
+    assert_eq!(float("1.5E-3").value, 0.0015);
+    assert_eq!(float("1.5E-3").lexeme, "1.5E-3");
+    assert_eq!(float("3.141592653589793").value, std::f64::consts::PI);
 }
 
 #[test]
 fn test_bool() {
     assert_eq!(
-        parse_term(Span::new("true")).unwrap().1,
+        parse_term(Span::new_extra("true", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Term {
             value: crate::puppet_lang::expression::TermVariant::Boolean(
                 crate::puppet_lang::expression::Boolean {
@@ -456,7 +631,9 @@ fn test_bool() {
         }
     );
     assert_eq!(
-        parse_term(Span::new("false")).unwrap().1,
+        parse_term(Span::new_extra("false", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Term {
             value: crate::puppet_lang::expression::TermVariant::Boolean(
                 crate::puppet_lang::expression::Boolean {
@@ -472,7 +649,9 @@ fn test_bool() {
 #[test]
 fn test_array() {
     assert_eq!(
-        parse_term(Span::new("[]")).unwrap().1,
+        parse_term(Span::new_extra("[]", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Term {
             value: crate::puppet_lang::expression::TermVariant::Array(
                 crate::puppet_lang::expression::Array {
@@ -485,7 +664,9 @@ fn test_array() {
     );
 
     assert_eq!(
-        parse_term(Span::new("[false]")).unwrap().1,
+        parse_term(Span::new_extra("[false]", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Term {
             value: crate::puppet_lang::expression::TermVariant::Array(
                 crate::puppet_lang::expression::Array {
@@ -519,7 +700,9 @@ fn test_array() {
 #[test]
 fn test_map() {
     assert_eq!(
-        parse_term(Span::new("{}")).unwrap().1,
+        parse_term(Span::new_extra("{}", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Term {
             value: crate::puppet_lang::expression::TermVariant::Map(
                 crate::puppet_lang::expression::Map {
@@ -532,7 +715,7 @@ fn test_map() {
     );
 
     assert_eq!(
-        parse_term(Span::new("{false => 1}")).unwrap().1,
+        parse_term(Span::new_extra("{false => 1}", &Default::default())).unwrap().1,
         crate::puppet_lang::expression::Term {
             value: crate::puppet_lang::expression::TermVariant::Map(
                 crate::puppet_lang::expression::Map {
@@ -563,6 +746,8 @@ fn test_map() {
                                         value: crate::puppet_lang::expression::TermVariant::Integer(
                                             crate::puppet_lang::expression::Integer {
                                                 value: 1,
+                                                radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                                lexeme: "1".to_string(),
                                                 extra: Range::new(10, 1, 11, 10, 1, 11)
                                             }
                                         ),
@@ -581,13 +766,19 @@ fn test_map() {
         }
     );
 
-    assert!(parse_term(Span::new("{'asdasd' => {}, 'a' => 'b', }")).is_ok());
+    assert!(parse_term(Span::new_extra(
+        "{'asdasd' => {}, 'a' => 'b', }",
+        &Default::default()
+    ))
+    .is_ok());
 }
 
 #[test]
 fn test_variable() {
     assert_eq!(
-        parse_variable(Span::new("$a")).unwrap().1,
+        parse_variable(Span::new_extra("$a", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Variable {
             identifier: crate::puppet_lang::identifier::LowerIdentifier {
                 name: vec!["a".to_owned()],
@@ -599,7 +790,9 @@ fn test_variable() {
         }
     );
     assert_eq!(
-        parse_variable(Span::new("$_a")).unwrap().1,
+        parse_variable(Span::new_extra("$_a", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Variable {
             identifier: crate::puppet_lang::identifier::LowerIdentifier {
                 name: vec!["_a".to_owned()],
@@ -611,7 +804,9 @@ fn test_variable() {
         }
     );
     assert_eq!(
-        parse_variable(Span::new("$::a::b")).unwrap().1,
+        parse_variable(Span::new_extra("$::a::b", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::expression::Variable {
             identifier: crate::puppet_lang::identifier::LowerIdentifier {
                 name: vec!["a".to_owned(), "b".to_owned()],
@@ -6,8 +6,9 @@ use crate::puppet_parser::{
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    combinator::{eof, map, opt},
-    sequence::{pair, preceded, terminated, tuple},
+    combinator::{map, opt},
+    sequence::{pair, preceded, tuple},
+    Slice,
 };
 use crate::puppet_lang::toplevel::{FunctionDef, Toplevel, ToplevelVariant};
 
@@ -33,7 +34,10 @@ pub fn parse_functiondef(input: Span) -> IResult<FunctionDef<Range>> {
     map(
         tuple((
             tag("function"),
-            preceded(super::common::separator1, crate::puppet_parser::class::parse_header),
+            preceded(
+                super::common::separator1,
+                crate::puppet_parser::class::parse_header(true),
+            ),
             ParseError::protect(
                 |_| "'{' or '>>' expected".to_string(),
                 pair(
@@ -44,7 +48,7 @@ pub fn parse_functiondef(input: Span) -> IResult<FunctionDef<Range>> {
                             space0_delimimited(crate::puppet_parser::typing::parse_type_specification),
                         ),
                     ))),
-                    crate::puppet_parser::statement::parse_statement_block,
+                    crate::puppet_parser::statement::parse_statement_block_with_recovery,
                 ),
             ),
         )),
@@ -74,6 +78,10 @@ pub fn parse(input: Span) -> IResult<Toplevel<Range>> {
             extra: v.extra.clone(),
             data: ToplevelVariant::Plan(v),
         }),
+        map(crate::puppet_parser::class::parse_node, |v| Toplevel {
+            extra: v.extra.clone(),
+            data: ToplevelVariant::Node(v),
+        }),
         map(parse_typedef, |v| Toplevel {
             extra: v.extra.clone(),
             data: ToplevelVariant::TypeDef(v),
@@ -85,15 +93,59 @@ pub fn parse(input: Span) -> IResult<Toplevel<Range>> {
     )))(input)
 }
 
+/// Parses whole file. Statements which failed to parse are kept in AST as SyntaxError nodes,
+/// use syntax_errors() to get them.
+pub fn parse_file_with_recovery(
+    input: Span,
+) -> IResult<crate::puppet_lang::List<Range, crate::puppet_lang::statement::Statement<Range>>> {
+    crate::puppet_parser::statement::parse_statement_list_with_recovery(true)(input)
+}
+
+/// Returns all SyntaxError nodes in the order of appearance
+pub fn syntax_errors<EXTRA>(
+    list: &[crate::puppet_lang::statement::Statement<EXTRA>],
+) -> Vec<&crate::puppet_lang::statement::SyntaxError<EXTRA>> {
+    let mut errors = Vec::new();
+    for statement in list {
+        match &statement.value {
+            crate::puppet_lang::statement::StatementVariant::SyntaxError(v) => errors.push(v),
+            crate::puppet_lang::statement::StatementVariant::Toplevel(v) => {
+                let body = match &v.data {
+                    ToplevelVariant::Class(v) => &v.body,
+                    ToplevelVariant::Definition(v) => &v.body,
+                    ToplevelVariant::Plan(v) => &v.body,
+                    ToplevelVariant::Node(v) => &v.body,
+                    ToplevelVariant::FunctionDef(v) => &v.body,
+                    ToplevelVariant::TypeDef(_) => continue,
+                };
+                errors.append(&mut syntax_errors(&body.value))
+            }
+            _ => (),
+        }
+    }
+    errors
+}
+
 pub fn parse_file(
     input: Span,
 ) -> IResult<crate::puppet_lang::List<Range, crate::puppet_lang::statement::Statement<Range>>> {
-    terminated(crate::puppet_parser::statement::parse_statement_list, eof)(input)
+    let (rest, list) = parse_file_with_recovery(input)?;
+
+    if let Some(err) = syntax_errors(&list.value).first() {
+        let location = input.slice(err.location.start().offset() - input.location_offset()..);
+        return Err(nom::Err::Failure(ParseError::new(
+            err.message.clone(),
+            location,
+            err.url.clone(),
+        )));
+    }
+
+    Ok((rest, list))
 }
 
 #[test]
 fn test_toplevel() {
-    assert!(crate::puppet_parser::statement::parse_statement_list(Span::new(
+    assert!(crate::puppet_parser::statement::parse_statement_list(Span::new_extra(
         "# @summary Install and enroll client to freeipa cluster
 #
 # A description of what this class does
@@ -101,16 +153,29 @@ fn test_toplevel() {
 # @example
 #   include freeipa::install::client
 class freeipa::install::client {
-}"
+}",
+        &Default::default(),
     ))
     .is_ok())
 }
 
 #[test]
 fn test_function() {
-    assert!(parse(Span::new("function abc::def () {}")).is_ok());
+    assert!(parse(Span::new_extra(
+        "function abc::def () {}",
+        &Default::default()
+    ))
+    .is_ok());
 
-    assert!(parse(Span::new("function abc::def ($a, $b) {}")).is_ok());
+    assert!(parse(Span::new_extra(
+        "function abc::def ($a, $b) {}",
+        &Default::default()
+    ))
+    .is_ok());
 
-    assert!(parse(Span::new("function abc::def ($a, $b) >> String {}")).is_ok());
+    assert!(parse(Span::new_extra(
+        "function abc::def ($a, $b) >> String {}",
+        &Default::default()
+    ))
+    .is_ok());
 }
@@ -1,18 +1,30 @@
-use crate::puppet_parser::{common::capture_comment, range::Range, IResult, ParseError, Span};
+use crate::puppet_parser::{
+    common::{capture_comment, multispace0},
+    range::Range,
+    IResult, ParseError, Span,
+};
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::multispace0,
-    combinator::{map, opt, value},
+    character::complete::satisfy,
+    combinator::{map, not, opt, value},
     multi::separated_list0,
     sequence::{pair, preceded, terminated, tuple},
     Parser,
 };
 use crate::puppet_lang::typing::{ExternalType, NotUndefStructKey, OptionalStructKey};
 
+/// Matches type name only if it is not a prefix of a longer name, e.g. Data vs Database
+fn type_name<'a>(name: &'static str) -> impl FnMut(Span<'a>) -> IResult<Span<'a>> {
+    terminated(
+        tag(name),
+        not(satisfy(|c: char| c.is_alphanumeric() || c == '_' || c == ':')),
+    )
+}
+
 pub fn parse_or_default<'a, O, F>(parser: F) -> impl FnMut(Span<'a>) -> IResult<Option<O>>
 where
-    F: Parser<Span<'a>, O, ParseError<'a>>,
+    F: Parser<Span<'a>, O, ParseError>,
     O: Clone,
 {
     alt((map(parser, |v: O| Some(v)), value(None, tag("default"))))
@@ -20,7 +32,7 @@ where
 
 fn parse_min_max<'a, O, F>(parser: F) -> impl FnMut(Span<'a>) -> IResult<(Option<O>, Option<O>)>
 where
-    F: Parser<Span<'a>, O, ParseError<'a>> + Copy,
+    F: Parser<Span<'a>, O, ParseError> + Copy,
     O: Clone,
 {
     move |input| {
@@ -42,12 +54,12 @@ fn parse_min_max_args<'a, O, F>(
     parser: F,
 ) -> impl FnMut(Span<'a>) -> IResult<(Option<O>, Option<O>, Range, Range)>
 where
-    F: Parser<Span<'a>, O, ParseError<'a>> + Copy,
+    F: Parser<Span<'a>, O, ParseError> + Copy,
     O: Clone,
 {
     map(
         pair(
-            tag(keyword),
+            type_name(keyword),
             preceded(
                 multispace0,
                 opt(crate::puppet_parser::common::square_brackets_delimimited(
@@ -122,7 +134,7 @@ fn parse_array(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecifica
         )),
     );
 
-    let (input, keyword) = tag("Array")(input)?;
+    let (input, keyword) = type_name("Array")(input)?;
 
     let parser = map(
         preceded(
@@ -164,7 +176,7 @@ fn parse_hash(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificat
         )),
     ));
 
-    let (input, keyword) = tag("Hash")(input)?;
+    let (input, keyword) = type_name("Hash")(input)?;
 
     let parser = map(
         opt(preceded(
@@ -196,7 +208,7 @@ fn parse_hash(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificat
 }
 
 fn parse_optional(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
-    let (input, keyword) = tag("Optional")(input)?;
+    let (input, keyword) = type_name("Optional")(input)?;
 
     let parser = alt((
         map(
@@ -228,7 +240,7 @@ fn parse_optional(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecif
 }
 
 fn parse_sensitive(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
-    let (input, keyword) = tag("Sensitive")(input)?;
+    let (input, keyword) = type_name("Sensitive")(input)?;
 
     let parser = alt((
         map(
@@ -322,7 +334,7 @@ fn parse_struct(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecific
 
     let parser = map(
         pair(
-            tag("Struct"),
+            type_name("Struct"),
             preceded(
                 multispace0,
                 crate::puppet_parser::common::square_brackets_delimimited(
@@ -364,7 +376,7 @@ fn parse_struct(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecific
 }
 
 fn parse_tuple(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
-    let (input, tag_kw) = tag("Tuple")(input)?;
+    let (input, tag_kw) = type_name("Tuple")(input)?;
 
     let parser = preceded(
         multispace0,
@@ -399,6 +411,364 @@ fn parse_tuple(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecifica
     map(parser, crate::puppet_lang::typing::TypeSpecificationVariant::Tuple)(input)
 }
 
+fn parse_type_arg<'a>(
+    keyword: &'static str,
+) -> impl FnMut(Span<'a>) -> IResult<(Option<crate::puppet_lang::typing::TypeSpecification<Range>>, Range)>
+{
+    map(
+        pair(
+            type_name(keyword),
+            preceded(
+                multispace0,
+                opt(crate::puppet_parser::common::square_brackets_delimimited(
+                    true,
+                    parse_type_specification,
+                )),
+            ),
+        ),
+        |(kw, args)| match args {
+            Some((_left_bracket, inner, right_bracket)) => {
+                (Some(inner), Range::from((kw, right_bracket)))
+            }
+            None => (None, Range::from((kw, kw))),
+        },
+    )
+}
+
+fn parse_expression_args<'a>(
+    keyword: &'static str,
+) -> impl FnMut(Span<'a>) -> IResult<(Vec<crate::puppet_lang::expression::Expression<Range>>, Range)>
+{
+    map(
+        pair(
+            type_name(keyword),
+            opt(crate::puppet_parser::common::square_brackets_comma_separated0(
+                false,
+                crate::puppet_parser::expression::parse_expression,
+            )),
+        ),
+        |(kw, args)| match args {
+            Some((_left_bracket, args, right_bracket)) => (args, Range::from((kw, right_bracket))),
+            None => (Vec::new(), Range::from((kw, kw))),
+        },
+    )
+}
+
+fn parse_type(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    map(parse_type_arg("Type"), |(inner, extra)| {
+        crate::puppet_lang::typing::TypeSpecificationVariant::Type(crate::puppet_lang::typing::TypeType {
+            inner: inner.map(Box::new),
+            extra,
+        })
+    })(input)
+}
+
+fn parse_iterable(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    map(parse_type_arg("Iterable"), |(inner, extra)| {
+        crate::puppet_lang::typing::TypeSpecificationVariant::Iterable(
+            crate::puppet_lang::typing::TypeIterable {
+                inner: inner.map(Box::new),
+                extra,
+            },
+        )
+    })(input)
+}
+
+fn parse_iterator(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    map(parse_type_arg("Iterator"), |(inner, extra)| {
+        crate::puppet_lang::typing::TypeSpecificationVariant::Iterator(
+            crate::puppet_lang::typing::TypeIterator {
+                inner: inner.map(Box::new),
+                extra,
+            },
+        )
+    })(input)
+}
+
+fn parse_class(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    map(parse_expression_args("Class"), |(titles, extra)| {
+        crate::puppet_lang::typing::TypeSpecificationVariant::Class(crate::puppet_lang::typing::TypeClass {
+            titles,
+            extra,
+        })
+    })(input)
+}
+
+fn parse_resource(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    map(parse_expression_args("Resource"), |(mut titles, extra)| {
+        let resource_type = if titles.is_empty() {
+            None
+        } else {
+            Some(Box::new(titles.remove(0)))
+        };
+        crate::puppet_lang::typing::TypeSpecificationVariant::Resource(
+            crate::puppet_lang::typing::TypeResource {
+                resource_type,
+                titles,
+                extra,
+            },
+        )
+    })(input)
+}
+
+fn parse_semver(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    map(parse_expression_args("SemVer"), |(ranges, extra)| {
+        crate::puppet_lang::typing::TypeSpecificationVariant::SemVer(crate::puppet_lang::typing::TypeSemVer {
+            ranges,
+            extra,
+        })
+    })(input)
+}
+
+fn parse_init(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    let (input, keyword) = type_name("Init")(input)?;
+
+    let parser = opt(crate::puppet_parser::common::square_brackets_delimimited(
+        true,
+        pair(
+            parse_type_specification,
+            opt(preceded(
+                crate::puppet_parser::common::comma_separator,
+                terminated(
+                    separated_list0(
+                        crate::puppet_parser::common::comma_separator,
+                        crate::puppet_parser::expression::parse_expression,
+                    ),
+                    opt(crate::puppet_parser::common::comma_separator),
+                ),
+            )),
+        ),
+    ));
+
+    map(parser, move |args| {
+        let v = match args {
+            None => crate::puppet_lang::typing::TypeInit {
+                inner: None,
+                arguments: Vec::new(),
+                extra: Range::from((keyword, keyword)),
+            },
+            Some((_left_bracket, (inner, arguments), right_bracket)) => {
+                crate::puppet_lang::typing::TypeInit {
+                    inner: Some(Box::new(inner)),
+                    arguments: arguments.unwrap_or_default(),
+                    extra: Range::from((keyword, right_bracket)),
+                }
+            }
+        };
+        crate::puppet_lang::typing::TypeSpecificationVariant::Init(v)
+    })(input)
+}
+
+fn parse_runtime(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    map(parse_expression_args("Runtime"), |(arguments, extra)| {
+        crate::puppet_lang::typing::TypeSpecificationVariant::Runtime(
+            crate::puppet_lang::typing::TypeRuntime { arguments, extra },
+        )
+    })(input)
+}
+
+fn parse_object(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    map(parse_expression_args("Object"), |(arguments, extra)| {
+        crate::puppet_lang::typing::TypeSpecificationVariant::Object(
+            crate::puppet_lang::typing::TypeObject { arguments, extra },
+        )
+    })(input)
+}
+
+fn parse_not_undef(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    let (input, keyword) = type_name("NotUndef")(input)?;
+
+    let parser = opt(preceded(
+        multispace0,
+        crate::puppet_parser::common::square_brackets_delimimited(
+            true,
+            alt((
+                map(parse_type_specification, |v| {
+                    crate::puppet_lang::typing::TypeNotUndefVariant::TypeSpecification(Box::new(v))
+                }),
+                map(crate::puppet_parser::term::parse_term, |v| {
+                    crate::puppet_lang::typing::TypeNotUndefVariant::Term(Box::new(v))
+                }),
+            )),
+        ),
+    ));
+
+    map(parser, move |args| {
+        let v = match args {
+            None => crate::puppet_lang::typing::TypeNotUndef {
+                value: None,
+                extra: Range::from((keyword, keyword)),
+            },
+            Some((_left_bracket, value, right_bracket)) => crate::puppet_lang::typing::TypeNotUndef {
+                value: Some(value),
+                extra: Range::from((keyword, right_bracket)),
+            },
+        };
+        crate::puppet_lang::typing::TypeSpecificationVariant::NotUndef(v)
+    })(input)
+}
+
+fn parse_collection(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    map(
+        parse_min_max_args("Collection", crate::puppet_parser::term::parse_usize_term),
+        |(min, max, start_range, end_range)| {
+            crate::puppet_lang::typing::TypeSpecificationVariant::Collection(
+                crate::puppet_lang::typing::TypeCollection {
+                    min,
+                    max,
+                    extra: Range::from((&start_range, &end_range)),
+                },
+            )
+        },
+    )(input)
+}
+
+/// Bound of Timespan or Timestamp, a string or a number. Keyword 'default' is not a bound.
+fn parse_time_bound(input: Span) -> IResult<Box<crate::puppet_lang::expression::Term<Range>>> {
+    map(
+        preceded(not(type_name("default")), crate::puppet_parser::term::parse_term),
+        Box::new,
+    )(input)
+}
+
+fn parse_timespan(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    map(
+        parse_min_max_args("Timespan", parse_time_bound),
+        |(min, max, start_range, end_range)| {
+            crate::puppet_lang::typing::TypeSpecificationVariant::Timespan(
+                crate::puppet_lang::typing::TypeTimespan {
+                    min,
+                    max,
+                    extra: Range::from((&start_range, &end_range)),
+                },
+            )
+        },
+    )(input)
+}
+
+fn parse_timestamp(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    map(
+        parse_min_max_args("Timestamp", parse_time_bound),
+        |(min, max, start_range, end_range)| {
+            crate::puppet_lang::typing::TypeSpecificationVariant::Timestamp(
+                crate::puppet_lang::typing::TypeTimestamp {
+                    min,
+                    max,
+                    extra: Range::from((&start_range, &end_range)),
+                },
+            )
+        },
+    )(input)
+}
+
+#[derive(Clone)]
+enum CallableArg {
+    Type(Box<crate::puppet_lang::typing::TypeSpecification<Range>>),
+    Bound(Option<crate::puppet_lang::expression::Usize<Range>>),
+}
+
+type CallableArgs = (
+    Vec<crate::puppet_lang::typing::TypeSpecification<Range>>,
+    Option<crate::puppet_lang::expression::Usize<Range>>,
+    Option<crate::puppet_lang::expression::Usize<Range>>,
+    Option<Box<crate::puppet_lang::typing::TypeSpecification<Range>>>,
+);
+
+/// Parameter types, then optional min and max count, then optional block type
+fn parse_callable_args(input: Span) -> IResult<CallableArgs> {
+    let (tail, list) = terminated(
+        separated_list0(
+            crate::puppet_parser::common::comma_separator,
+            alt((
+                map(parse_type_specification, |v| CallableArg::Type(Box::new(v))),
+                map(
+                    parse_or_default(crate::puppet_parser::term::parse_usize_term),
+                    CallableArg::Bound,
+                ),
+            )),
+        ),
+        opt(crate::puppet_parser::common::comma_separator),
+    )(input)?;
+
+    let mut params = Vec::new();
+    let mut bounds = Vec::new();
+    let mut block = None;
+    for arg in list {
+        match arg {
+            CallableArg::Type(v) if bounds.is_empty() => params.push(*v),
+            CallableArg::Bound(v) if block.is_none() && bounds.len() < 2 => bounds.push(v),
+            CallableArg::Type(v) if block.is_none() => block = Some(v),
+            _ => {
+                return ParseError::fatal(
+                    "Callable arguments are: parameter types, min count, max count, block type"
+                        .to_owned(),
+                    input,
+                )
+            }
+        }
+    }
+
+    let mut bounds = bounds.into_iter();
+    let min = bounds.next().flatten();
+    let max = bounds.next().flatten();
+
+    Ok((tail, (params, min, max, block)))
+}
+
+fn parse_callable(input: Span) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
+    let (input, keyword) = type_name("Callable")(input)?;
+
+    let with_return_type = map(
+        crate::puppet_parser::common::square_brackets_delimimited(
+            true,
+            pair(
+                crate::puppet_parser::common::square_brackets_delimimited(true, parse_callable_args),
+                preceded(
+                    crate::puppet_parser::common::comma_separator,
+                    parse_type_specification,
+                ),
+            ),
+        ),
+        |(_left_bracket, ((_, args, _), return_type), right_bracket)| {
+            (args, Some(Box::new(return_type)), right_bracket)
+        },
+    );
+
+    let without_return_type = map(
+        crate::puppet_parser::common::square_brackets_delimimited(true, parse_callable_args),
+        |(_left_bracket, args, right_bracket)| (args, None, right_bracket),
+    );
+
+    let parser = opt(preceded(
+        multispace0,
+        alt((with_return_type, without_return_type)),
+    ));
+
+    map(parser, move |args| {
+        let v = match args {
+            None => crate::puppet_lang::typing::TypeCallable {
+                params: Vec::new(),
+                min: None,
+                max: None,
+                block: None,
+                return_type: None,
+                extra: Range::from((keyword, keyword)),
+            },
+            Some(((params, min, max, block), return_type, right_bracket)) => {
+                crate::puppet_lang::typing::TypeCallable {
+                    params,
+                    min,
+                    max,
+                    block,
+                    return_type,
+                    extra: Range::from((keyword, right_bracket)),
+                }
+            }
+        };
+        crate::puppet_lang::typing::TypeSpecificationVariant::Callable(v)
+    })(input)
+}
+
 fn parse_external_type(
     input: Span,
 ) -> IResult<crate::puppet_lang::typing::TypeSpecificationVariant<Range>> {
@@ -406,10 +776,8 @@ fn parse_external_type(
         crate::puppet_parser::identifier::camelcase_identifier_with_ns,
         opt(crate::puppet_parser::common::square_brackets_comma_separated0(
             false,
-            alt((
-                crate::puppet_parser::expression::parse_expression,
-                // TODO parse values like Class[some::class::name]
-            )),
+            // Values like Class[some::class::name] are parsed as identifiers
+            crate::puppet_parser::expression::parse_expression,
         )),
     );
 
@@ -436,7 +804,7 @@ pub fn parse_type_specification(
 ) -> IResult<crate::puppet_lang::typing::TypeSpecification<Range>> {
     let parse_variant = map(
         pair(
-            tag("Variant"),
+            type_name("Variant"),
             preceded(
                 multispace0,
                 crate::puppet_parser::common::square_brackets_comma_separated1(true, parse_type_specification),
@@ -452,7 +820,7 @@ pub fn parse_type_specification(
 
     let parse_enum = map(
         pair(
-            tag("Enum"),
+            type_name("Enum"),
             preceded(
                 multispace0,
                 crate::puppet_parser::common::square_brackets_comma_separated1(true, crate::puppet_parser::term::parse_term),
@@ -468,7 +836,7 @@ pub fn parse_type_specification(
 
     let parse_pattern = map(
         pair(
-            tag("Pattern"),
+            type_name("Pattern"),
             preceded(
                 multispace0,
                 crate::puppet_parser::common::square_brackets_comma_separated1(true, crate::puppet_parser::regex::parse),
@@ -484,7 +852,7 @@ pub fn parse_type_specification(
 
     let parse_regex = map(
         pair(
-            tag("Regex"),
+            type_name("Regex"),
             preceded(
                 multispace0,
                 crate::puppet_parser::common::square_brackets_delimimited(true, crate::puppet_parser::regex::parse),
@@ -498,30 +866,60 @@ pub fn parse_type_specification(
         },
     );
 
-    let parse_numeric = map(tag("Numeric"), |kw| {
+    let parse_numeric = map(type_name("Numeric"), |kw| {
         crate::puppet_lang::typing::TypeSpecificationVariant::Numeric(crate::puppet_lang::typing::Numeric {
             extra: (kw, kw).into(),
         })
     });
 
-    let parse_boolean = map(tag("Boolean"), |kw| {
+    let parse_boolean = map(type_name("Boolean"), |kw| {
         crate::puppet_lang::typing::TypeSpecificationVariant::Boolean(crate::puppet_lang::typing::Boolean {
             extra: (kw, kw).into(),
         })
     });
 
-    let parse_undef = map(tag("Undef"), |kw| {
+    let parse_undef = map(type_name("Undef"), |kw| {
         crate::puppet_lang::typing::TypeSpecificationVariant::Undef(crate::puppet_lang::typing::Undef {
             extra: (kw, kw).into(),
         })
     });
 
-    let parse_any = map(tag("Any"), |kw| {
+    let parse_any = map(type_name("Any"), |kw| {
         crate::puppet_lang::typing::TypeSpecificationVariant::Any(crate::puppet_lang::typing::Any {
             extra: (kw, kw).into(),
         })
     });
 
+    let parse_scalar = map(type_name("Scalar"), |kw| {
+        crate::puppet_lang::typing::TypeSpecificationVariant::Scalar(crate::puppet_lang::typing::Scalar {
+            extra: (kw, kw).into(),
+        })
+    });
+
+    let parse_data = map(type_name("Data"), |kw| {
+        crate::puppet_lang::typing::TypeSpecificationVariant::Data(crate::puppet_lang::typing::Data {
+            extra: (kw, kw).into(),
+        })
+    });
+
+    let parse_semver_range = map(type_name("SemVerRange"), |kw| {
+        crate::puppet_lang::typing::TypeSpecificationVariant::SemVerRange(crate::puppet_lang::typing::SemVerRange {
+            extra: (kw, kw).into(),
+        })
+    });
+
+    let parse_binary = map(type_name("Binary"), |kw| {
+        crate::puppet_lang::typing::TypeSpecificationVariant::Binary(crate::puppet_lang::typing::Binary {
+            extra: (kw, kw).into(),
+        })
+    });
+
+    let parse_deferred = map(type_name("Deferred"), |kw| {
+        crate::puppet_lang::typing::TypeSpecificationVariant::Deferred(crate::puppet_lang::typing::Deferred {
+            extra: (kw, kw).into(),
+        })
+    });
+
     let parser = alt((
         parse_integer,
         parse_float,
@@ -540,6 +938,27 @@ pub fn parse_type_specification(
         parse_regex,
         parse_undef,
         parse_any,
+        alt((
+            parse_type,
+            parse_class,
+            parse_resource,
+            parse_callable,
+            parse_iterable,
+            parse_iterator,
+            parse_not_undef,
+            parse_scalar,
+            parse_data,
+            parse_collection,
+            parse_semver_range,
+            parse_semver,
+            parse_timespan,
+            parse_timestamp,
+            parse_binary,
+            parse_init,
+            parse_deferred,
+            parse_runtime,
+            parse_object,
+        )),
         parse_external_type,
     ));
 
@@ -555,7 +974,7 @@ pub fn parse_type_specification(
 #[test]
 fn test_float() {
     assert_eq!(
-        parse_float(Span::new("Float")).unwrap().1,
+        parse_float(Span::new_extra("Float", &Default::default())).unwrap().1,
         crate::puppet_lang::typing::TypeSpecificationVariant::Float(crate::puppet_lang::typing::TypeFloat {
             min: None,
             max: None,
@@ -563,10 +982,11 @@ fn test_float() {
         })
     );
     assert_eq!(
-        parse_float(Span::new("Float[ 100.0 ]")).unwrap().1,
+        parse_float(Span::new_extra("Float[ 100.0 ]", &Default::default())).unwrap().1,
         crate::puppet_lang::typing::TypeSpecificationVariant::Float(crate::puppet_lang::typing::TypeFloat {
             min: Some(crate::puppet_lang::expression::Float {
                 value: 100.0,
+                lexeme: "100.0".to_string(),
                 extra: Range::new(7, 1, 8, 11, 1, 12)
             }),
             max: None,
@@ -574,27 +994,29 @@ fn test_float() {
         })
     );
     assert_eq!(
-        parse_float(Span::new("Float[ 100.0, 200.0 ]")).unwrap().1,
+        parse_float(Span::new_extra("Float[ 100.0, 200.0 ]", &Default::default())).unwrap().1,
         crate::puppet_lang::typing::TypeSpecificationVariant::Float(crate::puppet_lang::typing::TypeFloat {
             min: Some(crate::puppet_lang::expression::Float {
                 value: 100.0,
+                lexeme: "100.0".to_string(),
                 extra: Range::new(7, 1, 8, 11, 1, 12)
             }),
             max: Some(crate::puppet_lang::expression::Float {
                 value: 200.0,
+                lexeme: "200.0".to_string(),
                 extra: Range::new(14, 1, 15, 18, 1, 19)
             }),
             extra: Range::new(0, 1, 1, 20, 1, 21)
         })
     );
-    assert!(parse_float(Span::new("Float[ 100,  1000 ]")).is_ok());
-    assert!(parse_float(Span::new("Float[]")).is_ok())
+    assert!(parse_float(Span::new_extra("Float[ 100,  1000 ]", &Default::default())).is_ok());
+    assert!(parse_float(Span::new_extra("Float[]", &Default::default())).is_ok())
 }
 
 #[test]
 fn test_array() {
     assert_eq!(
-        parse_array(Span::new("Array")).unwrap().1,
+        parse_array(Span::new_extra("Array", &Default::default())).unwrap().1,
         crate::puppet_lang::typing::TypeSpecificationVariant::Array(crate::puppet_lang::typing::TypeArray {
             extra: Range::new(0, 1, 1, 4, 1, 5),
             inner: None,
@@ -603,7 +1025,7 @@ fn test_array() {
         })
     );
     assert_eq!(
-        parse_array(Span::new("Array [String[ 1,2], 10 ]"))
+        parse_array(Span::new_extra("Array [String[ 1,2], 10 ]", &Default::default()))
             .unwrap()
             .1,
         crate::puppet_lang::typing::TypeSpecificationVariant::Array(crate::puppet_lang::typing::TypeArray {
@@ -637,7 +1059,7 @@ fn test_array() {
 #[test]
 fn test_hash() {
     assert_eq!(
-        parse_hash(Span::new("Hash [String[1,2 ], Boolean]"))
+        parse_hash(Span::new_extra("Hash [String[1,2 ], Boolean]", &Default::default()))
             .unwrap()
             .1,
         crate::puppet_lang::typing::TypeSpecificationVariant::Hash(crate::puppet_lang::typing::TypeHash {
@@ -672,13 +1094,17 @@ fn test_hash() {
             max: None
         })
     );
-    assert!(parse_hash(Span::new("Hash[String, Hash[ String, String]]]")).is_ok())
+    assert!(parse_hash(Span::new_extra(
+        "Hash[String, Hash[ String, String]]]",
+        &Default::default()
+    ))
+    .is_ok())
 }
 
 #[test]
 fn test_optional() {
     assert_eq!(
-        parse_optional(Span::new("Optional [String[1,2 ] ]"))
+        parse_optional(Span::new_extra("Optional [String[1,2 ] ]", &Default::default()))
             .unwrap()
             .1,
         crate::puppet_lang::typing::TypeSpecificationVariant::Optional(
@@ -711,7 +1137,7 @@ fn test_optional() {
 #[test]
 fn test_struct() {
     assert_eq!(
-        parse_struct(Span::new("Struct [{some_key => Boolean } ]"))
+        parse_struct(Span::new_extra("Struct [{some_key => Boolean } ]", &Default::default()))
             .unwrap()
             .1,
         crate::puppet_lang::typing::TypeSpecificationVariant::Struct(crate::puppet_lang::typing::TypeStruct {
@@ -753,7 +1179,7 @@ fn test_struct() {
 #[test]
 fn test_tuple() {
     assert_eq!(
-        parse_tuple(Span::new("Tuple [Integer[1,2], 10, 100 ]"))
+        parse_tuple(Span::new_extra("Tuple [Integer[1,2], 10, 100 ]", &Default::default()))
             .unwrap()
             .1,
         crate::puppet_lang::typing::TypeSpecificationVariant::Tuple(crate::puppet_lang::typing::TypeTuple {
@@ -764,10 +1190,14 @@ fn test_tuple() {
                     crate::puppet_lang::typing::TypeInteger {
                         min: Some(crate::puppet_lang::expression::Integer {
                             value: 1,
+                            radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                            lexeme: "1".to_string(),
                             extra: Range::new(15, 1, 16, 15, 1, 16)
                         }),
                         max: Some(crate::puppet_lang::expression::Integer {
                             value: 2,
+                            radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                            lexeme: "2".to_string(),
                             extra: Range::new(17, 1, 18, 17, 1, 18)
                         }),
                         extra: Range::new(7, 1, 8, 18, 1, 19)
@@ -786,7 +1216,7 @@ fn test_tuple() {
         })
     );
     assert_eq!(
-        parse_tuple(Span::new("Tuple [Integer[1,2], Integer[3,4] ]"))
+        parse_tuple(Span::new_extra("Tuple [Integer[1,2], Integer[3,4] ]", &Default::default()))
             .unwrap()
             .1,
         crate::puppet_lang::typing::TypeSpecificationVariant::Tuple(crate::puppet_lang::typing::TypeTuple {
@@ -798,10 +1228,14 @@ fn test_tuple() {
                         crate::puppet_lang::typing::TypeInteger {
                             min: Some(crate::puppet_lang::expression::Integer {
                                 value: 1,
+                                radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                lexeme: "1".to_string(),
                                 extra: Range::new(15, 1, 16, 15, 1, 16)
                             }),
                             max: Some(crate::puppet_lang::expression::Integer {
                                 value: 2,
+                                radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                lexeme: "2".to_string(),
                                 extra: Range::new(17, 1, 18, 17, 1, 18)
                             }),
                             extra: Range::new(7, 1, 8, 18, 1, 19)
@@ -815,10 +1249,14 @@ fn test_tuple() {
                         crate::puppet_lang::typing::TypeInteger {
                             min: Some(crate::puppet_lang::expression::Integer {
                                 value: 3,
+                                radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                lexeme: "3".to_string(),
                                 extra: Range::new(29, 1, 30, 29, 1, 30)
                             }),
                             max: Some(crate::puppet_lang::expression::Integer {
                                 value: 4,
+                                radix: crate::puppet_lang::expression::IntegerRadix::Decimal,
+                                lexeme: "4".to_string(),
                                 extra: Range::new(31, 1, 32, 31, 1, 32)
                             }),
                             extra: Range::new(21, 1, 22, 32, 1, 33)
@@ -831,13 +1269,13 @@ fn test_tuple() {
             max: None,
         })
     );
-    assert!(parse_tuple(Span::new("Tuple")).is_err());
+    assert!(parse_tuple(Span::new_extra("Tuple", &Default::default())).is_err());
 }
 
 #[test]
 fn test_type_specification() {
     assert_eq!(
-        parse_type_specification(Span::new("Stdlib::Unixpath"))
+        parse_type_specification(Span::new_extra("Stdlib::Unixpath", &Default::default()))
             .unwrap()
             .1,
         crate::puppet_lang::typing::TypeSpecification {
@@ -853,15 +1291,14 @@ fn test_type_specification() {
         }
     );
     assert_eq!(
-        parse_type_specification(Span::new("Class['hello']"))
+        parse_type_specification(Span::new_extra("Class['hello']", &Default::default()))
             .unwrap()
             .1,
         crate::puppet_lang::typing::TypeSpecification {
             comment: vec![],
-            data: crate::puppet_lang::typing::TypeSpecificationVariant::ExternalType(
-                crate::puppet_lang::typing::ExternalType {
-                    name: vec!["Class".to_owned(),],
-                    arguments: vec![crate::puppet_lang::expression::Expression {
+            data: crate::puppet_lang::typing::TypeSpecificationVariant::Class(
+                crate::puppet_lang::typing::TypeClass {
+                    titles: vec![crate::puppet_lang::expression::Expression {
                         accessor: None,
                         comment: vec![],
                         value: crate::puppet_lang::expression::ExpressionVariant::Term(
@@ -891,7 +1328,9 @@ fn test_type_specification() {
         }
     );
     assert_eq!(
-        parse_type_specification(Span::new("Numeric")).unwrap().1,
+        parse_type_specification(Span::new_extra("Numeric", &Default::default()))
+            .unwrap()
+            .1,
         crate::puppet_lang::typing::TypeSpecification {
             comment: vec![],
             data: crate::puppet_lang::typing::TypeSpecificationVariant::Numeric(
@@ -902,6 +1341,59 @@ fn test_type_specification() {
             extra: Range::new(0, 1, 1, 6, 1, 7)
         }
     );
-    assert!(parse_type_specification(Span::new("Pattern[//, /sdfsdf/]")).is_ok());
-    assert!(parse_type_specification(Span::new("Regexp[/sdfsdf/]")).is_ok());
+    assert!(parse_type_specification(Span::new_extra(
+        "Pattern[//, /sdfsdf/]",
+        &Default::default()
+    ))
+    .is_ok());
+    assert!(
+        parse_type_specification(Span::new_extra("Regexp[/sdfsdf/]", &Default::default())).is_ok()
+    );
+}
+
+#[test]
+fn test_callable() {
+    let callable = |s| match parse_type_specification(Span::new_extra(s, &Default::default()))
+        .unwrap()
+        .1
+        .data
+    {
+        crate::puppet_lang::typing::TypeSpecificationVariant::Callable(v) => v,
+        _ => panic!("Callable expected"),
+    };
+
+    let v = callable("Callable[String, Integer, 1, default, Callable]");
+    assert_eq!(v.params.len(), 2);
+    assert_eq!(v.min.map(|v| v.value), Some(1));
+    assert!(v.max.is_none());
+    assert!(v.block.is_some());
+    assert!(v.return_type.is_none());
+
+    let v = callable("Callable[[String], Boolean]");
+    assert_eq!(v.params.len(), 1);
+    assert!(v.return_type.is_some());
+
+    assert!(parse_type_specification(Span::new_extra(
+        "Callable[1, String, 2]",
+        &Default::default()
+    ))
+    .is_err());
+}
+
+#[test]
+fn test_type_name_boundary() {
+    assert!(matches!(
+        parse_type_specification(Span::new_extra("Data", &Default::default()))
+            .unwrap()
+            .1
+            .data,
+        crate::puppet_lang::typing::TypeSpecificationVariant::Data(_)
+    ));
+    assert!(matches!(
+        parse_type_specification(Span::new_extra("Database::Entry", &Default::default()))
+            .unwrap()
+            .1
+            .data,
+        crate::puppet_lang::typing::TypeSpecificationVariant::ExternalType(_)
+    ));
 }
@@ -0,0 +1,38 @@
+mod parser;
+
+use crate::puppet_parser::range::Range;
+
+#[derive(Debug, PartialEq)]
+pub struct Template {
+    /// Parameters declared in `<%- | ... | -%>` tag. None if template has no such tag and
+    /// accepts any parameters.
+    pub parameters: Option<Vec<crate::puppet_lang::argument::Argument<Range>>>,
+    /// Expressions of `<%= ... %>` blocks
+    pub expressions: Vec<crate::puppet_lang::expression::Expression<Range>>,
+}
+
+impl Template {
+    pub fn parse(content: &str) -> Option<Self> {
+        let state = crate::puppet_parser::ParserState::default();
+        parser::parse_toplevel(crate::puppet_parser::Span::new_extra(content, &state))
+            .ok()
+            .map(|(_, template)| template)
+    }
+
+    pub fn read(path: &std::path::Path) -> Option<Self> {
+        let file_content = match std::fs::read_to_string(path) {
+            Err(_err) => {
+                return None;
+            }
+            Ok(v) => v,
+        };
+
+        Self::parse(&file_content)
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<&crate::puppet_lang::argument::Argument<Range>> {
+        self.parameters
+            .as_ref()
+            .and_then(|list| list.iter().find(|arg| arg.name == name))
+    }
+}
@@ -0,0 +1,173 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_until},
+    combinator::{eof, map, opt, rest, value, verify},
+    multi::many0,
+    sequence::{delimited, pair, preceded, terminated, tuple},
+    Slice,
+};
+
+use crate::puppet_parser::common::separator0;
+use crate::puppet_parser::range::Range;
+use crate::puppet_parser::{IResult, ParseError, Span};
+
+pub fn parse_literal(input: Span) -> IResult<()> {
+    alt((
+        value((), verify(take_until("<%"), |s: &Span| !s.is_empty())),
+        // Escaped '<%'
+        value((), tag("<%%")),
+        value(
+            (),
+            verify(rest, |s: &Span| {
+                !s.is_empty() && !s.fragment().contains("<%")
+            }),
+        ),
+    ))(input)
+}
+
+fn tag_end(input: Span) -> IResult<()> {
+    value((), pair(opt(tag("-")), tag("%>")))(input)
+}
+
+pub fn parse_comment(input: Span) -> IResult<()> {
+    value(
+        (),
+        tuple((
+            tag("<%#"),
+            ParseError::protect(|_| "Unclosed comment tag".to_string(), take_until("%>")),
+            tag("%>"),
+        )),
+    )(input)
+}
+
+pub fn parse_code_block(input: Span) -> IResult<()> {
+    value(
+        (),
+        tuple((
+            tag("<%"),
+            ParseError::protect(|_| "Unclosed '<%' tag".to_string(), take_until("%>")),
+            tag("%>"),
+        )),
+    )(input)
+}
+
+pub fn parse_expression_block(
+    input: Span,
+) -> IResult<crate::puppet_lang::expression::Expression<Range>> {
+    let (input, _) = tag("<%=")(input)?;
+    let (tail, body) =
+        ParseError::protect(|_| "Unclosed '<%=' tag".to_string(), take_until("%>"))(input)?;
+    let (tail, _) = tag("%>")(tail)?;
+
+    // Strip '-' of trimming '-%>' tag end
+    let body = match body.fragment().trim_end().strip_suffix('-') {
+        Some(v) => body.slice(..v.len()),
+        None => body,
+    };
+
+    let (_, expr) = ParseError::protect(
+        |_| "Expression expected".to_string(),
+        terminated(
+            crate::puppet_parser::expression::parse_expression,
+            pair(separator0, eof),
+        ),
+    )(body)?;
+
+    Ok((tail, expr))
+}
+
+pub fn parse_parameters(
+    input: Span,
+) -> IResult<crate::puppet_lang::List<Range, crate::puppet_lang::argument::Argument<Range>>> {
+    map(
+        delimited(
+            tuple((separator0, tag("<%"), opt(tag("-")), separator0)),
+            crate::puppet_parser::common::pipes_delimimited(
+                crate::puppet_parser::common::comma_separated_list0_with_last_comment(
+                    crate::puppet_parser::argument::parse(false),
+                ),
+            ),
+            preceded(
+                separator0,
+                ParseError::protect(|_| "'%>' expected".to_string(), tag_end),
+            ),
+        ),
+        |(_left_pipe, list, _right_pipe)| list,
+    )(input)
+}
+
+pub fn parse_toplevel(input: Span) -> IResult<crate::puppet_pp_lint::ctx::epp_template::Template> {
+    let (input, parameters) = opt(parse_parameters)(input)?;
+
+    let parser = alt((
+        map(parse_literal, |_| None),
+        map(parse_comment, |_| None),
+        map(parse_expression_block, Some),
+        map(parse_code_block, |_| None),
+    ));
+
+    let (input, list) = terminated(many0(parser), eof)(input)?;
+
+    Ok((
+        input,
+        crate::puppet_pp_lint::ctx::epp_template::Template {
+            parameters: parameters.map(|v| v.value),
+            expressions: list.into_iter().flatten().collect(),
+        },
+    ))
+}
+
+#[test]
+fn test_expression_block() {
+    let state = crate::puppet_parser::ParserState::default();
+    let (tail, expr) = parse_expression_block(Span::new_extra("<%= $a + 1 -%>\n", &state)).unwrap();
+    assert_eq!(*tail.fragment(), "\n");
+    assert!(matches!(
+        expr.value,
+        crate::puppet_lang::expression::ExpressionVariant::Plus(_)
+    ));
+
+    assert!(parse_expression_block(Span::new_extra("<%= $a + %>", &Default::default())).is_err());
+    assert!(parse_expression_block(Span::new_extra("<%= $a", &Default::default())).is_err())
+}
+
+#[test]
+fn test_toplevel() {
+    let template = parse_toplevel(Span::new_extra(
+        "<%- | String $name,
+      Optional[Integer] $port = undef,
+| -%>
+<%# comment %>
+server <%= $name %>:<%= $port %> <%%
+<% if $port { -%>
+port=<%= $port -%>
+<% } -%>
+",
+        &Default::default(),
+    ))
+    .unwrap()
+    .1;
+
+    let parameters = template.parameters.unwrap();
+    assert_eq!(
+        parameters
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["name", "port"]
+    );
+    assert!(parameters[0].default.is_none());
+    assert!(parameters[1].default.is_some());
+    assert_eq!(template.expressions.len(), 3);
+
+    let template = parse_toplevel(Span::new_extra(
+        "no parameters <%= $a %>",
+        &Default::default(),
+    ))
+    .unwrap()
+    .1;
+    assert!(template.parameters.is_none());
+    assert_eq!(template.expressions.len(), 1);
+
+    assert!(parse_toplevel(Span::new_extra("unclosed <% if $a {", &Default::default())).is_err())
+}
@@ -1,5 +1,7 @@
 pub mod builtin_resources;
+pub mod epp_template;
 pub mod erb_template;
+pub mod suppression;
 
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
@@ -61,12 +63,18 @@ pub struct NamedBlock {
     pub value: crate::puppet_lang::toplevel::Toplevel<Range>,
 }
 
+type KnownVariables = Rc<std::cell::RefCell<HashMap<String, Rc<Variable>>>>;
 type KnownResources = Rc<std::cell::RefCell<HashMap<Vec<String>, Rc<Option<NamedBlock>>>>>;
 type KnownErbTemplates = Rc<
     std::cell::RefCell<
         HashMap<std::path::PathBuf, Rc<Option<crate::puppet_pp_lint::ctx::erb_template::Template>>>,
     >,
 >;
+type KnownEppTemplates = Rc<
+    std::cell::RefCell<
+        HashMap<std::path::PathBuf, Rc<Option<crate::puppet_pp_lint::ctx::epp_template::Template>>>,
+    >,
+>;
 
 #[derive(Clone)]
 pub enum Path<'ast, EXTRA: Clone> {
@@ -85,8 +93,17 @@ pub struct Ctx<'ast, EXTRA: Clone> {
     pub builtin_resources:
         Rc<HashMap<&'static str, crate::puppet_pp_lint::ctx::builtin_resources::Resource>>,
     pub resource_metaparameters: Rc<HashMap<&'static str, Attribute>>,
-    pub variables: Rc<std::cell::RefCell<HashMap<String, Rc<Variable>>>>,
+    pub variables: KnownVariables,
+    /// Variables of enclosing scope. Set for lambda scopes only.
+    pub outer_variables: Option<KnownVariables>,
     pub erb_templates: KnownErbTemplates,
+    pub epp_templates: KnownEppTemplates,
+    /// Suppression directives found in comments of current file
+    pub suppressions: Rc<RefCell<Vec<crate::puppet_pp_lint::ctx::suppression::Directive>>>,
+    /// Path of current file
+    pub file_path: Rc<std::path::PathBuf>,
+    /// Definitions and use sites of all checked files
+    pub repository: Rc<RefCell<crate::puppet_pp_lint::repository::Repository>>,
     pub path: Vec<Path<'ast, EXTRA>>,
 }
 
@@ -348,7 +365,14 @@ impl<'ast, EXTRA: Clone> Ctx<'ast, EXTRA> {
             builtin_resources: Rc::new(crate::puppet_pp_lint::ctx::builtin_resources::generate()),
             resource_metaparameters: Rc::new(resource_metaparameters),
             variables: Rc::new(RefCell::new(variables)),
+            outer_variables: None,
             erb_templates: Rc::new(std::cell::RefCell::new(HashMap::new())),
+            epp_templates: Rc::new(std::cell::RefCell::new(HashMap::new())),
+            suppressions: Rc::new(RefCell::new(Vec::new())),
+            file_path: Rc::new(std::path::PathBuf::new()),
+            repository: Rc::new(RefCell::new(
+                crate::puppet_pp_lint::repository::Repository::default(),
+            )),
             path: Vec::new(),
         }
     }
@@ -376,8 +400,9 @@ impl<'ast, EXTRA: Clone> Ctx<'ast, EXTRA> {
                 Ok(v) => v,
             };
 
-        let (_, statement_list) = match crate::puppet_parser::toplevel::parse_file(
-            crate::puppet_parser::Span::new(file_content.as_str()),
+        let state = crate::puppet_parser::ParserState::default();
+        let (_, statement_list) = match crate::puppet_parser::toplevel::parse_file_with_recovery(
+            crate::puppet_parser::Span::new_extra(file_content.as_str(), &state),
         ) {
             Err(_err) => {
                 return;
@@ -406,6 +431,7 @@ impl<'ast, EXTRA: Clone> Ctx<'ast, EXTRA> {
                             // TODO
                             None
                         }
+                        crate::puppet_lang::toplevel::ToplevelVariant::Node(_) => None,
                     };
 
                     if let Some(name) = name {
@@ -419,7 +445,9 @@ impl<'ast, EXTRA: Clone> Ctx<'ast, EXTRA> {
                 | crate::puppet_lang::statement::StatementVariant::IfElse(_)
                 | crate::puppet_lang::statement::StatementVariant::Unless(_)
                 | crate::puppet_lang::statement::StatementVariant::Case(_)
-                | crate::puppet_lang::statement::StatementVariant::ResourceDefaults(_) => (),
+                | crate::puppet_lang::statement::StatementVariant::ResourceDefaults(_)
+                | crate::puppet_lang::statement::StatementVariant::ResourceOverride(_)
+                | crate::puppet_lang::statement::StatementVariant::SyntaxError(_) => (),
             }
         }
     }
@@ -458,7 +486,30 @@ impl<'ast, EXTRA: Clone> Ctx<'ast, EXTRA> {
         new_ctx
     }
 
-    pub fn new_file(&mut self) {
+    /// Lambda sees all variables of enclosing scope, but variables defined in lambda are not
+    /// visible outside of it.
+    pub fn new_lambda_scope(&self) -> Self {
+        let mut new_ctx = self.clone();
+        new_ctx.outer_variables = Some(self.variables.clone());
+        new_ctx.variables = Rc::new(RefCell::new(self.variables.borrow().clone()));
+        new_ctx
+    }
+
+    /// Returns true if variable is inherited from enclosing scope of lambda
+    pub fn is_outer_variable(&self, name: &str, variable: &Rc<Variable>) -> bool {
+        match &self.outer_variables {
+            None => false,
+            Some(outer_variables) => match outer_variables.borrow().get(name) {
+                Some(v) => Rc::ptr_eq(v, variable),
+                None => false,
+            },
+        }
+    }
+
+    pub fn new_file(&mut self, file_path: &std::path::Path) {
+        self.file_path = Rc::new(file_path.to_path_buf());
+        self.repository.borrow_mut().remove_file(file_path);
+
         // cleanup
         {
             let mut variables = self.variables.borrow_mut();
@@ -469,6 +520,46 @@ impl<'ast, EXTRA: Clone> Ctx<'ast, EXTRA> {
                 VariableVariant::Defined(_) => false,
             });
         }
+        self.suppressions.borrow_mut().clear();
+    }
+
+    pub fn register_definition<S: AsRef<str>>(
+        &self,
+        kind: crate::puppet_pp_lint::repository::Kind,
+        name: &[S],
+        location: &Range,
+    ) {
+        self.repository.borrow_mut().add_definition(
+            kind,
+            &crate::puppet_pp_lint::repository::normalize_name(name),
+            &self.file_path,
+            location,
+        )
+    }
+
+    pub fn register_use<S: AsRef<str>>(
+        &self,
+        kind: crate::puppet_pp_lint::repository::Kind,
+        name: &[S],
+        location: &Range,
+    ) {
+        self.repository.borrow_mut().add_use(
+            kind,
+            &crate::puppet_pp_lint::repository::normalize_name(name),
+            &self.file_path,
+            location,
+        )
+    }
+
+    pub fn register_suppressions(&self, comments: &[crate::puppet_lang::comment::Comment<Range>]) {
+        let mut suppressions = self.suppressions.borrow_mut();
+        for comment in comments {
+            if let Some(directive) =
+                crate::puppet_pp_lint::ctx::suppression::Directive::parse(comment)
+            {
+                suppressions.push(directive)
+            }
+        }
     }
 
     pub fn add_path(&self, path_elt: Path<'ast, EXTRA>) -> Self {
@@ -520,27 +611,33 @@ impl<'ast, EXTRA: Clone> Ctx<'ast, EXTRA> {
         self.variables.borrow().contains_key(name)
     }
 
+    /// Converts 'module/file' template reference to path in repository
+    fn template_path(&self, path: &str) -> Option<std::path::PathBuf> {
+        let path = std::path::Path::new(path);
+        let mut path_components = path.components();
+
+        let module_name = path_components.next()?;
+
+        Some(
+            self.repository_path
+                .join("modules")
+                .join(module_name)
+                .join("templates")
+                .join(path_components),
+        )
+    }
+
     pub fn erb_of_path(
         &self,
         path: &str,
     ) -> Rc<Option<crate::puppet_pp_lint::ctx::erb_template::Template>> {
-        let path = std::path::Path::new(path);
-        let mut path_components = path.components();
-
-        let module_name = match path_components.next() {
+        let full_path = match self.template_path(path) {
             Some(v) => v,
             None => return Rc::new(None),
         };
 
-        let full_path = self
-            .repository_path
-            .join("modules")
-            .join(module_name)
-            .join("templates")
-            .join(path_components);
-
         {
-            if let Some(v) = self.erb_templates.borrow().get(path) {
+            if let Some(v) = self.erb_templates.borrow().get(&full_path) {
                 return v.clone();
             }
         }
@@ -552,4 +649,27 @@ impl<'ast, EXTRA: Clone> Ctx<'ast, EXTRA> {
         let _ = erb_templates.insert(full_path, template.clone());
         template
     }
+
+    pub fn epp_of_path(
+        &self,
+        path: &str,
+    ) -> Rc<Option<crate::puppet_pp_lint::ctx::epp_template::Template>> {
+        let full_path = match self.template_path(path) {
+            Some(v) => v,
+            None => return Rc::new(None),
+        };
+
+        {
+            if let Some(v) = self.epp_templates.borrow().get(&full_path) {
+                return v.clone();
+            }
+        }
+
+        let template = Rc::new(crate::puppet_pp_lint::ctx::epp_template::Template::read(
+            &full_path,
+        ));
+        let mut epp_templates = self.epp_templates.borrow_mut();
+        let _ = epp_templates.insert(full_path, template.clone());
+        template
+    }
 }
@@ -0,0 +1,216 @@
+use std::cell::Cell;
+
+use crate::puppet_parser::range::Range;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DirectiveVariant {
+    /// shadowplay:disable-line=Lint1,Lint2
+    DisableLine,
+    /// shadowplay:disable-next-line=Lint1,Lint2
+    DisableNextLine,
+    /// shadowplay:disable=Lint1,Lint2, lasts till enable or end of file
+    Disable,
+    /// shadowplay:enable=Lint1,Lint2
+    Enable,
+}
+
+/// Suppression directive found in a comment
+#[derive(Clone, Debug, PartialEq)]
+pub struct Directive {
+    pub variant: DirectiveVariant,
+    pub lints: Vec<String>,
+    pub location: Range,
+}
+
+impl Directive {
+    pub fn parse(comment: &crate::puppet_lang::comment::Comment<Range>) -> Option<Self> {
+        let (variant, lints) = comment
+            .value
+            .trim()
+            .strip_prefix("shadowplay:")?
+            .split_once('=')?;
+
+        let variant = match variant.trim() {
+            "disable-line" => DirectiveVariant::DisableLine,
+            "disable-next-line" => DirectiveVariant::DisableNextLine,
+            "disable" => DirectiveVariant::Disable,
+            "enable" => DirectiveVariant::Enable,
+            _ => return None,
+        };
+
+        let lints: Vec<String> = lints
+            .split(',')
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect();
+
+        if lints.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            variant,
+            lints,
+            location: comment.extra.clone(),
+        })
+    }
+}
+
+/// Lint suppressed for range of lines
+pub struct Suppression {
+    pub lint: String,
+    pub first_line: u32,
+    /// None means end of file
+    pub last_line: Option<u32>,
+    /// Location of comment which declared the suppression
+    pub location: Range,
+    used: Cell<bool>,
+}
+
+impl Suppression {
+    fn new(lint: &str, first_line: u32, last_line: Option<u32>, location: &Range) -> Self {
+        Self {
+            lint: lint.to_string(),
+            first_line,
+            last_line,
+            location: location.clone(),
+            used: Cell::new(false),
+        }
+    }
+
+    pub fn is_used(&self) -> bool {
+        self.used.get()
+    }
+}
+
+/// Suppressions of single file
+pub struct Suppressions {
+    pub list: Vec<Suppression>,
+    /// Enable directives without preceding disable
+    pub unmatched_enable: Vec<(String, Range)>,
+}
+
+impl Suppressions {
+    pub fn new(directives: &[Directive]) -> Self {
+        let mut directives: Vec<&Directive> = directives.iter().collect();
+        directives.sort_by_key(|v| v.location.start().offset());
+        directives.dedup();
+
+        let mut list = Vec::new();
+        let mut unmatched_enable = Vec::new();
+        // Indexes in list of not yet closed disable blocks
+        let mut opened: Vec<usize> = Vec::new();
+
+        for directive in directives {
+            let line = directive.location.start().line();
+            for lint in &directive.lints {
+                match directive.variant {
+                    DirectiveVariant::DisableLine => list.push(Suppression::new(
+                        lint,
+                        line,
+                        Some(line),
+                        &directive.location,
+                    )),
+                    DirectiveVariant::DisableNextLine => list.push(Suppression::new(
+                        lint,
+                        line + 1,
+                        Some(line + 1),
+                        &directive.location,
+                    )),
+                    DirectiveVariant::Disable => {
+                        opened.push(list.len());
+                        list.push(Suppression::new(lint, line, None, &directive.location))
+                    }
+                    DirectiveVariant::Enable => {
+                        match opened.iter().position(|idx| &list[*idx].lint == lint) {
+                            Some(pos) => {
+                                let idx = opened.remove(pos);
+                                list[idx].last_line = Some(line)
+                            }
+                            None => {
+                                unmatched_enable.push((lint.clone(), directive.location.clone()))
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            list,
+            unmatched_enable,
+        }
+    }
+
+    /// Returns true if error is suppressed. Marks matching suppressions as used.
+    pub fn suppresses(&self, err: &crate::puppet_pp_lint::lint::LintError) -> bool {
+        let line = err.location.start().line();
+        let mut suppressed = false;
+        for suppression in &self.list {
+            if suppression.lint == err.linter.name()
+                && line >= suppression.first_line
+                && suppression.last_line.map(|v| line <= v).unwrap_or(true)
+            {
+                suppression.used.set(true);
+                suppressed = true;
+            }
+        }
+        suppressed
+    }
+}
+
+#[test]
+fn test_directive() {
+    let comment = |value: &str| crate::puppet_lang::comment::Comment {
+        value: value.to_string(),
+        extra: Range::new(0, 1, 1, 0, 1, 1),
+    };
+
+    assert_eq!(
+        Directive::parse(&comment(
+            " shadowplay:disable-line=MagicNumber, UnusedVariables"
+        )),
+        Some(Directive {
+            variant: DirectiveVariant::DisableLine,
+            lints: vec!["MagicNumber".to_string(), "UnusedVariables".to_string()],
+            location: Range::new(0, 1, 1, 0, 1, 1),
+        })
+    );
+    assert_eq!(
+        Directive::parse(&comment(" shadowplay:enable=MagicNumber"))
+            .unwrap()
+            .variant,
+        DirectiveVariant::Enable
+    );
+    assert!(Directive::parse(&comment(" shadowplay:disable=")).is_none());
+    assert!(Directive::parse(&comment(" shadowplay:ignore=MagicNumber")).is_none());
+    assert!(Directive::parse(&comment(" just a comment")).is_none());
+}
+
+#[test]
+fn test_suppressions() {
+    let directive = |variant, lint: &str, line| Directive {
+        variant,
+        lints: vec![lint.to_string()],
+        location: Range::new(line as usize * 10, line, 1, line as usize * 10, line, 1),
+    };
+
+    let suppressions = Suppressions::new(&[
+        directive(DirectiveVariant::Enable, "B", 9),
+        directive(DirectiveVariant::DisableNextLine, "A", 1),
+        directive(DirectiveVariant::Disable, "B", 3),
+        directive(DirectiveVariant::Enable, "B", 5),
+        directive(DirectiveVariant::Disable, "C", 7),
+    ]);
+
+    let ranges: Vec<_> = suppressions
+        .list
+        .iter()
+        .map(|v| (v.lint.as_str(), v.first_line, v.last_line))
+        .collect();
+    assert_eq!(
+        ranges,
+        vec![("A", 2, Some(2)), ("B", 3, Some(5)), ("C", 7, None)]
+    );
+    assert_eq!(suppressions.unmatched_enable.len(), 1);
+}
@@ -0,0 +1,294 @@
+use crate::puppet_parser::range::Range;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Replacement {
+    Text(String),
+    /// Text between byte offsets of the same source, end offset is exclusive
+    Source {
+        start: usize,
+        end: usize,
+    },
+}
+
+/// Replacement of source text between byte offsets, end offset is exclusive
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextEdit {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: Replacement,
+}
+
+/// Range end location points to the last char of range
+fn end_offset(range: &Range) -> usize {
+    range.end().offset() + 1
+}
+
+impl TextEdit {
+    pub fn new(start: usize, end: usize, replacement: Replacement) -> Self {
+        Self {
+            start,
+            end,
+            replacement,
+        }
+    }
+
+    pub fn replace(range: &Range, text: &str) -> Self {
+        Self::new(
+            range.start().offset(),
+            end_offset(range),
+            Replacement::Text(text.to_string()),
+        )
+    }
+
+    /// Replaces text of range with text of another range of the same source
+    pub fn replace_with_source(range: &Range, source: &Range) -> Self {
+        Self::new(
+            range.start().offset(),
+            end_offset(range),
+            Replacement::Source {
+                start: source.start().offset(),
+                end: end_offset(source),
+            },
+        )
+    }
+
+    pub fn delete(range: &Range) -> Self {
+        Self::replace(range, "")
+    }
+
+    pub fn insert(offset: usize, text: &str) -> Self {
+        Self::new(offset, offset, Replacement::Text(text.to_string()))
+    }
+
+    pub fn insert_source(offset: usize, source: &Range) -> Self {
+        Self::new(
+            offset,
+            offset,
+            Replacement::Source {
+                start: source.start().offset(),
+                end: end_offset(source),
+            },
+        )
+    }
+
+    /// Byte ranges of source which are replaced or copied by edit
+    fn touched(&self) -> Vec<(usize, usize)> {
+        match &self.replacement {
+            Replacement::Text(_) => vec![(self.start, self.end)],
+            Replacement::Source { start, end } => vec![(self.start, self.end), (*start, *end)],
+        }
+    }
+
+    fn overlaps(&self, other: &Self) -> bool {
+        self.touched().iter().any(|range| {
+            other
+                .touched()
+                .iter()
+                .any(|other_range| ranges_overlap(range, other_range))
+        })
+    }
+}
+
+/// Insertion at the start of replaced text overlaps with it too, otherwise the inserted text
+/// would be glued to the replacement
+fn ranges_overlap(
+    (start, end): &(usize, usize),
+    (other_start, other_end): &(usize, usize),
+) -> bool {
+    (start < other_end && other_start < end)
+        || (start == end && start == other_start && other_start < other_end)
+        || (other_start == other_end && other_start == start && start < end)
+}
+
+/// Moves offset to the nearest char boundary, None if offset is out of source
+fn char_boundary(source: &str, offset: usize) -> Option<usize> {
+    (offset..=source.len()).find(|v| source.is_char_boundary(*v))
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$' || c == '\'' || c == '"'
+}
+
+/// Applies edits of errors. Edits of single error are applied all together or not applied at
+/// all. Edits overlapping with already accepted ones are skipped. Returns None if nothing was
+/// applied.
+pub fn apply(source: &str, errors: &[crate::puppet_pp_lint::lint::LintError]) -> Option<String> {
+    let mut accepted: Vec<TextEdit> = Vec::new();
+
+    for err in errors {
+        let mut edits = Vec::new();
+        for edit in &err.edits {
+            let start = char_boundary(source, edit.start);
+            let end = char_boundary(source, edit.end);
+            let replacement = match &edit.replacement {
+                Replacement::Text(v) => Some(Replacement::Text(v.clone())),
+                Replacement::Source { start, end } => {
+                    match (char_boundary(source, *start), char_boundary(source, *end)) {
+                        (Some(start), Some(end)) if start <= end => {
+                            Some(Replacement::Source { start, end })
+                        }
+                        _ => None,
+                    }
+                }
+            };
+            match (start, end, replacement) {
+                (Some(start), Some(end), Some(replacement)) if start <= end => {
+                    edits.push(TextEdit::new(start, end, replacement))
+                }
+                _ => {
+                    edits.clear();
+                    break;
+                }
+            }
+        }
+
+        if edits.is_empty()
+            || edits
+                .iter()
+                .any(|edit| accepted.iter().any(|other| edit.overlaps(other)))
+        {
+            continue;
+        }
+        accepted.append(&mut edits);
+    }
+
+    if accepted.is_empty() {
+        return None;
+    }
+
+    // Stable sort keeps order of insertions at the same offset
+    accepted.sort_by_key(|v| v.start);
+
+    let mut r = String::new();
+    let mut pos = 0;
+    for edit in accepted {
+        r.push_str(&source[pos..edit.start]);
+        match &edit.replacement {
+            Replacement::Text(text) if text.is_empty() => {
+                // Deletion must not glue neighbour tokens together
+                let prev = source[..edit.start].chars().last();
+                let next = source[edit.end..].chars().next();
+                if let (Some(prev), Some(next)) = (prev, next) {
+                    if is_word_char(prev) && is_word_char(next) {
+                        r.push(' ')
+                    }
+                }
+            }
+            Replacement::Text(text) => r.push_str(text),
+            Replacement::Source { start, end } => r.push_str(&source[*start..*end]),
+        }
+        pos = edit.end;
+    }
+    r.push_str(&source[pos..]);
+
+    Some(r)
+}
+
+#[cfg(test)]
+fn test_error(edits: Vec<TextEdit>) -> crate::puppet_pp_lint::lint::LintError {
+    crate::puppet_pp_lint::lint::LintError::new(
+        Box::new(crate::puppet_pp_lint::lint_unless::DoNotUseUnless),
+        "",
+        &Range::new(0, 1, 1, 0, 1, 1),
+    )
+    .with_edits(edits)
+}
+
+#[test]
+fn test_apply() {
+    let source = "if($a) { $b = \"x\" }";
+
+    assert_eq!(apply(source, &[test_error(vec![])]), None);
+
+    assert_eq!(
+        apply(
+            source,
+            &[
+                test_error(vec![
+                    TextEdit::new(2, 3, Replacement::Text(String::new())),
+                    TextEdit::new(5, 6, Replacement::Text(String::new())),
+                ]),
+                test_error(vec![TextEdit::replace(
+                    &Range::new(14, 1, 15, 16, 1, 17),
+                    "'x'"
+                )]),
+                // Overlaps with the first one
+                test_error(vec![
+                    TextEdit::insert(0, "# "),
+                    TextEdit::replace(&Range::new(2, 1, 3, 3, 1, 4), "$c"),
+                ]),
+            ]
+        ),
+        Some("if $a { $b = 'x' }".to_string())
+    );
+
+    // Swap
+    assert_eq!(
+        apply(
+            "a, bb",
+            &[test_error(vec![
+                TextEdit::replace_with_source(
+                    &Range::new(0, 1, 1, 0, 1, 1),
+                    &Range::new(3, 1, 4, 4, 1, 5)
+                ),
+                TextEdit::replace_with_source(
+                    &Range::new(3, 1, 4, 4, 1, 5),
+                    &Range::new(0, 1, 1, 0, 1, 1)
+                ),
+            ])]
+        ),
+        Some("bb, a".to_string())
+    );
+
+    // Deletion which starts at offset of accepted insertion is postponed to the next pass
+    assert_eq!(
+        apply(
+            "unless($a) {}",
+            &[
+                test_error(vec![TextEdit::insert(6, "!")]),
+                test_error(vec![TextEdit::delete(&Range::new(6, 1, 7, 6, 1, 7))]),
+            ]
+        ),
+        Some("unless!($a) {}".to_string())
+    );
+}
+
+#[cfg(test)]
+fn test_fix(source: &str) -> String {
+    let mut source = source.to_string();
+    let mut ctx = crate::puppet_pp_lint::ctx::Ctx::new(std::path::Path::new("."));
+    ctx.new_file(std::path::Path::new("init.pp"));
+    for _ in 0..10 {
+        let state = crate::puppet_parser::ParserState::default();
+        let (_, statements) = crate::puppet_parser::toplevel::parse_file(
+            crate::puppet_parser::Span::new_extra(&source, &state),
+        )
+        .unwrap();
+        let errors = crate::puppet_pp_lint::lint::AstLinter.check_file(
+            &crate::puppet_pp_lint::lint::Storage::default(),
+            &ctx,
+            &statements,
+        );
+        match apply(&source, &errors) {
+            Some(fixed) => source = fixed,
+            None => break,
+        }
+    }
+    source
+}
+
+#[test]
+fn test_fix_conditions() {
+    assert_eq!(
+        test_fix("unless($y) {\n  notice('b')\n}\n"),
+        "if !$y {\n  notice('b')\n}\n"
+    );
+    assert_eq!(
+        test_fix("unless !$y {\n  notice('b')\n}\n"),
+        "if $y {\n  notice('b')\n}\n"
+    );
+    assert_eq!(
+        test_fix("if($a) {\n  notice('b')\n}\n"),
+        "if $a {\n  notice('b')\n}\n"
+    );
+}
@@ -1,9 +1,55 @@
 use crate::puppet_parser::range::Range;
 use serde::{Deserialize, Serialize};
 
+/// Importance of lint error. Variants are ordered from the least important one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Hint,
+    Info,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Hint => write!(f, "hint"),
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hint" => Ok(Self::Hint),
+            "info" => Ok(Self::Info),
+            "warning" => Ok(Self::Warning),
+            "error" => Ok(Self::Error),
+            _ => Err(format!("Invalid severity: {}", s)),
+        }
+    }
+}
+
 pub trait LintPass {
     fn name(&self) -> &str;
     fn description(&self) -> &str;
+    /// Default severity of lint errors, can be overridden in config
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+}
+
+/// Secondary location related to lint error, e.g. previous definition of duplicated name
+#[derive(Clone)]
+pub struct Label {
+    pub message: String,
+    pub location: Range,
 }
 
 // #[derive(Clone)]
@@ -12,15 +58,25 @@ pub struct LintError {
     pub message: String,
     pub url: Option<String>,
     pub location: Range,
+    pub severity: Severity,
+    /// Automatic fix of the error
+    pub edits: Vec<crate::puppet_pp_lint::fix::TextEdit>,
+    pub labels: Vec<Label>,
+    /// Free-form explanations and hints
+    pub notes: Vec<String>,
 }
 
 impl LintError {
     pub fn new(linter: Box<dyn LintPass>, message: &str, location: &Range) -> Self {
         Self {
+            severity: linter.severity(),
             linter,
             message: message.to_owned(),
             url: None,
             location: location.clone(),
+            edits: Vec::new(),
+            labels: Vec::new(),
+            notes: Vec::new(),
         }
     }
     pub fn new_with_url(
@@ -30,12 +86,50 @@ impl LintError {
         location: &Range,
     ) -> Self {
         Self {
+            severity: linter.severity(),
             linter,
             message: message.to_owned(),
             url: Some(url.to_owned()),
             location: location.clone(),
+            edits: Vec::new(),
+            labels: Vec::new(),
+            notes: Vec::new(),
         }
     }
+
+    pub fn with_edits(mut self, edits: Vec<crate::puppet_pp_lint::fix::TextEdit>) -> Self {
+        self.edits = edits;
+        self
+    }
+
+    pub fn with_label(mut self, message: &str, location: &Range) -> Self {
+        self.labels.push(Label {
+            message: message.to_owned(),
+            location: location.clone(),
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: &str) -> Self {
+        self.notes.push(note.to_owned());
+        self
+    }
+}
+
+/// Lint error of late lint pass. Such errors may be reported for any file of repository.
+pub struct RepositoryLintError {
+    pub file: std::path::PathBuf,
+    pub error: LintError,
+}
+
+/// Lint pass which runs after all files of the run are checked by early passes
+pub trait LateLintPass: LintPass {
+    fn check_repository(
+        &self,
+        _repository: &crate::puppet_pp_lint::repository::Repository,
+    ) -> Vec<RepositoryLintError> {
+        Vec::new()
+    }
 }
 
 pub trait EarlyLintPass: LintPass {
@@ -64,6 +158,9 @@ pub trait EarlyLintPass: LintPass {
     fn check_typedef(&self, _: &crate::puppet_lang::toplevel::TypeDef<Range>) -> Vec<LintError> {
         Vec::new()
     }
+    fn check_node(&self, _: &crate::puppet_lang::toplevel::Node<Range>) -> Vec<LintError> {
+        Vec::new()
+    }
     fn check_functiondef(
         &self,
         _: &crate::puppet_lang::toplevel::FunctionDef<Range>,
@@ -151,6 +248,20 @@ pub trait EarlyLintPass: LintPass {
     ) -> Vec<LintError> {
         Vec::new()
     }
+    fn check_resource_reference(
+        &self,
+        _ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        _: &crate::puppet_lang::expression::ResourceReference<Range>,
+    ) -> Vec<LintError> {
+        Vec::new()
+    }
+    fn check_resource_override(
+        &self,
+        _ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        _: &crate::puppet_lang::statement::ResourceOverride<Range>,
+    ) -> Vec<LintError> {
+        Vec::new()
+    }
     fn check_case_statement(
         &self,
         _: &crate::puppet_lang::statement::Case<Range>,
@@ -194,6 +305,7 @@ pub enum EarlyLintPassVariant {
     ErbReferencesToUnknownVariable(
         crate::puppet_pp_lint::lint_builtin::ErbReferencesToUnknownVariable,
     ),
+    EppParametersMismatch(crate::puppet_pp_lint::lint_builtin::EppParametersMismatch),
     UselessDoubleQuotes(crate::puppet_pp_lint::lint_string_expr::UselessDoubleQuotes),
     ExpressionInSingleQuotes(crate::puppet_pp_lint::lint_string_expr::ExpressionInSingleQuotes),
     LowerCaseVariable(crate::puppet_pp_lint::lint_term::LowerCaseVariable),
@@ -219,6 +331,7 @@ pub enum EarlyLintPassVariant {
     InvalidResourceCollectionInvocation(
         crate::puppet_pp_lint::lint_resource_set::InvalidResourceCollectionInvocation,
     ),
+    InvalidResourceReference(crate::puppet_pp_lint::lint_resource_set::InvalidResourceReference),
     EmptyCasesList(crate::puppet_pp_lint::lint_case_statement::EmptyCasesList),
     DefaultCaseIsNotLast(crate::puppet_pp_lint::lint_case_statement::DefaultCaseIsNotLast),
     MultipleDefaultCase(crate::puppet_pp_lint::lint_case_statement::MultipleDefaultCase),
@@ -230,6 +343,8 @@ pub enum EarlyLintPassVariant {
     DeepCode(crate::puppet_pp_lint::lint_statement::DeepCode),
     HugeCodeBlock(crate::puppet_pp_lint::lint_statement::HugeCodeBlock),
     TooManyArguments(crate::puppet_pp_lint::lint_toplevel::TooManyArguments),
+    UnusedSuppression(crate::puppet_pp_lint::lint_suppression::UnusedSuppression),
+    Custom(crate::puppet_pp_lint::lint_custom::Custom),
 }
 
 impl EarlyLintPassVariant {
@@ -249,6 +364,7 @@ impl EarlyLintPassVariant {
             EarlyLintPassVariant::NegationOfEquation(v) => Box::new(v),
             EarlyLintPassVariant::ConstantExpressionInCondition(v) => Box::new(v),
             EarlyLintPassVariant::ErbReferencesToUnknownVariable(v) => Box::new(v),
+            EarlyLintPassVariant::EppParametersMismatch(v) => Box::new(v),
             EarlyLintPassVariant::UselessDoubleQuotes(v) => Box::new(v),
             EarlyLintPassVariant::ExpressionInSingleQuotes(v) => Box::new(v),
             EarlyLintPassVariant::LowerCaseVariable(v) => Box::new(v),
@@ -264,6 +380,7 @@ impl EarlyLintPassVariant {
             EarlyLintPassVariant::UnconditionalExec(v) => Box::new(v),
             EarlyLintPassVariant::InvalidResourceSetInvocation(v) => Box::new(v),
             EarlyLintPassVariant::InvalidResourceCollectionInvocation(v) => Box::new(v),
+            EarlyLintPassVariant::InvalidResourceReference(v) => Box::new(v),
             EarlyLintPassVariant::EmptyCasesList(v) => Box::new(v),
             EarlyLintPassVariant::DefaultCaseIsNotLast(v) => Box::new(v),
             EarlyLintPassVariant::MultipleDefaultCase(v) => Box::new(v),
@@ -276,6 +393,21 @@ impl EarlyLintPassVariant {
             EarlyLintPassVariant::DeepCode(v) => Box::new(v),
             EarlyLintPassVariant::HugeCodeBlock(v) => Box::new(v),
             EarlyLintPassVariant::TooManyArguments(v) => Box::new(v),
+            EarlyLintPassVariant::UnusedSuppression(v) => Box::new(v),
+            EarlyLintPassVariant::Custom(v) => Box::new(v),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Serialize)]
+pub enum LateLintPassVariant {
+    DuplicateDefinition(crate::puppet_pp_lint::lint_repository::DuplicateDefinition),
+}
+
+impl LateLintPassVariant {
+    pub fn inner(&self) -> Box<&dyn LateLintPass> {
+        match self {
+            LateLintPassVariant::DuplicateDefinition(v) => Box::new(v),
         }
     }
 }
@@ -283,6 +415,11 @@ impl EarlyLintPassVariant {
 #[derive(Deserialize, Clone, Serialize)]
 pub struct Storage {
     early_pass: Vec<EarlyLintPassVariant>,
+    #[serde(default)]
+    late_pass: Vec<LateLintPassVariant>,
+    /// Overrides of default lint severities
+    #[serde(default)]
+    severity: std::collections::BTreeMap<String, Severity>,
 }
 
 impl Storage {
@@ -293,12 +430,68 @@ impl Storage {
     pub fn early_pass(&self) -> &[EarlyLintPassVariant] {
         &self.early_pass
     }
+
+    /// Enables lint or replaces configuration of already enabled one
+    pub fn set_early_pass(&mut self, lint: EarlyLintPassVariant) {
+        match self
+            .early_pass
+            .iter_mut()
+            .find(|v| v.inner().name() == lint.inner().name())
+        {
+            Some(v) => *v = lint,
+            None => self.early_pass.push(lint),
+        }
+    }
+
+    pub fn register_late_pass(&mut self, lint: LateLintPassVariant) {
+        self.late_pass.push(lint);
+    }
+
+    pub fn late_pass(&self) -> &[LateLintPassVariant] {
+        &self.late_pass
+    }
+
+    /// Enables lint or replaces configuration of already enabled one
+    pub fn set_late_pass(&mut self, lint: LateLintPassVariant) {
+        match self
+            .late_pass
+            .iter_mut()
+            .find(|v| v.inner().name() == lint.inner().name())
+        {
+            Some(v) => *v = lint,
+            None => self.late_pass.push(lint),
+        }
+    }
+
+    /// Disables early or late lint pass
+    pub fn disable(&mut self, name: &str) {
+        self.early_pass.retain(|v| v.inner().name() != name);
+        self.late_pass.retain(|v| v.inner().name() != name)
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.early_pass.iter().any(|v| v.inner().name() == name)
+            || self.late_pass.iter().any(|v| v.inner().name() == name)
+    }
+
+    pub fn set_severity(&mut self, name: &str, severity: Severity) {
+        let _ = self.severity.insert(name.to_string(), severity);
+    }
+
+    pub fn severity(&self, lint: &dyn LintPass) -> Severity {
+        self.severity
+            .get(lint.name())
+            .copied()
+            .unwrap_or_else(|| lint.severity())
+    }
 }
 
 impl Default for Storage {
     fn default() -> Self {
         let mut v = Self {
             early_pass: Vec::new(),
+            late_pass: Vec::new(),
+            severity: std::collections::BTreeMap::new(),
         };
 
         v.register_early_pass(EarlyLintPassVariant::OptionalArgumentsGoesFirst(
@@ -343,6 +536,9 @@ impl Default for Storage {
         v.register_early_pass(EarlyLintPassVariant::ErbReferencesToUnknownVariable(
             super::lint_builtin::ErbReferencesToUnknownVariable,
         ));
+        v.register_early_pass(EarlyLintPassVariant::EppParametersMismatch(
+            super::lint_builtin::EppParametersMismatch,
+        ));
         v.register_early_pass(EarlyLintPassVariant::UselessDoubleQuotes(
             super::lint_string_expr::UselessDoubleQuotes,
         ));
@@ -388,6 +584,9 @@ impl Default for Storage {
         v.register_early_pass(EarlyLintPassVariant::InvalidResourceCollectionInvocation(
             super::lint_resource_set::InvalidResourceCollectionInvocation,
         ));
+        v.register_early_pass(EarlyLintPassVariant::InvalidResourceReference(
+            super::lint_resource_set::InvalidResourceReference,
+        ));
         v.register_early_pass(EarlyLintPassVariant::EmptyCasesList(
             super::lint_case_statement::EmptyCasesList,
         ));
@@ -424,6 +623,12 @@ impl Default for Storage {
         v.register_early_pass(EarlyLintPassVariant::TooManyArguments(
             super::lint_toplevel::TooManyArguments::default(),
         ));
+        v.register_early_pass(EarlyLintPassVariant::UnusedSuppression(
+            super::lint_suppression::UnusedSuppression,
+        ));
+        v.register_late_pass(LateLintPassVariant::DuplicateDefinition(
+            super::lint_repository::DuplicateDefinition,
+        ));
         v
     }
 }
@@ -431,6 +636,57 @@ impl Default for Storage {
 pub struct AstLinter;
 
 impl AstLinter {
+    /// Checks all statements of file. Errors suppressed by shadowplay:disable comments are dropped.
+    pub fn check_file(
+        &self,
+        storage: &Storage,
+        ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        statements: &crate::puppet_lang::List<
+            Range,
+            crate::puppet_lang::statement::Statement<Range>,
+        >,
+    ) -> Vec<LintError> {
+        let mut errors = Vec::new();
+        for statement in &statements.value {
+            errors.append(&mut self.check_statement(storage, ctx, statement));
+        }
+        ctx.register_suppressions(&statements.last_comment);
+
+        let suppressions =
+            crate::puppet_pp_lint::ctx::suppression::Suppressions::new(&ctx.suppressions.borrow());
+        errors.retain(|err| !suppressions.suppresses(err));
+
+        for lint in storage.early_pass() {
+            if let EarlyLintPassVariant::UnusedSuppression(v) = lint {
+                errors.append(&mut v.check_suppressions(&suppressions))
+            }
+        }
+
+        for err in &mut errors {
+            err.severity = storage.severity(err.linter.as_ref())
+        }
+
+        errors
+    }
+
+    /// Runs late lint passes over repository model filled by checks of files
+    pub fn check_repository(
+        &self,
+        storage: &Storage,
+        repository: &crate::puppet_pp_lint::repository::Repository,
+    ) -> Vec<RepositoryLintError> {
+        let mut errors = Vec::new();
+        for lint in storage.late_pass() {
+            errors.append(&mut lint.inner().check_repository(repository));
+        }
+
+        for err in &mut errors {
+            err.error.severity = storage.severity(err.error.linter.as_ref())
+        }
+
+        errors
+    }
+
     pub fn check_string_expression(
         &self,
         storage: &Storage,
@@ -442,13 +698,16 @@ impl AstLinter {
             errors.append(&mut lint.inner().check_string_expression(elt));
         }
 
-        if let crate::puppet_lang::string::StringVariant::DoubleQuoted(s) = &elt.data {
-            for fragment in s {
-                match fragment {
-                    crate::puppet_lang::string::DoubleQuotedFragment::StringFragment(_) => {}
-                    crate::puppet_lang::string::DoubleQuotedFragment::Expression(elt) => errors
-                        .append(&mut self.check_expression(storage, ctx, true, false, &elt.data)),
-                }
+        let fragments = match &elt.data {
+            crate::puppet_lang::string::StringVariant::SingleQuoted(_) => &[][..],
+            crate::puppet_lang::string::StringVariant::DoubleQuoted(s) => s.as_slice(),
+            crate::puppet_lang::string::StringVariant::Heredoc(s) => s.data.as_slice(),
+        };
+        for fragment in fragments {
+            match fragment {
+                crate::puppet_lang::string::DoubleQuotedFragment::StringFragment(_) => {}
+                crate::puppet_lang::string::DoubleQuotedFragment::Expression(elt) => errors
+                    .append(&mut self.check_expression(storage, ctx, true, false, &elt.data)),
             }
         }
 
@@ -525,6 +784,11 @@ impl AstLinter {
 
         match &elt.data {
             crate::puppet_lang::typing::TypeSpecificationVariant::ExternalType(elt) => {
+                ctx.register_use(
+                    crate::puppet_pp_lint::repository::Kind::TypeAlias,
+                    &elt.name,
+                    &elt.extra,
+                );
                 for arg in &elt.arguments {
                     errors.append(&mut self.check_expression(storage, ctx, true, false, arg));
                 }
@@ -596,6 +860,95 @@ impl AstLinter {
                     errors.append(&mut self.check_type_specification(storage, ctx, elt))
                 }
             }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Type(
+                crate::puppet_lang::typing::TypeType { inner, .. },
+            )
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Iterable(
+                crate::puppet_lang::typing::TypeIterable { inner, .. },
+            )
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Iterator(
+                crate::puppet_lang::typing::TypeIterator { inner, .. },
+            ) => {
+                if let Some(inner) = inner {
+                    errors.append(&mut self.check_type_specification(storage, ctx, inner))
+                }
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Class(elt) => {
+                for elt in &elt.titles {
+                    errors.append(&mut self.check_expression(storage, ctx, true, false, elt))
+                }
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Resource(elt) => {
+                if let Some(resource_type) = &elt.resource_type {
+                    errors.append(&mut self.check_expression(
+                        storage,
+                        ctx,
+                        true,
+                        false,
+                        resource_type,
+                    ))
+                }
+                for elt in &elt.titles {
+                    errors.append(&mut self.check_expression(storage, ctx, true, false, elt))
+                }
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Callable(elt) => {
+                for elt in &elt.params {
+                    errors.append(&mut self.check_type_specification(storage, ctx, elt))
+                }
+                if let Some(block) = &elt.block {
+                    errors.append(&mut self.check_type_specification(storage, ctx, block))
+                }
+                if let Some(return_type) = &elt.return_type {
+                    errors.append(&mut self.check_type_specification(storage, ctx, return_type))
+                }
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::NotUndef(elt) => {
+                match &elt.value {
+                    Some(crate::puppet_lang::typing::TypeNotUndefVariant::TypeSpecification(
+                        elt,
+                    )) => {
+                        errors.append(&mut self.check_type_specification(storage, ctx, elt));
+                    }
+                    Some(crate::puppet_lang::typing::TypeNotUndefVariant::Term(elt)) => {
+                        errors.append(&mut self.check_term(storage, ctx, false, elt));
+                    }
+                    None => (),
+                }
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Timespan(
+                crate::puppet_lang::typing::TypeTimespan { min, max, .. },
+            )
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Timestamp(
+                crate::puppet_lang::typing::TypeTimestamp { min, max, .. },
+            ) => {
+                for elt in min.iter().chain(max.iter()) {
+                    errors.append(&mut self.check_term(storage, ctx, false, elt));
+                }
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Init(elt) => {
+                if let Some(inner) = &elt.inner {
+                    errors.append(&mut self.check_type_specification(storage, ctx, inner))
+                }
+                for elt in &elt.arguments {
+                    errors.append(&mut self.check_expression(storage, ctx, true, false, elt))
+                }
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::SemVer(
+                crate::puppet_lang::typing::TypeSemVer {
+                    ranges: arguments, ..
+                },
+            )
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Runtime(
+                crate::puppet_lang::typing::TypeRuntime { arguments, .. },
+            )
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Object(
+                crate::puppet_lang::typing::TypeObject { arguments, .. },
+            ) => {
+                for elt in arguments {
+                    errors.append(&mut self.check_expression(storage, ctx, true, false, elt))
+                }
+            }
             crate::puppet_lang::typing::TypeSpecificationVariant::Float(_)
             | crate::puppet_lang::typing::TypeSpecificationVariant::Integer(_)
             | crate::puppet_lang::typing::TypeSpecificationVariant::Numeric(_)
@@ -604,7 +957,13 @@ impl AstLinter {
             | crate::puppet_lang::typing::TypeSpecificationVariant::Regex(_)
             | crate::puppet_lang::typing::TypeSpecificationVariant::Boolean(_)
             | crate::puppet_lang::typing::TypeSpecificationVariant::Undef(_)
-            | crate::puppet_lang::typing::TypeSpecificationVariant::Any(_) => { // TODO
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Any(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Scalar(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Data(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Collection(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::SemVerRange(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Binary(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Deferred(_) => { // TODO
             }
         }
 
@@ -642,6 +1001,9 @@ impl AstLinter {
             crate::puppet_lang::expression::TermVariant::TypeSpecitifaction(elt) => {
                 errors.append(&mut self.check_type_specification(storage, ctx, elt))
             }
+            crate::puppet_lang::expression::TermVariant::ResourceReference(elt) => {
+                errors.append(&mut self.check_resource_reference(storage, ctx, elt))
+            }
             crate::puppet_lang::expression::TermVariant::Float(_)
             | crate::puppet_lang::expression::TermVariant::Integer(_)
             | crate::puppet_lang::expression::TermVariant::Boolean(_)
@@ -664,6 +1026,15 @@ impl AstLinter {
     ) -> Vec<LintError> {
         let mut errors = Vec::new();
 
+        ctx.register_use(
+            crate::puppet_pp_lint::repository::Kind::Function,
+            &elt.identifier.name,
+            &elt.identifier.extra,
+        );
+        if elt.identifier.name == ["contain"] {
+            self.register_class_uses(ctx, &elt.args);
+        }
+
         for arg in &elt.args {
             errors.append(&mut self.check_expression(storage, ctx, true, false, arg));
         }
@@ -674,6 +1045,23 @@ impl AstLinter {
         errors
     }
 
+    /// Registers use sites of classes passed by name to include(), require() and others
+    fn register_class_uses(
+        &self,
+        ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        args: &[crate::puppet_lang::expression::Expression<Range>],
+    ) {
+        for arg in args {
+            if let Some(name) = crate::puppet_tool::expression::string_constant_value(arg) {
+                ctx.register_use(
+                    crate::puppet_pp_lint::repository::Kind::Class,
+                    &[name],
+                    &arg.extra,
+                )
+            }
+        }
+    }
+
     pub fn check_builtin(
         &self,
         storage: &Storage,
@@ -682,6 +1070,14 @@ impl AstLinter {
     ) -> Vec<LintError> {
         let mut errors = Vec::new();
 
+        match elt {
+            crate::puppet_lang::builtin::BuiltinVariant::Require(v)
+            | crate::puppet_lang::builtin::BuiltinVariant::Include(v) => {
+                self.register_class_uses(ctx, &v.args)
+            }
+            _ => (),
+        }
+
         match elt {
             crate::puppet_lang::builtin::BuiltinVariant::Undef => {}
             crate::puppet_lang::builtin::BuiltinVariant::Tag(v)
@@ -689,6 +1085,8 @@ impl AstLinter {
             | crate::puppet_lang::builtin::BuiltinVariant::Realize(v)
             | crate::puppet_lang::builtin::BuiltinVariant::CreateResources(v)
             | crate::puppet_lang::builtin::BuiltinVariant::Template(v)
+            | crate::puppet_lang::builtin::BuiltinVariant::Epp(v)
+            | crate::puppet_lang::builtin::BuiltinVariant::InlineEpp(v)
             | crate::puppet_lang::builtin::BuiltinVariant::Include(v) => {
                 for arg in &v.args {
                     errors.append(&mut self.check_expression(storage, ctx, true, false, arg));
@@ -1114,7 +1512,7 @@ impl AstLinter {
 
         errors.append(&mut self.check_expression(storage, ctx, true, false, &elt.condition));
 
-        errors.append(&mut self.check_statement_set(storage, ctx, elt.body.value.as_ref()));
+        errors.append(&mut self.check_statement_set(storage, ctx, &elt.body));
 
         errors
     }
@@ -1153,11 +1551,7 @@ impl AstLinter {
             &elt.condition.condition,
         ));
 
-        errors.append(&mut self.check_statement_set(
-            storage,
-            ctx,
-            elt.condition.body.value.as_ref(),
-        ));
+        errors.append(&mut self.check_statement_set(storage, ctx, &elt.condition.body));
 
         for elsif_block in &elt.elsif_list {
             errors.append(&mut self.check_expression(
@@ -1172,15 +1566,11 @@ impl AstLinter {
                 ctx,
                 &elsif_block.condition,
             ));
-            errors.append(&mut self.check_statement_set(
-                storage,
-                ctx,
-                elsif_block.body.value.as_ref(),
-            ));
+            errors.append(&mut self.check_statement_set(storage, ctx, &elsif_block.body));
         }
 
         if let Some(else_block) = &elt.else_block {
-            errors.append(&mut self.check_statement_set(storage, ctx, else_block.value.as_ref()));
+            errors.append(&mut self.check_statement_set(storage, ctx, else_block));
         }
 
         errors
@@ -1197,20 +1587,48 @@ impl AstLinter {
             errors.append(&mut lint.inner().check_resource_set(ctx, elt));
         }
 
+        if elt.name.name == ["class"] {
+            for resource in &elt.list.value {
+                self.register_class_uses(ctx, std::slice::from_ref(&resource.title))
+            }
+        } else {
+            ctx.register_use(
+                crate::puppet_pp_lint::repository::Kind::Definition,
+                &elt.name.name,
+                &elt.name.extra,
+            );
+        }
+
         for resource in &elt.list.value {
             errors.append(&mut self.check_expression(storage, ctx, true, false, &resource.title));
             ctx.register_phantom_variable("title");
-            for attribute in &resource.attributes.value {
-                match &attribute.value {
-                    crate::puppet_lang::statement::ResourceAttributeVariant::Name((
-                        _name,
-                        value,
-                    )) => {
-                        errors.append(&mut self.check_expression(storage, ctx, true, false, value))
-                    }
-                    crate::puppet_lang::statement::ResourceAttributeVariant::Group(term) => {
-                        errors.append(&mut self.check_term(storage, ctx, false, term))
-                    }
+            errors.append(&mut self.check_resource_attributes(storage, ctx, &resource.attributes));
+        }
+
+        errors
+    }
+
+    pub fn check_resource_attributes(
+        &self,
+        storage: &Storage,
+        ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        list: &crate::puppet_lang::List<
+            Range,
+            crate::puppet_lang::statement::ResourceAttribute<Range>,
+        >,
+    ) -> Vec<LintError> {
+        ctx.register_suppressions(&list.last_comment);
+
+        let mut errors = Vec::new();
+        for attribute in &list.value {
+            ctx.register_suppressions(&attribute.comment);
+            match &attribute.value {
+                crate::puppet_lang::statement::ResourceAttributeVariant::Name((_name, value))
+                | crate::puppet_lang::statement::ResourceAttributeVariant::Append((_name, value)) => {
+                    errors.append(&mut self.check_expression(storage, ctx, true, false, value))
+                }
+                crate::puppet_lang::statement::ResourceAttributeVariant::Group(term) => {
+                    errors.append(&mut self.check_term(storage, ctx, false, term))
                 }
             }
         }
@@ -1229,8 +1647,70 @@ impl AstLinter {
             errors.append(&mut lint.inner().check_resource_collection(ctx, elt));
         }
 
+        match &elt.type_specification.data {
+            crate::puppet_lang::typing::TypeSpecificationVariant::ExternalType(v) => ctx
+                .register_use(
+                    crate::puppet_pp_lint::repository::Kind::Definition,
+                    &v.name,
+                    &v.extra,
+                ),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Class(v) => {
+                self.register_class_uses(ctx, &v.titles)
+            }
+            _ => (),
+        }
+
         errors.append(&mut self.check_type_specification(storage, ctx, &elt.type_specification));
 
+        if let Some(overrides) = &elt.overrides {
+            errors.append(&mut self.check_resource_attributes(storage, ctx, overrides));
+        }
+
+        errors
+    }
+
+    pub fn check_resource_reference(
+        &self,
+        storage: &Storage,
+        ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        elt: &crate::puppet_lang::expression::ResourceReference<Range>,
+    ) -> Vec<LintError> {
+        let mut errors = Vec::new();
+        for lint in storage.early_pass() {
+            errors.append(&mut lint.inner().check_resource_reference(ctx, elt));
+        }
+
+        if elt.name.len() == 1 && elt.name[0].eq_ignore_ascii_case("class") {
+            self.register_class_uses(ctx, &elt.titles)
+        } else {
+            ctx.register_use(
+                crate::puppet_pp_lint::repository::Kind::Definition,
+                &elt.name,
+                &elt.extra,
+            );
+        }
+
+        for title in &elt.titles {
+            errors.append(&mut self.check_expression(storage, ctx, true, false, title));
+        }
+
+        errors
+    }
+
+    pub fn check_resource_override(
+        &self,
+        storage: &Storage,
+        ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        elt: &crate::puppet_lang::statement::ResourceOverride<Range>,
+    ) -> Vec<LintError> {
+        let mut errors = Vec::new();
+        for lint in storage.early_pass() {
+            errors.append(&mut lint.inner().check_resource_override(ctx, elt));
+        }
+
+        errors.append(&mut self.check_resource_reference(storage, ctx, &elt.reference));
+        errors.append(&mut self.check_resource_attributes(storage, ctx, &elt.attributes));
+
         errors
     }
 
@@ -1319,7 +1799,7 @@ impl AstLinter {
                 }
             }
 
-            errors.append(&mut self.check_statement_set(storage, ctx, &case.body.value));
+            errors.append(&mut self.check_statement_set(storage, ctx, &case.body));
         }
 
         errors
@@ -1329,14 +1809,16 @@ impl AstLinter {
         &self,
         storage: &Storage,
         ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
-        list: &[crate::puppet_lang::statement::Statement<Range>],
+        list: &crate::puppet_lang::List<Range, crate::puppet_lang::statement::Statement<Range>>,
     ) -> Vec<LintError> {
+        ctx.register_suppressions(&list.last_comment);
+
         let mut errors = Vec::new();
-        for statement in list {
+        for statement in &list.value {
             errors.append(&mut self.check_statement(storage, ctx, statement));
         }
         for lint in storage.early_pass() {
-            errors.append(&mut lint.inner().check_statement_set(ctx, list));
+            errors.append(&mut lint.inner().check_statement_set(ctx, &list.value));
         }
 
         errors
@@ -1367,6 +1849,7 @@ impl AstLinter {
         statement: &crate::puppet_lang::statement::Statement<Range>,
     ) -> Vec<LintError> {
         let ctx = ctx.add_path(crate::puppet_pp_lint::ctx::Path::Statement(statement));
+        ctx.register_suppressions(&statement.comment);
 
         let mut errors = Vec::new();
         for lint in storage.early_pass() {
@@ -1394,6 +1877,12 @@ impl AstLinter {
             StatementVariant::ResourceDefaults(elt) => {
                 errors.append(&mut self.check_deprecated_resource_defaults(storage, &ctx, elt))
             }
+            StatementVariant::ResourceOverride(elt) => {
+                errors.append(&mut self.check_resource_override(storage, &ctx, elt))
+            }
+            StatementVariant::SyntaxError(_) => {
+                // Reported by parser
+            }
         };
 
         errors
@@ -1403,15 +1892,16 @@ impl AstLinter {
         &self,
         storage: &Storage,
         ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
-        arguments: &[crate::puppet_lang::argument::Argument<Range>],
-        body: &[crate::puppet_lang::statement::Statement<Range>],
+        arguments: &crate::puppet_lang::List<Range, crate::puppet_lang::argument::Argument<Range>>,
+        body: &crate::puppet_lang::List<Range, crate::puppet_lang::statement::Statement<Range>>,
     ) -> Vec<LintError> {
         let ctx = ctx.new_scope();
         ctx.register_phantom_variable("name");
         ctx.register_phantom_variable("title");
+        ctx.register_suppressions(&arguments.last_comment);
 
         let mut errors = Vec::new();
-        for arg in arguments {
+        for arg in &arguments.value {
             errors.append(&mut self.check_argument(storage, &ctx, arg));
         }
 
@@ -1434,12 +1924,7 @@ impl AstLinter {
         for lint in storage.early_pass() {
             errors.append(&mut lint.inner().check_class(elt));
         }
-        errors.append(&mut self.check_toplevel_variant(
-            storage,
-            ctx,
-            &elt.arguments.value,
-            &elt.body.value,
-        ));
+        errors.append(&mut self.check_toplevel_variant(storage, ctx, &elt.arguments, &elt.body));
 
         errors
     }
@@ -1451,6 +1936,7 @@ impl AstLinter {
         elt: &crate::puppet_lang::argument::Argument<Range>,
     ) -> Vec<LintError> {
         let ctx = ctx.add_path(crate::puppet_pp_lint::ctx::Path::Argument(elt));
+        ctx.register_suppressions(&elt.comment);
 
         let mut errors = Vec::new();
         for lint in storage.early_pass() {
@@ -1474,12 +1960,18 @@ impl AstLinter {
         ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
         elt: &crate::puppet_lang::expression::Lambda<Range>,
     ) -> Vec<LintError> {
-        let ctx = ctx.clone();
+        let ctx = ctx.new_lambda_scope();
+        ctx.register_suppressions(&elt.args.last_comment);
+
         let mut errors = Vec::new();
         for arg in &elt.args.value {
             errors.append(&mut self.check_argument(storage, &ctx, arg))
         }
-        errors.append(&mut self.check_statement_set(storage, &ctx, &elt.body.value));
+        errors.append(&mut self.check_statement_set(storage, &ctx, &elt.body));
+
+        for lint in storage.early_pass() {
+            errors.append(&mut lint.inner().check_ctx(&ctx));
+        }
 
         errors
     }
@@ -1495,12 +1987,7 @@ impl AstLinter {
             errors.append(&mut lint.inner().check_definition(elt));
         }
 
-        errors.append(&mut self.check_toplevel_variant(
-            storage,
-            ctx,
-            &elt.arguments.value,
-            &elt.body.value,
-        ));
+        errors.append(&mut self.check_toplevel_variant(storage, ctx, &elt.arguments, &elt.body));
 
         errors
     }
@@ -1516,11 +2003,27 @@ impl AstLinter {
             errors.append(&mut lint.inner().check_plan(elt));
         }
 
+        errors.append(&mut self.check_toplevel_variant(storage, ctx, &elt.arguments, &elt.body));
+
+        errors
+    }
+
+    pub fn check_node(
+        &self,
+        storage: &Storage,
+        ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        elt: &crate::puppet_lang::toplevel::Node<Range>,
+    ) -> Vec<LintError> {
+        let mut errors = Vec::new();
+        for lint in storage.early_pass() {
+            errors.append(&mut lint.inner().check_node(elt));
+        }
+
         errors.append(&mut self.check_toplevel_variant(
             storage,
             ctx,
-            &elt.arguments.value,
-            &elt.body.value,
+            &crate::puppet_lang::List::default(),
+            &elt.body,
         ));
 
         errors
@@ -1552,12 +2055,7 @@ impl AstLinter {
             errors.append(&mut lint.inner().check_functiondef(elt));
         }
 
-        errors.append(&mut self.check_toplevel_variant(
-            storage,
-            &ctx,
-            &elt.arguments.value,
-            &elt.body.value,
-        ));
+        errors.append(&mut self.check_toplevel_variant(storage, &ctx, &elt.arguments, &elt.body));
 
         for lint in storage.early_pass() {
             errors.append(&mut lint.inner().check_ctx(&ctx));
@@ -1576,6 +2074,46 @@ impl AstLinter {
 
         let ctx = ctx.add_path(crate::puppet_pp_lint::ctx::Path::Toplevel(elt));
 
+        match &elt.data {
+            crate::puppet_lang::toplevel::ToplevelVariant::Class(v) => {
+                ctx.register_definition(
+                    crate::puppet_pp_lint::repository::Kind::Class,
+                    &v.identifier.name,
+                    &v.identifier.extra,
+                );
+                if let Some(inherits) = &v.inherits {
+                    ctx.register_use(
+                        crate::puppet_pp_lint::repository::Kind::Class,
+                        &inherits.name,
+                        &inherits.extra,
+                    );
+                }
+            }
+            crate::puppet_lang::toplevel::ToplevelVariant::Definition(v) => ctx
+                .register_definition(
+                    crate::puppet_pp_lint::repository::Kind::Definition,
+                    &v.identifier.name,
+                    &v.identifier.extra,
+                ),
+            crate::puppet_lang::toplevel::ToplevelVariant::Plan(v) => ctx.register_definition(
+                crate::puppet_pp_lint::repository::Kind::Plan,
+                &v.identifier.name,
+                &v.identifier.extra,
+            ),
+            crate::puppet_lang::toplevel::ToplevelVariant::TypeDef(v) => ctx.register_definition(
+                crate::puppet_pp_lint::repository::Kind::TypeAlias,
+                &v.identifier.name,
+                &v.identifier.extra,
+            ),
+            crate::puppet_lang::toplevel::ToplevelVariant::FunctionDef(v) => ctx
+                .register_definition(
+                    crate::puppet_pp_lint::repository::Kind::Function,
+                    &v.identifier.name,
+                    &v.identifier.extra,
+                ),
+            crate::puppet_lang::toplevel::ToplevelVariant::Node(_) => (),
+        }
+
         match &elt.data {
             crate::puppet_lang::toplevel::ToplevelVariant::Class(elt) => {
                 errors.append(&mut self.check_class(storage, &ctx, elt))
@@ -1592,6 +2130,9 @@ impl AstLinter {
             crate::puppet_lang::toplevel::ToplevelVariant::FunctionDef(elt) => {
                 errors.append(&mut self.check_functiondef(storage, &ctx, elt))
             }
+            crate::puppet_lang::toplevel::ToplevelVariant::Node(elt) => {
+                errors.append(&mut self.check_node(storage, &ctx, elt))
+            }
         }
         for lint in storage.early_pass() {
             errors.append(&mut lint.inner().check_toplevel(&ctx, elt))
@@ -1600,3 +2141,90 @@ impl AstLinter {
         errors
     }
 }
+
+#[test]
+fn test_trailing_comment_suppressions() {
+    let (_, statements) =
+        crate::puppet_parser::toplevel::parse_file(crate::puppet_parser::Span::new_extra(
+            "# @summary Test
+#
+# @param port Port
+class test (
+  $port = 80, # shadowplay:disable-line=ArgumentTyped
+) {
+  file { '/tmp/x':
+    ensure => file,
+    mode   => 644, # shadowplay:disable-line=FileModeAttributeIsString,MagicNumber
+  }
+  notice($port)
+  $unused = 1 # shadowplay:disable-line=UnusedVariables
+}
+",
+            &Default::default(),
+        ))
+        .unwrap();
+
+    let mut ctx = crate::puppet_pp_lint::ctx::Ctx::new(std::path::Path::new("."));
+    ctx.new_file(std::path::Path::new("init.pp"));
+    let errors = AstLinter.check_file(&Storage::default(), &ctx, &statements);
+    let messages: Vec<_> = errors.iter().map(|err| err.message.as_str()).collect();
+    assert_eq!(messages, Vec::<&str>::new());
+}
+
+#[test]
+fn test_labels_and_notes() {
+    let state = crate::puppet_parser::ParserState::default();
+    let (_, statements) =
+        crate::puppet_parser::toplevel::parse_file(crate::puppet_parser::Span::new_extra(
+            "class test (
+  String $port,
+  String $port,
+) {
+  notice($port)
+  [1].each |Integer $port| {
+    notice(1)
+  }
+}
+",
+            &state,
+        ))
+        .unwrap();
+
+    let mut ctx = crate::puppet_pp_lint::ctx::Ctx::new(std::path::Path::new("."));
+    ctx.new_file(std::path::Path::new("init.pp"));
+    let errors: Vec<_> = AstLinter
+        .check_file(&Storage::default(), &ctx, &statements)
+        .into_iter()
+        .map(|err| {
+            (
+                err.location.start().line(),
+                err.message,
+                err.labels
+                    .iter()
+                    .map(|v| (v.location.start().line(), v.message.clone()))
+                    .collect::<Vec<_>>(),
+                err.notes,
+            )
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            (
+                3,
+                "Argument 'port' was already defined earlier at line 2".to_owned(),
+                vec![(2, "first defined here".to_owned())],
+                vec![]
+            ),
+            (
+                6,
+                "Argument 'port' is never used [EXPERIMENTAL]".to_owned(),
+                vec![(3, "shadows variable defined here".to_owned())],
+                vec![
+                    "Lambda parameters are positional, only trailing unused ones can be removed"
+                        .to_owned()
+                ]
+            ),
+        ]
+    );
+}
@@ -94,3 +94,186 @@ impl EarlyLintPass for ErbReferencesToUnknownVariable {
         errors
     }
 }
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EppParametersMismatch;
+
+impl LintPass for EppParametersMismatch {
+    fn name(&self) -> &str {
+        "EppParametersMismatch"
+    }
+    fn description(&self) -> &str {
+        "Checks parameters passed to epp() and inline_epp() against parameters declared in template"
+    }
+}
+
+impl EppParametersMismatch {
+    fn check_args(
+        &self,
+        template_name: &str,
+        template: &crate::puppet_pp_lint::ctx::epp_template::Template,
+        args: Option<&crate::puppet_lang::expression::Expression<Range>>,
+        range: &Range,
+    ) -> Vec<LintError> {
+        let parameters = match &template.parameters {
+            Some(v) => v,
+            // Template without parameters tag accepts anything
+            None => return Vec::new(),
+        };
+
+        let kv_list = match args.map(|v| &v.value) {
+            None => Vec::new(),
+            Some(ExpressionVariant::Term(crate::puppet_lang::expression::Term {
+                value: TermVariant::Map(v),
+                ..
+            })) => v.value.value.iter().collect(),
+            // Not a literal hash, nothing to check
+            Some(_) => return Vec::new(),
+        };
+
+        let mut errors = Vec::new();
+        let mut passed = std::collections::HashSet::new();
+        let mut all_keys_known = true;
+
+        for kv in kv_list {
+            let key = match &kv.key.value {
+                ExpressionVariant::Term(crate::puppet_lang::expression::Term {
+                    value: TermVariant::String(v),
+                    ..
+                }) => crate::puppet_tool::string::constant_value(v),
+                _ => None,
+            };
+            let key = match key {
+                Some(v) => v,
+                None => {
+                    all_keys_known = false;
+                    continue;
+                }
+            };
+
+            let parameter = match template.parameter(&key) {
+                Some(v) => v,
+                None => {
+                    errors.push(LintError::new(
+                        Box::new(self.clone()),
+                        &format!("{} has no parameter {:?}", template_name, key),
+                        &kv.key.extra,
+                    ));
+                    continue;
+                }
+            };
+
+            if let Some(type_spec) = &parameter.type_spec {
+                if crate::puppet_pp_lint::tool::typing::literal_matches(type_spec, &kv.value)
+                    == Some(false)
+                {
+                    errors.push(LintError::new(
+                        Box::new(self.clone()),
+                        &format!(
+                            "Value of parameter {:?} does not match its type declared in {}",
+                            key, template_name
+                        ),
+                        &kv.value.extra,
+                    ));
+                }
+            }
+
+            let _ = passed.insert(key);
+        }
+
+        if !all_keys_known {
+            return errors;
+        }
+
+        for parameter in parameters {
+            if parameter.default.is_some() || passed.contains(&parameter.name) {
+                continue;
+            }
+            if let Some(type_spec) = &parameter.type_spec {
+                if crate::puppet_pp_lint::tool::typing::accepts_undef(type_spec) {
+                    continue;
+                }
+            }
+            errors.push(LintError::new(
+                Box::new(self.clone()),
+                &format!(
+                    "Required parameter {:?} of {} is not passed",
+                    parameter.name, template_name
+                ),
+                range,
+            ));
+        }
+
+        errors
+    }
+}
+
+impl EarlyLintPass for EppParametersMismatch {
+    fn check_expression(
+        &self,
+        ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        _is_toplevel_expr: bool,
+        elt: &crate::puppet_lang::expression::Expression<Range>,
+    ) -> Vec<super::lint::LintError> {
+        let builtin = if let ExpressionVariant::BuiltinFunction(v) = &elt.value {
+            v
+        } else {
+            return Vec::new();
+        };
+
+        let (is_inline, args) = match builtin {
+            BuiltinVariant::Epp(v) => (false, &v.args),
+            BuiltinVariant::InlineEpp(v) => (true, &v.args),
+            _ => return Vec::new(),
+        };
+
+        let source = match args.first().map(|v| &v.value) {
+            Some(ExpressionVariant::Term(crate::puppet_lang::expression::Term {
+                value: TermVariant::String(v),
+                ..
+            })) => crate::puppet_tool::string::constant_value(v),
+            _ => None,
+        };
+        let source = match source {
+            Some(v) => v,
+            None => return Vec::new(),
+        };
+
+        if is_inline {
+            let template = match crate::puppet_pp_lint::ctx::epp_template::Template::parse(&source)
+            {
+                Some(v) => v,
+                None => {
+                    return vec![LintError::new(
+                        Box::new(self.clone()),
+                        "Failed to parse inline EPP template",
+                        &elt.extra,
+                    )]
+                }
+            };
+            return self.check_args("inline EPP template", &template, args.get(1), &elt.extra);
+        }
+
+        let template = ctx.epp_of_path(&source);
+        let template = match template.as_ref() {
+            Some(v) => v,
+            None => {
+                return vec![LintError::new(
+                    Box::new(self.clone()),
+                    &format!(
+                        "EPP template {:?} does not exist or failed to parse",
+                        source
+                    ),
+                    &elt.extra,
+                )]
+            }
+        };
+
+        self.check_args(
+            &format!("EPP template {:?}", source),
+            template,
+            args.get(1),
+            &elt.extra,
+        )
+    }
+}
@@ -41,26 +41,79 @@ impl LintPass for DefaultCaseIsNotLast {
     }
 }
 
+fn is_default_case(elt: &crate::puppet_lang::statement::CaseElement<Range>) -> bool {
+    elt.matches
+        .iter()
+        .any(|elt| matches!(elt, crate::puppet_lang::expression::CaseVariant::Default(_)))
+}
+
+/// Range of case element including its leading comments
+fn case_range(elt: &crate::puppet_lang::statement::CaseElement<Range>) -> Range {
+    match elt.comment.first() {
+        Some(comment) => Range::from((&comment.extra, &elt.extra)),
+        None => elt.extra.clone(),
+    }
+}
+
+impl DefaultCaseIsNotLast {
+    /// Moves default case to the end of list
+    fn edits(
+        list: &[crate::puppet_lang::statement::CaseElement<Range>],
+    ) -> Vec<crate::puppet_pp_lint::fix::TextEdit> {
+        let defaults: Vec<_> = list.iter().enumerate().filter(|(_, v)| is_default_case(v)).collect();
+        let (pos, default) = match defaults.as_slice() {
+            [v] => *v,
+            _ => return Vec::new(),
+        };
+        let (next, last) = match (list.get(pos + 1), list.last()) {
+            (Some(next), Some(last)) => (next, last),
+            _ => return Vec::new(),
+        };
+
+        let default_range = case_range(default);
+        let indent = " ".repeat(default_range.start().column().saturating_sub(1));
+
+        vec![
+            crate::puppet_pp_lint::fix::TextEdit::new(
+                default_range.start().offset(),
+                case_range(next).start().offset(),
+                crate::puppet_pp_lint::fix::Replacement::Text(String::new()),
+            ),
+            crate::puppet_pp_lint::fix::TextEdit::insert(
+                last.extra.end().offset() + 1,
+                &format!("\n{}", indent),
+            ),
+            crate::puppet_pp_lint::fix::TextEdit::insert_source(
+                last.extra.end().offset() + 1,
+                &default_range,
+            ),
+        ]
+    }
+}
+
 impl EarlyLintPass for DefaultCaseIsNotLast {
     fn check_case_statement(&self, elt: &crate::puppet_lang::statement::Case<Range>) -> Vec<LintError> {
         let mut default = None;
         let mut errors = Vec::new();
         for case in &elt.elements.value {
-            if case
-                .matches
-                .iter()
-                .any(|elt| matches!(elt, crate::puppet_lang::expression::CaseVariant::Default(_)))
-            {
+            if is_default_case(case) {
                 default = Some(case)
             } else if let Some(default) = default {
-                errors.push(LintError::new(
+                let error = LintError::new(
                     Box::new(self.clone()),
                     &format!(
                         "Match case after default match which is defined earlier at line {}",
                         default.extra.start().line()
                     ),
                     &elt.extra,
-                ))
+                );
+                // Single fix moves default case for all the errors
+                let error = if errors.is_empty() {
+                    error.with_edits(Self::edits(&elt.elements.value))
+                } else {
+                    error
+                };
+                errors.push(error)
             }
         }
 
@@ -91,14 +144,17 @@ impl EarlyLintPass for MultipleDefaultCase {
                 .any(|elt| matches!(elt, crate::puppet_lang::expression::CaseVariant::Default(_)))
             {
                 if let Some(default) = default {
-                    errors.push(LintError::new(
-                        Box::new(self.clone()),
-                        &format!(
-                            "Default match case is already defined at line {}",
-                            default.extra.start().line()
-                        ),
-                        &elt.extra,
-                    ))
+                    errors.push(
+                        LintError::new(
+                            Box::new(self.clone()),
+                            &format!(
+                                "Default match case is already defined at line {}",
+                                default.extra.start().line()
+                            ),
+                            &case.extra,
+                        )
+                        .with_label("first default case", &default.extra),
+                    )
                 }
                 default = Some(case)
             }
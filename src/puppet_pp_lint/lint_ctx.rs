@@ -28,21 +28,41 @@ impl EarlyLintPass for UnusedVariables {
                 continue;
             }
 
+            if ctx.is_outer_variable(varname, variable) {
+                // Reported in scope where it was defined
+                continue;
+            }
+
             match &variable.variant {
                 crate::puppet_pp_lint::ctx::VariableVariant::Builtin => (),
                 crate::puppet_pp_lint::ctx::VariableVariant::Defined(variable) => {
-                    errors.push(LintError::new(
-                        Box::new(self.clone()),
-                        &format!("Variable '{}' is never used [EXPERIMENTAL]", varname),
-                        &variable.extra,
+                    errors.push(with_shadowed_label(
+                        ctx,
+                        varname,
+                        LintError::new(
+                            Box::new(self.clone()),
+                            &format!("Variable '{}' is never used [EXPERIMENTAL]", varname),
+                            &variable.extra,
+                        ),
                     ))
                 }
                 crate::puppet_pp_lint::ctx::VariableVariant::Argument(arg) => {
-                    errors.push(LintError::new(
+                    if ctx.outer_variables.is_some() && has_used_argument_after(ctx, arg) {
+                        // Lambda parameters are positional, so |$key, $value| is required
+                        // even if only $value is used
+                        continue;
+                    }
+                    let mut error = LintError::new(
                         Box::new(self.clone()),
                         &format!("Argument '{}' is never used [EXPERIMENTAL]", varname),
                         &arg.extra,
-                    ))
+                    );
+                    if ctx.outer_variables.is_some() {
+                        error = error.with_note(
+                            "Lambda parameters are positional, only trailing unused ones can be removed",
+                        )
+                    }
+                    errors.push(with_shadowed_label(ctx, varname, error))
                 }
                 crate::puppet_pp_lint::ctx::VariableVariant::Phantom => (),
             }
@@ -51,3 +71,43 @@ impl EarlyLintPass for UnusedVariables {
         errors
     }
 }
+
+/// Points to variable of outer scope which is hidden by the unused one
+fn with_shadowed_label(
+    ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+    varname: &str,
+    error: LintError,
+) -> LintError {
+    let outer_variables = match &ctx.outer_variables {
+        Some(v) => v.borrow(),
+        None => return error,
+    };
+    let outer = match outer_variables.get(varname) {
+        Some(v) => v,
+        None => return error,
+    };
+    let location = match &outer.variant {
+        crate::puppet_pp_lint::ctx::VariableVariant::Defined(v) => &v.extra,
+        crate::puppet_pp_lint::ctx::VariableVariant::Argument(v) => &v.extra,
+        crate::puppet_pp_lint::ctx::VariableVariant::Builtin
+        | crate::puppet_pp_lint::ctx::VariableVariant::Phantom => return error,
+    };
+    error.with_label("shadows variable defined here", location)
+}
+
+fn has_used_argument_after(
+    ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+    arg: &crate::puppet_lang::argument::Argument<Range>,
+) -> bool {
+    ctx.variables
+        .borrow()
+        .iter()
+        .any(|(varname, variable)| match &variable.variant {
+            crate::puppet_pp_lint::ctx::VariableVariant::Argument(other) => {
+                !ctx.is_outer_variable(varname, variable)
+                    && other.extra.start().offset() > arg.extra.start().offset()
+                    && *variable.use_count.borrow() > 0
+            }
+            _ => false,
+        })
+}
@@ -0,0 +1,447 @@
+use crate::puppet_parser::range::Range;
+use serde::{Deserialize, Serialize};
+
+use crate::puppet_lang::expression::{ExpressionVariant, TermVariant};
+use crate::puppet_pp_lint::lint::{EarlyLintPass, LintError, LintPass, Severity};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AttributeSelector {
+    #[serde(with = "serde_regex")]
+    pub name: regex::Regex,
+    /// Matched against constant value of attribute. Non-constant values never match.
+    #[serde(default, with = "serde_regex")]
+    pub value: Option<regex::Regex>,
+}
+
+/// What custom lint looks for. Regular expressions are not anchored.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Selector {
+    /// Resource declaration
+    Resource {
+        #[serde(rename = "type", with = "serde_regex")]
+        resource_type: regex::Regex,
+        /// Matched against constant title. Resources with non-constant titles never match.
+        #[serde(default, with = "serde_regex")]
+        title: Option<regex::Regex>,
+        /// Resource has matching attribute
+        #[serde(default)]
+        attribute: Option<AttributeSelector>,
+        /// Resource has no attribute with this name
+        #[serde(default)]
+        missing_attribute: Option<String>,
+    },
+    /// Function call
+    Function {
+        #[serde(with = "serde_regex")]
+        name: regex::Regex,
+    },
+    /// Variable reference. Names of toplevel variables start with '::'.
+    Variable {
+        #[serde(with = "serde_regex")]
+        name: regex::Regex,
+    },
+}
+
+fn default_severity() -> Severity {
+    Severity::Error
+}
+
+/// Lint defined in config
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Custom {
+    pub name: String,
+    pub message: String,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    #[serde(default)]
+    pub url: Option<String>,
+    pub selector: Selector,
+}
+
+impl LintPass for Custom {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn description(&self) -> &str {
+        &self.message
+    }
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+}
+
+/// Value of attribute if it is known at compile time
+fn constant_text(expr: &crate::puppet_lang::expression::Expression<Range>) -> Option<String> {
+    if let Some(v) = crate::puppet_tool::expression::string_constant_value(expr) {
+        return Some(v);
+    }
+    match &expr.value {
+        ExpressionVariant::Term(term) => match &term.value {
+            TermVariant::Identifier(v) => Some(v.name.join("::")),
+            TermVariant::Boolean(v) => Some(v.value.to_string()),
+            TermVariant::Integer(v) => Some(v.lexeme.clone()),
+            TermVariant::Float(v) => Some(v.lexeme.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn builtin_name(elt: &crate::puppet_lang::builtin::BuiltinVariant<Range>) -> Option<&'static str> {
+    use crate::puppet_lang::builtin::BuiltinVariant;
+    match elt {
+        BuiltinVariant::Undef => None,
+        BuiltinVariant::Return(_) => Some("return"),
+        BuiltinVariant::Template(_) => Some("template"),
+        BuiltinVariant::Epp(_) => Some("epp"),
+        BuiltinVariant::InlineEpp(_) => Some("inline_epp"),
+        BuiltinVariant::Tag(_) => Some("tag"),
+        BuiltinVariant::Require(_) => Some("require"),
+        BuiltinVariant::Include(_) => Some("include"),
+        BuiltinVariant::Realize(_) => Some("realize"),
+        BuiltinVariant::CreateResources(_) => Some("create_resources"),
+    }
+}
+
+impl Custom {
+    fn error(&self, location: &Range) -> LintError {
+        match &self.url {
+            Some(url) => {
+                LintError::new_with_url(Box::new(self.clone()), &self.message, url, location)
+            }
+            None => LintError::new(Box::new(self.clone()), &self.message, location),
+        }
+    }
+
+    /// Returns location of match
+    fn match_resource(
+        &self,
+        resource: &crate::puppet_lang::statement::Resource<Range>,
+        title: &Option<regex::Regex>,
+        attribute: &Option<AttributeSelector>,
+        missing_attribute: &Option<String>,
+    ) -> Option<Range> {
+        use crate::puppet_lang::statement::ResourceAttributeVariant;
+
+        if let Some(title) = title {
+            if !title.is_match(&crate::puppet_tool::expression::string_constant_value(
+                &resource.title,
+            )?) {
+                return None;
+            }
+        }
+
+        let attributes: Vec<_> = resource
+            .attributes
+            .value
+            .iter()
+            .filter_map(|attr| match &attr.value {
+                ResourceAttributeVariant::Name((name, value))
+                | ResourceAttributeVariant::Append((name, value)) => Some((name, value)),
+                ResourceAttributeVariant::Group(_) => None,
+            })
+            .collect();
+
+        if let Some(missing_attribute) = missing_attribute {
+            let has_group = resource
+                .attributes
+                .value
+                .iter()
+                .any(|attr| matches!(attr.value, ResourceAttributeVariant::Group(_)));
+            // Attributes passed with '* => $hash' are unknown
+            if has_group
+                || attributes
+                    .iter()
+                    .any(|(name, _)| &name.data == missing_attribute)
+            {
+                return None;
+            }
+        }
+
+        match attribute {
+            None => Some(resource.extra.clone()),
+            Some(selector) => attributes.iter().find_map(|(name, value)| {
+                if !selector.name.is_match(&name.data) {
+                    return None;
+                }
+                match &selector.value {
+                    None => Some(name.extra.clone()),
+                    Some(regex) => match constant_text(value) {
+                        Some(text) if regex.is_match(&text) => Some(value.extra.clone()),
+                        _ => None,
+                    },
+                }
+            }),
+        }
+    }
+}
+
+impl EarlyLintPass for Custom {
+    fn check_resource_set(
+        &self,
+        _ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        elt: &crate::puppet_lang::statement::ResourceSet<Range>,
+    ) -> Vec<LintError> {
+        let (resource_type, title, attribute, missing_attribute) = match &self.selector {
+            Selector::Resource {
+                resource_type,
+                title,
+                attribute,
+                missing_attribute,
+            } => (resource_type, title, attribute, missing_attribute),
+            _ => return Vec::new(),
+        };
+
+        if !resource_type.is_match(&elt.name.name.join("::")) {
+            return Vec::new();
+        }
+
+        elt.list
+            .value
+            .iter()
+            .filter_map(|resource| {
+                self.match_resource(resource, title, attribute, missing_attribute)
+            })
+            .map(|location| self.error(&location))
+            .collect()
+    }
+
+    fn check_expression(
+        &self,
+        _ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        _is_toplevel_expr: bool,
+        elt: &crate::puppet_lang::expression::Expression<Range>,
+    ) -> Vec<LintError> {
+        let regex = match &self.selector {
+            Selector::Function { name } => name,
+            _ => return Vec::new(),
+        };
+
+        let (name, location) = match &elt.value {
+            ExpressionVariant::FunctionCall(v) => {
+                (v.identifier.name.join("::"), &v.identifier.extra)
+            }
+            ExpressionVariant::ChainCall(v) => (
+                v.right.identifier.name.join("::"),
+                &v.right.identifier.extra,
+            ),
+            ExpressionVariant::BuiltinFunction(v) => match builtin_name(v) {
+                Some(name) => (name.to_string(), &elt.extra),
+                None => return Vec::new(),
+            },
+            _ => return Vec::new(),
+        };
+
+        if regex.is_match(&name) {
+            vec![self.error(location)]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn check_term(
+        &self,
+        _ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        _is_assignment: bool,
+        elt: &crate::puppet_lang::expression::Term<Range>,
+    ) -> Vec<LintError> {
+        let regex = match &self.selector {
+            Selector::Variable { name } => name,
+            _ => return Vec::new(),
+        };
+
+        let variable = match &elt.value {
+            TermVariant::Variable(v) => v,
+            _ => return Vec::new(),
+        };
+
+        let mut name = variable.identifier.name.join("::");
+        if variable.identifier.is_toplevel {
+            name = format!("::{}", name)
+        }
+
+        if regex.is_match(&name) {
+            vec![self.error(&variable.extra)]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+fn test_matches(lint: &str, source: &str) -> Vec<(u32, usize)> {
+    let storage: crate::puppet_pp_lint::lint::Storage =
+        serde_yaml::from_str(&format!("early_pass:\n  - Custom:\n{}", lint)).unwrap();
+    let state = crate::puppet_parser::ParserState::default();
+    let (_, statements) = crate::puppet_parser::toplevel::parse_file(
+        crate::puppet_parser::Span::new_extra(source, &state),
+    )
+    .unwrap();
+    let mut ctx = crate::puppet_pp_lint::ctx::Ctx::new(std::path::Path::new("."));
+    ctx.new_file(std::path::Path::new("init.pp"));
+    crate::puppet_pp_lint::lint::AstLinter
+        .check_file(&storage, &ctx, &statements)
+        .into_iter()
+        .map(|err| {
+            assert_eq!(err.linter.name(), "NoTmp");
+            assert_eq!(err.message, "Do not use it");
+            (err.location.start().line(), err.location.start().column())
+        })
+        .collect()
+}
+
+#[test]
+fn test_deserialize() {
+    let lint: Custom = serde_yaml::from_str(
+        "name: NoTmp
+message: Do not use it
+selector:
+  resource:
+    type: ^file$
+    title: ^/tmp/
+    attribute:
+      name: ^mode$
+      value: '777'
+    missing_attribute: owner
+",
+    )
+    .unwrap();
+    assert_eq!(lint.severity, Severity::Error);
+    assert_eq!(lint.url, None);
+    match &lint.selector {
+        Selector::Resource {
+            resource_type,
+            title,
+            attribute,
+            missing_attribute,
+        } => {
+            assert_eq!(resource_type.as_str(), "^file$");
+            assert_eq!(title.as_ref().unwrap().as_str(), "^/tmp/");
+            let attribute = attribute.as_ref().unwrap();
+            assert_eq!(attribute.name.as_str(), "^mode$");
+            assert_eq!(attribute.value.as_ref().unwrap().as_str(), "777");
+            assert_eq!(missing_attribute.as_deref(), Some("owner"));
+        }
+        _ => panic!("resource selector expected"),
+    }
+
+    let lint: Custom = serde_yaml::from_str(
+        "name: NoTmp
+message: Do not use it
+severity: warning
+url: https://example.com/no-tmp
+selector:
+  variable:
+    name: ^::tmp
+",
+    )
+    .unwrap();
+    assert_eq!(lint.severity, Severity::Warning);
+    assert_eq!(lint.url.as_deref(), Some("https://example.com/no-tmp"));
+    assert!(matches!(lint.selector, Selector::Variable { .. }));
+
+    // Invalid regex and unknown selector
+    assert!(serde_yaml::from_str::<Custom>(
+        "name: NoTmp\nmessage: x\nselector:\n  function:\n    name: '('\n"
+    )
+    .is_err());
+    assert!(serde_yaml::from_str::<Custom>(
+        "name: NoTmp\nmessage: x\nselector:\n  class:\n    name: x\n"
+    )
+    .is_err());
+}
+
+#[test]
+fn test_resource_selector() {
+    let source = "file { '/tmp/a': mode => '0777' }
+file { '/etc/a': mode => '0777' }
+file { \"/tmp/${x}\": mode => '0777' }
+file { '/tmp/b': owner => root, mode => '0644' }
+file { '/tmp/c': * => $attrs }
+package { '/tmp/d': }
+";
+
+    let lint = "      name: NoTmp
+      message: Do not use it
+      selector:
+        resource:
+          type: ^file$
+";
+    assert_eq!(
+        test_matches(&format!("{}          title: ^/tmp/\n", lint), source),
+        vec![(1, 8), (4, 8), (5, 8)]
+    );
+    assert_eq!(
+        test_matches(
+            &format!(
+                "{}          attribute:\n            name: ^mode$\n            value: '7$'\n",
+                lint
+            ),
+            source
+        ),
+        vec![(1, 26), (2, 26), (3, 29)]
+    );
+    // Attribute without value selector points to attribute name
+    assert_eq!(
+        test_matches(
+            &format!("{}          attribute:\n            name: ^owner$\n", lint),
+            source
+        ),
+        vec![(4, 18)]
+    );
+    // Attributes passed with '*' may contain the missing one
+    assert_eq!(
+        test_matches(
+            &format!("{}          missing_attribute: owner\n", lint),
+            source
+        ),
+        vec![(1, 8), (2, 8), (3, 8)]
+    );
+    assert_eq!(
+        test_matches(
+            "      name: NoTmp
+      message: Do not use it
+      selector:
+        resource:
+          type: ^service$
+",
+            source
+        ),
+        Vec::new()
+    );
+}
+
+#[test]
+fn test_function_selector() {
+    let lint = "      name: NoTmp
+      message: Do not use it
+      selector:
+        function:
+          name: ^(hiera|include|tmp)$
+";
+    assert_eq!(
+        test_matches(
+            lint,
+            "$a = hiera('x')\n$b = $a.tmp()\ninclude tmp\n$c = lookup('x')\n$d = hiera_array('x')\n"
+        ),
+        vec![(1, 6), (2, 9), (3, 1)]
+    );
+}
+
+#[test]
+fn test_variable_selector() {
+    let lint = "      name: NoTmp
+      message: Do not use it
+      selector:
+        variable:
+          name: ^(::tmp|local)$
+";
+    assert_eq!(
+        test_matches(
+            lint,
+            "class a {\n  $local = $::tmp\n  notice($tmp, $local, $::tmp_dir)\n}\n"
+        ),
+        vec![(2, 3), (2, 12), (3, 16)]
+    );
+}
@@ -35,11 +35,14 @@ impl UselessParens {
 
         let inner_priority = crate::puppet_pp_lint::tool::expression::priority(&inner.value);
         if outer_priority < inner_priority {
-            errors.push(LintError::new(
-                Box::new(self.clone()),
-                "Parens can be safely removed",
-                parens_extra,
-            ));
+            errors.push(
+                LintError::new(
+                    Box::new(self.clone()),
+                    "Parens can be safely removed",
+                    parens_extra,
+                )
+                .with_edits(Self::edits(elt)),
+            );
             return;
         }
 
@@ -47,14 +50,43 @@ impl UselessParens {
             inner.value.value,
             crate::puppet_lang::expression::ExpressionVariant::Term(_)
         ) {
-            errors.push(LintError::new(
-                Box::new(self.clone()),
-                "Parens around term can be safely removed",
-                parens_extra,
-            ));
+            errors.push(
+                LintError::new(
+                    Box::new(self.clone()),
+                    "Parens around term can be safely removed",
+                    parens_extra,
+                )
+                .with_edits(Self::edits(elt)),
+            );
         }
     }
 
+    /// Removal of parens
+    fn edits(elt: &Expression<Range>) -> Vec<crate::puppet_pp_lint::fix::TextEdit> {
+        let parens = match &elt.value {
+            ExpressionVariant::Term(Term {
+                value: TermVariant::Parens(v),
+                ..
+            }) if elt.accessor.is_none() => v,
+            _ => return Vec::new(),
+        };
+
+        let left = parens.extra.start().offset();
+        let right = parens.extra.end().offset();
+        vec![
+            crate::puppet_pp_lint::fix::TextEdit::new(
+                left,
+                left + 1,
+                crate::puppet_pp_lint::fix::Replacement::Text(String::new()),
+            ),
+            crate::puppet_pp_lint::fix::TextEdit::new(
+                right,
+                right + 1,
+                crate::puppet_pp_lint::fix::Replacement::Text(String::new()),
+            ),
+        ]
+    }
+
     fn inner_check<C>(&self, elt: &Expression<Range>, checker: C) -> bool
     where
         C: Fn(&ExpressionVariant<Range>) -> bool,
@@ -89,36 +121,32 @@ impl EarlyLintPass for UselessParens {
                 self.check(outer_priority, &mut errors, left);
                 self.check(outer_priority, &mut errors, right);
                 if self.inner_check(left, |elt| matches!(elt, ExpressionVariant::And(_))) {
-                    errors.push(LintError::new(
-                        Box::new(self.clone()),
-                        "Parens can be safely removed. '($a and $b) and $c' can be replaced with '$a and $b and $c'",
-                        &left.extra,
-                    ));
+                    errors.push(
+                        LintError::new(Box::new(self.clone()), "Parens can be safely removed. '($a and $b) and $c' can be replaced with '$a and $b and $c'", &left.extra)
+                            .with_edits(Self::edits(left)),
+                    );
                 }
                 if self.inner_check(right, |elt| matches!(elt, ExpressionVariant::And(_))) {
-                    errors.push(LintError::new(
-                        Box::new(self.clone()),
-                        "Parens can be safely removed. '$a and ($b and $c)' can be replaced with '$a and $b and $c'",
-                        &right.extra,
-                    ));
+                    errors.push(
+                        LintError::new(Box::new(self.clone()), "Parens can be safely removed. '$a and ($b and $c)' can be replaced with '$a and $b and $c'", &right.extra)
+                            .with_edits(Self::edits(right)),
+                    );
                 }
             }
             ExpressionVariant::Or((left, right)) => {
                 self.check(outer_priority, &mut errors, left);
                 self.check(outer_priority, &mut errors, right);
                 if self.inner_check(left, |elt| matches!(elt, ExpressionVariant::Or(_))) {
-                    errors.push(LintError::new(
-                        Box::new(self.clone()),
-                        "Parens can be safely removed. '($a or $b) or $c' can be replaced with '$a or $b or $c'",
-                        &left.extra,
-                    ));
+                    errors.push(
+                        LintError::new(Box::new(self.clone()), "Parens can be safely removed. '($a or $b) or $c' can be replaced with '$a or $b or $c'", &left.extra)
+                            .with_edits(Self::edits(left)),
+                    );
                 }
                 if self.inner_check(right, |elt| matches!(elt, ExpressionVariant::Or(_))) {
-                    errors.push(LintError::new(
-                        Box::new(self.clone()),
-                        "Parens can be safely removed. '$a or ($b or $c)' can be replaced with '$a or $b or $c'",
-                        &right.extra,
-                    ));
+                    errors.push(
+                        LintError::new(Box::new(self.clone()), "Parens can be safely removed. '$a or ($b or $c)' can be replaced with '$a or $b or $c'", &right.extra)
+                            .with_edits(Self::edits(right)),
+                    );
                 }
             }
             ExpressionVariant::Equal((left, right)) => {
@@ -161,22 +189,20 @@ impl EarlyLintPass for UselessParens {
                         || matches!(elt, ExpressionVariant::Minus(_))
                 });
                 if eq {
-                    errors.push(LintError::new(
-                        Box::new(self.clone()),
-                        "Parens can be safely removed. '($a + $b) + $c' can be replaced with '$a + $b + $c'",
-                        &left.extra,
-                    ));
+                    errors.push(
+                        LintError::new(Box::new(self.clone()), "Parens can be safely removed. '($a + $b) + $c' can be replaced with '$a + $b + $c'", &left.extra)
+                            .with_edits(Self::edits(left)),
+                    );
                 }
                 let eq = self.inner_check(right, |elt| {
                     matches!(elt, ExpressionVariant::Plus(_))
                         || matches!(elt, ExpressionVariant::Minus(_))
                 });
                 if eq {
-                    errors.push(LintError::new(
-                        Box::new(self.clone()),
-                        "Parens can be safely removed. '$a + ($b + $c)' can be replaced with '$a + $b + $c'",
-                        &right.extra,
-                    ));
+                    errors.push(
+                        LintError::new(Box::new(self.clone()), "Parens can be safely removed. '$a + ($b + $c)' can be replaced with '$a + $b + $c'", &right.extra)
+                            .with_edits(Self::edits(right)),
+                    );
                 }
             }
             ExpressionVariant::Multiply((left, right))
@@ -188,22 +214,20 @@ impl EarlyLintPass for UselessParens {
                         || matches!(elt, ExpressionVariant::Divide(_))
                 });
                 if eq {
-                    errors.push(LintError::new(
-                        Box::new(self.clone()),
-                        "Parens can be safely removed. '($a * $b) * $c' can be replaced with '$a * $b * $c'",
-                        &left.extra,
-                    ));
+                    errors.push(
+                        LintError::new(Box::new(self.clone()), "Parens can be safely removed. '($a * $b) * $c' can be replaced with '$a * $b * $c'", &left.extra)
+                            .with_edits(Self::edits(left)),
+                    );
                 }
                 let eq = self.inner_check(right, |elt| {
                     matches!(elt, ExpressionVariant::Multiply(_))
                         || matches!(elt, ExpressionVariant::Divide(_))
                 });
                 if eq {
-                    errors.push(LintError::new(
-                        Box::new(self.clone()),
-                        "Parens can be safely removed. '$a * ($b * $c)' can be replaced with '$a * $b * $c'",
-                        &right.extra,
-                    ));
+                    errors.push(
+                        LintError::new(Box::new(self.clone()), "Parens can be safely removed. '$a * ($b * $c)' can be replaced with '$a * $b * $c'", &right.extra)
+                            .with_edits(Self::edits(right)),
+                    );
                 }
             }
             ExpressionVariant::Modulo((left, right)) => {
@@ -240,15 +264,25 @@ impl EarlyLintPass for UselessParens {
             ExpressionVariant::BuiltinFunction(_) => {
                 // no inner elements available
             }
-            ExpressionVariant::Term(elt) => {
-                if let TermVariant::Parens(Parens { value: inner, .. }) = &elt.value {
+            ExpressionVariant::Term(outer) => {
+                if let TermVariant::Parens(Parens { value: inner, .. }) = &outer.value {
                     if let ExpressionVariant::Term(elt) = &inner.value {
                         if let TermVariant::Parens(_) = &elt.value {
-                            errors.push(LintError::new(
-                                Box::new(self.clone()),
-                                "Double parens. Can be safely removed.",
-                                &elt.extra,
-                            ));
+                            // Replacing whole outer parens keeps fix from conflicting with
+                            // removal of the outer parens
+                            errors.push(
+                                LintError::new(
+                                    Box::new(self.clone()),
+                                    "Double parens. Can be safely removed.",
+                                    &elt.extra,
+                                )
+                                .with_edits(vec![
+                                    crate::puppet_pp_lint::fix::TextEdit::replace_with_source(
+                                        &outer.extra,
+                                        &elt.extra,
+                                    ),
+                                ]),
+                            );
                         }
                     }
                 }
@@ -261,11 +295,14 @@ impl EarlyLintPass for UselessParens {
                     t.value,
                     crate::puppet_lang::expression::TermVariant::Parens(_)
                 ) {
-                    errors.push(LintError::new(
-                        Box::new(self.clone()),
-                        "Toplevel parens can be safely removed",
-                        &elt.extra,
-                    ));
+                    errors.push(
+                        LintError::new(
+                            Box::new(self.clone()),
+                            "Toplevel parens can be safely removed",
+                            &elt.extra,
+                        )
+                        .with_edits(Self::edits(elt)),
+                    );
                 }
             }
         }
@@ -298,12 +335,15 @@ impl EarlyLintPass for DoubleNegation {
             _ => return Vec::new(),
         };
 
-        if matches!(inner.value, ExpressionVariant::Not(_)) {
-            return vec![LintError::new(
-                Box::new(self.clone()),
-                "Double negation",
-                &elt.extra,
-            )];
+        if let ExpressionVariant::Not(inner) = &inner.value {
+            return vec![
+                LintError::new(Box::new(self.clone()), "Double negation", &elt.extra).with_edits(
+                    vec![crate::puppet_pp_lint::fix::TextEdit::replace_with_source(
+                        &elt.extra,
+                        &inner.extra,
+                    )],
+                ),
+            ];
         }
 
         Vec::new()
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use crate::puppet_pp_lint::{
+    lint::{LateLintPass, LintError, LintPass, RepositoryLintError},
+    repository::Repository,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DuplicateDefinition;
+
+impl LintPass for DuplicateDefinition {
+    fn name(&self) -> &str {
+        "DuplicateDefinition"
+    }
+    fn description(&self) -> &str {
+        "Checks for classes, defined types, functions and type aliases defined more than once"
+    }
+}
+
+impl LateLintPass for DuplicateDefinition {
+    fn check_repository(&self, repository: &Repository) -> Vec<RepositoryLintError> {
+        let mut definitions: Vec<_> = repository.definitions.iter().collect();
+        definitions.sort_by(|a, b| {
+            (a.kind, &a.name, &a.file, a.location.start().offset()).cmp(&(
+                b.kind,
+                &b.name,
+                &b.file,
+                b.location.start().offset(),
+            ))
+        });
+
+        let mut errors = Vec::new();
+        let mut first: Option<&crate::puppet_pp_lint::repository::Entity> = None;
+        for elt in definitions {
+            match first {
+                Some(first) if first.kind == elt.kind && first.name == elt.name => {
+                    errors.push(RepositoryLintError {
+                        file: elt.file.clone(),
+                        error: LintError::new(
+                            Box::new(self.clone()),
+                            &format!(
+                                "{} {} is already defined in {:?} at line {}",
+                                elt.kind,
+                                elt.name,
+                                first.file,
+                                first.location.start().line()
+                            ),
+                            &elt.location,
+                        ),
+                    })
+                }
+                _ => first = Some(elt),
+            }
+        }
+
+        errors
+    }
+}
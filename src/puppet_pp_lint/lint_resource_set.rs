@@ -51,6 +51,41 @@ impl LintPass for UniqueAttributeName {
     }
 }
 
+impl UniqueAttributeName {
+    fn check_attributes(
+        &self,
+        attributes: &[crate::puppet_lang::statement::ResourceAttribute<Range>],
+    ) -> Vec<LintError> {
+        let mut errors = Vec::new();
+        let mut names: std::collections::HashMap<&String, &Range> =
+            std::collections::HashMap::new();
+        for attribute in attributes {
+            let name = match &attribute.value {
+                crate::puppet_lang::statement::ResourceAttributeVariant::Name((name, _))
+                | crate::puppet_lang::statement::ResourceAttributeVariant::Append((name, _)) => {
+                    name
+                }
+                crate::puppet_lang::statement::ResourceAttributeVariant::Group(_) => continue,
+            };
+            match names.get(&name.data) {
+                Some(first) => errors.push(
+                    LintError::new(
+                        Box::new(self.clone()),
+                        &format!("Attribute {:?} is not unique", name.data),
+                        &name.extra,
+                    )
+                    .with_label("first defined here", first),
+                ),
+                None => {
+                    let _ = names.insert(&name.data, &name.extra);
+                }
+            }
+        }
+
+        errors
+    }
+}
+
 impl EarlyLintPass for UniqueAttributeName {
     fn check_resource_set(
         &self,
@@ -59,26 +94,30 @@ impl EarlyLintPass for UniqueAttributeName {
     ) -> Vec<LintError> {
         let mut errors = Vec::new();
         for resource in &elt.list.value {
-            let mut names = std::collections::HashSet::new();
-            for attribute in &resource.attributes.value {
-                if let crate::puppet_lang::statement::ResourceAttributeVariant::Name(pair) =
-                    &attribute.value
-                {
-                    let name = &pair.0.data;
-                    if names.contains(name) {
-                        errors.push(LintError::new(
-                            Box::new(self.clone()),
-                            &format!("Attribute {:?} is not unique", name),
-                            &elt.extra,
-                        ));
-                    }
-                    let _ = names.insert(name.clone());
-                }
-            }
+            errors.append(&mut self.check_attributes(&resource.attributes.value));
         }
 
         errors
     }
+
+    fn check_resource_collection(
+        &self,
+        _ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        elt: &crate::puppet_lang::resource_collection::ResourceCollection<Range>,
+    ) -> Vec<LintError> {
+        match &elt.overrides {
+            Some(overrides) => self.check_attributes(&overrides.value),
+            None => Vec::new(),
+        }
+    }
+
+    fn check_resource_override(
+        &self,
+        _ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        elt: &crate::puppet_lang::statement::ResourceOverride<Range>,
+    ) -> Vec<LintError> {
+        self.check_attributes(&elt.attributes.value)
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -93,6 +132,34 @@ impl LintPass for EnsureAttributeIsNotTheFirst {
     }
 }
 
+impl EnsureAttributeIsNotTheFirst {
+    fn attribute_range(
+        attribute: &crate::puppet_lang::statement::ResourceAttribute<Range>,
+    ) -> Option<Range> {
+        match &attribute.value {
+            crate::puppet_lang::statement::ResourceAttributeVariant::Name((name, value))
+            | crate::puppet_lang::statement::ResourceAttributeVariant::Append((name, value)) => {
+                Some(Range::from((&name.extra, &value.extra)))
+            }
+            crate::puppet_lang::statement::ResourceAttributeVariant::Group(_) => None,
+        }
+    }
+
+    /// Swaps 'ensure' with the first attribute
+    fn edits(
+        first: &crate::puppet_lang::statement::ResourceAttribute<Range>,
+        ensure: &crate::puppet_lang::statement::ResourceAttribute<Range>,
+    ) -> Vec<crate::puppet_pp_lint::fix::TextEdit> {
+        match (Self::attribute_range(first), Self::attribute_range(ensure)) {
+            (Some(first), Some(ensure)) => vec![
+                crate::puppet_pp_lint::fix::TextEdit::replace_with_source(&first, &ensure),
+                crate::puppet_pp_lint::fix::TextEdit::replace_with_source(&ensure, &first),
+            ],
+            _ => Vec::new(),
+        }
+    }
+}
+
 impl EarlyLintPass for EnsureAttributeIsNotTheFirst {
     fn check_resource_set(
         &self,
@@ -112,7 +179,7 @@ impl EarlyLintPass for EnsureAttributeIsNotTheFirst {
                             "Attribute 'ensure' is not the first.",
                             "https://puppet.com/docs/puppet/7/style_guide.html#style_guide_resources-attribute-ordering",
                             &elt.extra,
-                        ));
+                        ).with_edits(Self::edits(&resource.attributes.value[0], attribute)));
                     }
                 }
             }
@@ -135,10 +202,25 @@ impl LintPass for FileModeAttributeIsString {
 }
 
 impl FileModeAttributeIsString {
+    /// Replaces 3 or 4 octal digits mode with 4-digit string
+    fn edits(digits: &str, range: &Range) -> Vec<crate::puppet_pp_lint::fix::TextEdit> {
+        if !(3..=4).contains(&digits.len()) || !digits.chars().all(|v| v.is_digit(8)) {
+            return Vec::new();
+        }
+        vec![crate::puppet_pp_lint::fix::TextEdit::replace(
+            range,
+            &format!("'{:0>4}'", digits),
+        )]
+    }
+
     fn check_expr(&self, expr: &crate::puppet_lang::string::StringExpr<Range>) -> Vec<LintError> {
         let list = match &expr.data {
             crate::puppet_lang::string::StringVariant::SingleQuoted(list) => list.clone(),
-            crate::puppet_lang::string::StringVariant::DoubleQuoted(list) => {
+            crate::puppet_lang::string::StringVariant::DoubleQuoted(list)
+            | crate::puppet_lang::string::StringVariant::Heredoc(crate::puppet_lang::string::Heredoc {
+                data: list,
+                ..
+            }) => {
                 let mut r = Vec::new();
                 for elt in list {
                     match elt {
@@ -154,6 +236,7 @@ impl FileModeAttributeIsString {
             }
         };
 
+        let is_single_fragment = list.len() == 1;
         let mut errors = Vec::new();
         for elt in list {
             match elt {
@@ -172,7 +255,11 @@ impl FileModeAttributeIsString {
                                             "Mode attribute is a string which length != 4.",
                                             "https://puppet.com/docs/puppet/7/style_guide.html#style_guide_resources-file-modes",
                                             &expr.extra,
-                                        ));
+                                        ).with_edits(if is_single_fragment {
+                                            Self::edits(&v.data, &expr.extra)
+                                        } else {
+                                            Vec::new()
+                                        }));
                     }
                 }
                 crate::puppet_lang::string::StringFragment::Escaped(elt)
@@ -214,13 +301,24 @@ impl EarlyLintPass for FileModeAttributeIsString {
                                 crate::puppet_lang::expression::TermVariant::String(v) => {
                                     return self.check_expr(v)
                                 }
-                                crate::puppet_lang::expression::TermVariant::Integer(_) => {
+                                crate::puppet_lang::expression::TermVariant::Integer(v) => {
+                                    let message = match v.radix {
+                                        crate::puppet_lang::expression::IntegerRadix::Octal => format!(
+                                            "Octal integer value of mode attribute. Use string '{}'.",
+                                            v.lexeme
+                                        ),
+                                        _ => "Integer value of mode attribute. Use string.".to_string(),
+                                    };
+                                    let edits = match v.radix {
+                                        crate::puppet_lang::expression::IntegerRadix::Hexadecimal => Vec::new(),
+                                        _ => Self::edits(&v.lexeme, &attribute.1.extra),
+                                    };
                                     return vec![LintError::new_with_url(
                                     Box::new(self.clone()),
-                                        "Integer value of mode attribute. Use string.",
+                                        &message,
                                         "https://puppet.com/docs/puppet/7/style_guide.html#style_guide_resources-file-modes",
                                         &attribute.1.extra,
-                                    )];
+                                    ).with_edits(edits)];
                                 }
                                 _ => {}
                             }
@@ -538,30 +636,30 @@ fn check_builtin_invocation<LINTER>(
     linter: &LINTER,
     ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
     errors: &mut Vec<super::lint::LintError>,
-    elt: &crate::puppet_lang::statement::ResourceSet<Range>,
+    resource_name: &[String],
+    attributes: &[crate::puppet_lang::statement::ResourceAttribute<Range>],
     builtin: &crate::puppet_pp_lint::ctx::builtin_resources::Resource,
 ) where
     LINTER: LintPass + Clone + 'static,
 {
-    for resource in &elt.list.value {
-        for attribute in &resource.attributes.value {
-            let name = match &attribute.value {
-                crate::puppet_lang::statement::ResourceAttributeVariant::Name((name, _)) => name,
-                crate::puppet_lang::statement::ResourceAttributeVariant::Group(_) => continue,
-            };
-            if !builtin.attributes.contains_key(name.data.as_str())
-                && !ctx.resource_metaparameters.contains_key(name.data.as_str())
-            {
-                errors.push(LintError::new(
-                    Box::new(linter.clone()),
-                    &format!(
-                        "Builtin resource {:?} does not accept argument {:?}",
-                        elt.name.name.join("::"),
-                        name.data
-                    ),
-                    &name.extra,
-                ))
-            }
+    for attribute in attributes {
+        let name = match &attribute.value {
+            crate::puppet_lang::statement::ResourceAttributeVariant::Name((name, _))
+            | crate::puppet_lang::statement::ResourceAttributeVariant::Append((name, _)) => name,
+            crate::puppet_lang::statement::ResourceAttributeVariant::Group(_) => continue,
+        };
+        if !builtin.attributes.contains_key(name.data.as_str())
+            && !ctx.resource_metaparameters.contains_key(name.data.as_str())
+        {
+            errors.push(LintError::new(
+                Box::new(linter.clone()),
+                &format!(
+                    "Builtin resource {:?} does not accept argument {:?}",
+                    resource_name.join("::"),
+                    name.data
+                ),
+                &name.extra,
+            ))
         }
     }
 }
@@ -570,7 +668,8 @@ fn check_defined_resource_invocation<LINTER>(
     linter: &LINTER,
     ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
     errors: &mut Vec<super::lint::LintError>,
-    elt: &crate::puppet_lang::statement::ResourceSet<Range>,
+    resource_name: &[String],
+    attributes: &[crate::puppet_lang::statement::ResourceAttribute<Range>],
     named_block: &crate::puppet_pp_lint::ctx::NamedBlock,
 ) where
     LINTER: LintPass + Clone + 'static,
@@ -581,31 +680,51 @@ fn check_defined_resource_invocation<LINTER>(
         crate::puppet_lang::toplevel::ToplevelVariant::Plan(v) => &v.arguments.value,
         crate::puppet_lang::toplevel::ToplevelVariant::TypeDef(_) => return,
         crate::puppet_lang::toplevel::ToplevelVariant::FunctionDef(_) => return,
+        crate::puppet_lang::toplevel::ToplevelVariant::Node(_) => return,
     };
 
-    for resource in &elt.list.value {
-        for attribute in &resource.attributes.value {
-            let name = match &attribute.value {
-                crate::puppet_lang::statement::ResourceAttributeVariant::Name((name, _)) => name,
-                crate::puppet_lang::statement::ResourceAttributeVariant::Group(_) => continue,
-            };
-            if !arguments.iter().any(|arg| arg.name == name.data)
-                && !ctx.resource_metaparameters.contains_key(name.data.as_str())
-            {
-                errors.push(LintError::new(
-                    Box::new(linter.clone()),
-                    &format!(
-                        "Resource {:?} does not accept argument {:?}",
-                        elt.name.name.join("::"),
-                        name.data
-                    ),
-                    &name.extra,
-                ))
-            }
+    for attribute in attributes {
+        let name = match &attribute.value {
+            crate::puppet_lang::statement::ResourceAttributeVariant::Name((name, _))
+            | crate::puppet_lang::statement::ResourceAttributeVariant::Append((name, _)) => name,
+            crate::puppet_lang::statement::ResourceAttributeVariant::Group(_) => continue,
+        };
+        if !arguments.iter().any(|arg| arg.name == name.data)
+            && !ctx.resource_metaparameters.contains_key(name.data.as_str())
+        {
+            errors.push(LintError::new(
+                Box::new(linter.clone()),
+                &format!(
+                    "Resource {:?} does not accept argument {:?}",
+                    resource_name.join("::"),
+                    name.data
+                ),
+                &name.extra,
+            ))
         }
     }
 }
 
+/// Returns class name referenced by constant title, like 'nginx::config' in Class['nginx::config']
+/// or Class[nginx::config]
+fn class_title(title: &crate::puppet_lang::expression::Expression<Range>) -> Option<String> {
+    let title = match &title.value {
+        crate::puppet_lang::expression::ExpressionVariant::Term(term) => match &term.value {
+            crate::puppet_lang::expression::TermVariant::String(expr) => {
+                crate::puppet_tool::string::constant_value(expr)?
+            }
+            crate::puppet_lang::expression::TermVariant::Identifier(identifier) => {
+                identifier.name.join("::")
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let title = title.to_lowercase();
+    Some(title.strip_prefix("::").unwrap_or(&title).to_string())
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct InvalidResourceSetInvocation;
 
@@ -632,34 +751,48 @@ impl EarlyLintPass for InvalidResourceSetInvocation {
 
         if let Some(named_block) = ctx.block_of_name(name.as_slice()).as_ref() {
             known_resource = true;
-            check_defined_resource_invocation(self, ctx, &mut errors, elt, named_block)
+            for resource in &elt.list.value {
+                check_defined_resource_invocation(
+                    self,
+                    ctx,
+                    &mut errors,
+                    &elt.name.name,
+                    &resource.attributes.value,
+                    named_block,
+                )
+            }
         }
 
         if !known_resource && name.len() == 1 {
             if let Some(builtin) = ctx.builtin_resources.get(name.first().unwrap().as_str()) {
                 known_resource = true;
-                check_builtin_invocation(self, ctx, &mut errors, elt, builtin)
+                for resource in &elt.list.value {
+                    check_builtin_invocation(
+                        self,
+                        ctx,
+                        &mut errors,
+                        &elt.name.name,
+                        &resource.attributes.value,
+                        builtin,
+                    )
+                }
             }
 
             if name.first().unwrap() == "class" {
                 known_resource = true;
+                if elt.visibility != crate::puppet_lang::statement::ResourceVisibility::Normal {
+                    errors.push(LintError::new(
+                        Box::new(self.clone()),
+                        "Classes can not be virtual or exported",
+                        &elt.extra,
+                    ));
+                }
                 for resource in &elt.list.value {
-                    let title = match &resource.title.value {
-                        crate::puppet_lang::expression::ExpressionVariant::Term(term) => {
-                            match &term.value {
-                                crate::puppet_lang::expression::TermVariant::String(v) => {
-                                    match crate::puppet_tool::string::constant_value(v) {
-                                        None => continue,
-                                        Some(v) => v,
-                                    }
-                                }
-                                _ => continue,
-                            }
-                        }
-                        _ => continue,
+                    let title = match class_title(&resource.title) {
+                        Some(v) => v,
+                        None => continue,
                     };
 
-                    let title = title.strip_prefix("::").unwrap_or(&title);
                     let title_as_list: Vec<_> = title.split("::").map(|v| v.to_string()).collect();
 
                     match ctx.block_of_name(title_as_list.as_slice()).as_ref() {
@@ -703,6 +836,45 @@ impl LintPass for InvalidResourceCollectionInvocation {
     }
 }
 
+impl InvalidResourceCollectionInvocation {
+    fn check_class_collection(
+        &self,
+        ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        elt: &crate::puppet_lang::resource_collection::ResourceCollection<Range>,
+        titles: &[crate::puppet_lang::expression::Expression<Range>],
+    ) -> Vec<LintError> {
+        let mut errors = Vec::new();
+        if elt.is_exported {
+            errors.push(LintError::new(
+                Box::new(self.clone()),
+                "Classes can not be collected as exported resources",
+                &elt.extra,
+            ));
+        }
+        for arg in titles {
+            let title = match class_title(arg) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let title_as_list: Vec<_> = title.split("::").map(|v| v.to_string()).collect();
+
+            if ctx.block_of_name(title_as_list.as_slice()).is_none() {
+                // Plain reference like Class['x'] has no search expression
+                let message = match &elt.search_expression {
+                    None => format!("Reference to undefined class {:?}", title),
+                    Some(_) => format!(
+                        "Reference to undefined class {:?} in resource collection",
+                        title,
+                    ),
+                };
+                errors.push(LintError::new(Box::new(self.clone()), &message, &arg.extra));
+            }
+        }
+        errors
+    }
+}
+
 impl EarlyLintPass for InvalidResourceCollectionInvocation {
     fn check_resource_collection(
         &self,
@@ -713,6 +885,9 @@ impl EarlyLintPass for InvalidResourceCollectionInvocation {
 
         let type_specification = match &elt.type_specification.data {
             crate::puppet_lang::typing::TypeSpecificationVariant::ExternalType(elt) => elt,
+            crate::puppet_lang::typing::TypeSpecificationVariant::Class(class) => {
+                return self.check_class_collection(ctx, elt, &class.titles)
+            }
             crate::puppet_lang::typing::TypeSpecificationVariant::Float(_)
             | crate::puppet_lang::typing::TypeSpecificationVariant::Integer(_)
             | crate::puppet_lang::typing::TypeSpecificationVariant::Numeric(_)
@@ -729,7 +904,25 @@ impl EarlyLintPass for InvalidResourceCollectionInvocation {
             | crate::puppet_lang::typing::TypeSpecificationVariant::Enum(_)
             | crate::puppet_lang::typing::TypeSpecificationVariant::Struct(_)
             | crate::puppet_lang::typing::TypeSpecificationVariant::Sensitive(_)
-            | crate::puppet_lang::typing::TypeSpecificationVariant::Tuple(_) => return Vec::new(),
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Tuple(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Type(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Resource(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Callable(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Iterable(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Iterator(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::NotUndef(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Scalar(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Data(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Collection(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::SemVer(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::SemVerRange(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Timespan(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Timestamp(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Binary(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Init(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Deferred(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Runtime(_)
+            | crate::puppet_lang::typing::TypeSpecificationVariant::Object(_) => return Vec::new(),
         };
 
         let name: Vec<_> = type_specification
@@ -740,54 +933,46 @@ impl EarlyLintPass for InvalidResourceCollectionInvocation {
 
         let mut known_resource = false;
 
-        if let Some(_named_block) = ctx.block_of_name(name.as_slice()).as_ref() {
+        let overrides = match &elt.overrides {
+            Some(v) => v.value.as_slice(),
+            None => &[],
+        };
+
+        if let Some(named_block) = ctx.block_of_name(name.as_slice()).as_ref() {
             // TODO check search expression of collection
-            return Vec::new();
+            check_defined_resource_invocation(
+                self,
+                ctx,
+                &mut errors,
+                &type_specification.name,
+                overrides,
+                named_block,
+            );
+            return errors;
         }
 
         if name.len() == 1 {
-            if let Some(_builtin) = ctx.builtin_resources.get(name.first().unwrap().as_str()) {
+            if let Some(builtin) = ctx.builtin_resources.get(name.first().unwrap().as_str()) {
                 // TODO check search expression of collection
-                return Vec::new();
+                check_builtin_invocation(
+                    self,
+                    ctx,
+                    &mut errors,
+                    &type_specification.name,
+                    overrides,
+                    builtin,
+                );
+                return errors;
             }
 
             let name = name.first().unwrap();
             if name == "class" {
                 known_resource = true;
-                for arg in &type_specification.arguments {
-                    let title = match &arg.value {
-                        crate::puppet_lang::expression::ExpressionVariant::Term(term) => {
-                            match &term.value {
-                                crate::puppet_lang::expression::TermVariant::String(expr) => {
-                                    match crate::puppet_tool::string::constant_value(expr) {
-                                        None => continue,
-                                        Some(v) => v,
-                                    }
-                                }
-                                _ => continue,
-                            }
-                        }
-                        _ => continue,
-                    };
-
-                    let title = title.to_lowercase();
-                    let title = title.strip_prefix("::").unwrap_or(&title);
-                    let title_as_list: Vec<_> = title.split("::").map(|v| v.to_string()).collect();
-
-                    match ctx.block_of_name(title_as_list.as_slice()).as_ref() {
-                        Some(_) => (),
-                        None => {
-                            errors.push(LintError::new(
-                                Box::new(self.clone()),
-                                &format!(
-                                    "Reference to undefined class {:?} in resource collection",
-                                    title,
-                                ),
-                                &arg.extra,
-                            ));
-                        }
-                    }
-                }
+                errors.append(&mut self.check_class_collection(
+                    ctx,
+                    elt,
+                    &type_specification.arguments,
+                ));
             }
         }
 
@@ -805,3 +990,229 @@ impl EarlyLintPass for InvalidResourceCollectionInvocation {
         errors
     }
 }
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InvalidResourceReference;
+
+impl LintPass for InvalidResourceReference {
+    fn name(&self) -> &str {
+        "InvalidResourceReference"
+    }
+    fn description(&self) -> &str {
+        "Checks if referenced resource exists and all overridden arguments are known in it's class"
+    }
+}
+
+impl EarlyLintPass for InvalidResourceReference {
+    fn check_resource_reference(
+        &self,
+        ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        elt: &crate::puppet_lang::expression::ResourceReference<Range>,
+    ) -> Vec<LintError> {
+        let mut errors = Vec::new();
+
+        let name: Vec<_> = elt.name.iter().map(|v| v.to_lowercase()).collect();
+
+        if ctx.block_of_name(name.as_slice()).is_some() {
+            return errors;
+        }
+
+        if name.len() == 1 {
+            let name = name.first().unwrap();
+            if ctx.builtin_resources.contains_key(name.as_str()) || name == "resource" {
+                return errors;
+            }
+
+            if name == "class" {
+                for title in &elt.titles {
+                    let title = match class_title(title) {
+                        Some(v) => v,
+                        None => continue,
+                    };
+                    let title_as_list: Vec<_> = title.split("::").map(|v| v.to_string()).collect();
+                    if ctx.block_of_name(title_as_list.as_slice()).is_none() {
+                        errors.push(LintError::new(
+                            Box::new(self.clone()),
+                            &format!("Reference to undefined class {:?}", title),
+                            &elt.extra,
+                        ));
+                    }
+                }
+                return errors;
+            }
+        }
+
+        errors.push(LintError::new(
+            Box::new(self.clone()),
+            &format!("Reference to undefined resource {:?}", elt.name.join("::")),
+            &elt.extra,
+        ));
+
+        errors
+    }
+
+    fn check_resource_override(
+        &self,
+        ctx: &crate::puppet_pp_lint::ctx::Ctx<Range>,
+        elt: &crate::puppet_lang::statement::ResourceOverride<Range>,
+    ) -> Vec<LintError> {
+        let mut errors = Vec::new();
+
+        let name: Vec<_> = elt
+            .reference
+            .name
+            .iter()
+            .map(|v| v.to_lowercase())
+            .collect();
+
+        if let Some(named_block) = ctx.block_of_name(name.as_slice()).as_ref() {
+            check_defined_resource_invocation(
+                self,
+                ctx,
+                &mut errors,
+                &elt.reference.name,
+                &elt.attributes.value,
+                named_block,
+            );
+            return errors;
+        }
+
+        if name.len() != 1 {
+            return errors;
+        }
+
+        let name = name.first().unwrap();
+        if let Some(builtin) = ctx.builtin_resources.get(name.as_str()) {
+            check_builtin_invocation(
+                self,
+                ctx,
+                &mut errors,
+                &elt.reference.name,
+                &elt.attributes.value,
+                builtin,
+            );
+        }
+
+        if name == "class" {
+            for title in &elt.reference.titles {
+                let title = match class_title(title) {
+                    Some(v) => v,
+                    None => continue,
+                };
+                let title_as_list: Vec<_> = title.split("::").map(|v| v.to_string()).collect();
+                if let Some(named_block) = ctx.block_of_name(title_as_list.as_slice()).as_ref() {
+                    check_defined_resource_invocation(
+                        self,
+                        ctx,
+                        &mut errors,
+                        &title_as_list,
+                        &elt.attributes.value,
+                        named_block,
+                    );
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+#[test]
+fn test_class_collection() {
+    let repo = std::env::temp_dir().join(format!(
+        "shadowplay-class-collection-{}",
+        std::process::id()
+    ));
+    let manifests = repo.join("modules/known_class/manifests");
+    std::fs::create_dir_all(&manifests).unwrap();
+    std::fs::write(manifests.join("init.pp"), "class known_class {\n}\n").unwrap();
+
+    let (_, ast) = crate::puppet_parser::toplevel::parse_file(crate::puppet_parser::Span::new_extra(
+        "class known_class::user {\n  Class['unknown_class'] -> Class['known_class']\n  Class <<| |>>\n  Class['other_class'] <| tag == 'x' |>\n}\n",
+        &Default::default(),
+    ))
+    .unwrap();
+    let ctx = crate::puppet_pp_lint::ctx::Ctx::new(&repo);
+    let errors = crate::puppet_pp_lint::lint::AstLinter.check_file(
+        &crate::puppet_pp_lint::lint::Storage::default(),
+        &ctx,
+        &ast,
+    );
+    std::fs::remove_dir_all(&repo).unwrap();
+
+    let messages: Vec<_> = errors
+        .iter()
+        .filter(|err| err.linter.name() == "InvalidResourceCollectionInvocation")
+        .map(|err| (err.message.as_str(), err.location.start().line()))
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            ("Reference to undefined class \"unknown_class\"", 2),
+            ("Classes can not be collected as exported resources", 3),
+            (
+                "Reference to undefined class \"other_class\" in resource collection",
+                4
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_resource_references() {
+    let repo = std::env::temp_dir().join(format!(
+        "shadowplay-resource-reference-{}",
+        std::process::id()
+    ));
+    let manifests = repo.join("modules/known_class/manifests");
+    std::fs::create_dir_all(&manifests).unwrap();
+    std::fs::write(
+        manifests.join("init.pp"),
+        "class known_class (\n  String $port,\n) {\n}\n",
+    )
+    .unwrap();
+
+    let state = crate::puppet_parser::ParserState::default();
+    let (_, ast) =
+        crate::puppet_parser::toplevel::parse_file(crate::puppet_parser::Span::new_extra(
+            "class known_class::user {
+  File['/etc/motd'] { mode => '0600' }
+  Class['known_class'] { port => '80' }
+  notice(Service['nginx'], Resource[File, '/etc/motd'], Class['known_class'])
+  File['/etc/motd'] { unknown => '0600' }
+  Class['known_class'] { host => 'localhost' }
+  notice(Unknown_type['x'], Class['unknown_class'])
+}
+",
+            &state,
+        ))
+        .unwrap();
+    let ctx = crate::puppet_pp_lint::ctx::Ctx::new(&repo);
+    let errors = crate::puppet_pp_lint::lint::AstLinter.check_file(
+        &crate::puppet_pp_lint::lint::Storage::default(),
+        &ctx,
+        &ast,
+    );
+    std::fs::remove_dir_all(&repo).unwrap();
+
+    let messages: Vec<_> = errors
+        .iter()
+        .filter(|err| err.linter.name() == "InvalidResourceReference")
+        .map(|err| (err.message.as_str(), err.location.start().line()))
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            (
+                "Builtin resource \"File\" does not accept argument \"unknown\"",
+                5
+            ),
+            (
+                "Resource \"known_class\" does not accept argument \"host\"",
+                6
+            ),
+            ("Reference to undefined resource \"Unknown_type\"", 7),
+            ("Reference to undefined class \"unknown_class\"", 7),
+        ]
+    );
+}
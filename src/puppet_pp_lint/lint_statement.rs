@@ -3,7 +3,7 @@ use crate::puppet_parser::range::Range;
 use serde::{Deserialize, Serialize};
 
 use crate::puppet_lang::ExtraGetter;
-use crate::puppet_pp_lint::lint::{EarlyLintPass, LintError, LintPass};
+use crate::puppet_pp_lint::lint::{EarlyLintPass, LintError, LintPass, Severity};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct StatementWithNoEffect;
@@ -91,6 +91,9 @@ impl LintPass for DeepCode {
     fn description(&self) -> &str {
         "Warns if statement is too deep"
     }
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
 }
 
 impl EarlyLintPass for DeepCode {
@@ -134,6 +137,9 @@ impl LintPass for HugeCodeBlock {
     fn description(&self) -> &str {
         "Warns if statement set is too long"
     }
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
 }
 
 impl EarlyLintPass for HugeCodeBlock {
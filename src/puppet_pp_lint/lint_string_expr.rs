@@ -30,6 +30,8 @@ impl EarlyLintPass for InvalidStringEscape {
                     crate::puppet_lang::string::DoubleQuotedFragment::Expression(_) => None,
                 })
                 .collect::<Vec<_>>(),
+            // Only enabled escapes are parsed as escaped characters in heredoc
+            crate::puppet_lang::string::StringVariant::Heredoc(_) => return Vec::new(),
         };
 
         let mut errors = Vec::new();
@@ -66,6 +68,7 @@ impl EarlyLintPass for InvalidStringEscape {
                             ))
                         }
                     }
+                    crate::puppet_lang::string::StringVariant::Heredoc(_) => {}
                 }
             }
         }
@@ -86,13 +89,41 @@ impl LintPass for UselessDoubleQuotes {
     }
 }
 
+impl UselessDoubleQuotes {
+    /// Single quoted form of string. None if string contains escapes which can't be kept.
+    fn single_quoted(list: &[crate::puppet_lang::string::DoubleQuotedFragment<Range>]) -> Option<String> {
+        let mut r = String::from("'");
+        for fragment in list {
+            match fragment {
+                crate::puppet_lang::string::DoubleQuotedFragment::StringFragment(
+                    crate::puppet_lang::string::StringFragment::Literal(v),
+                ) => r.push_str(&v.data.replace('\'', "\\'")),
+                crate::puppet_lang::string::DoubleQuotedFragment::StringFragment(
+                    crate::puppet_lang::string::StringFragment::Escaped(v),
+                ) => match v.data {
+                    '"' | '$' => r.push(v.data),
+                    '\\' => r.push_str("\\\\"),
+                    _ => return None,
+                },
+                crate::puppet_lang::string::DoubleQuotedFragment::StringFragment(
+                    crate::puppet_lang::string::StringFragment::EscapedUTF(_),
+                )
+                | crate::puppet_lang::string::DoubleQuotedFragment::Expression(_) => return None,
+            }
+        }
+        r.push('\'');
+        Some(r)
+    }
+}
+
 impl EarlyLintPass for UselessDoubleQuotes {
     fn check_string_expression(
         &self,
         elt: &crate::puppet_lang::string::StringExpr<Range>,
     ) -> Vec<super::lint::LintError> {
         let s = match &elt.data {
-            crate::puppet_lang::string::StringVariant::SingleQuoted(_) => return Vec::new(),
+            crate::puppet_lang::string::StringVariant::SingleQuoted(_)
+            | crate::puppet_lang::string::StringVariant::Heredoc(_) => return Vec::new(),
             crate::puppet_lang::string::StringVariant::DoubleQuoted(elt) => elt,
         };
 
@@ -114,11 +145,15 @@ impl EarlyLintPass for UselessDoubleQuotes {
         }
 
         if !is_useful {
+            let edits = Self::single_quoted(s)
+                .map(|v| vec![crate::puppet_pp_lint::fix::TextEdit::replace(&elt.extra, &v)])
+                .unwrap_or_default();
             return vec![LintError::new(
                 Box::new(self.clone()),
                 "Double quoted string with no interpolated values and no escaped double quotes",
                 &elt.extra,
-            )];
+            )
+            .with_edits(edits)];
         }
         vec![]
     }
@@ -143,7 +178,8 @@ impl EarlyLintPass for ExpressionInSingleQuotes {
     ) -> Vec<super::lint::LintError> {
         let v = match &elt.data {
             crate::puppet_lang::string::StringVariant::SingleQuoted(v) => v,
-            crate::puppet_lang::string::StringVariant::DoubleQuoted(_) => return vec![],
+            crate::puppet_lang::string::StringVariant::DoubleQuoted(_)
+            | crate::puppet_lang::string::StringVariant::Heredoc(_) => return vec![],
         };
 
         for fragment in v {
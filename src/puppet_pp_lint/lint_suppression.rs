@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+use crate::puppet_pp_lint::{
+    ctx::suppression::Suppressions,
+    lint::{EarlyLintPass, LintError, LintPass},
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UnusedSuppression;
+
+impl LintPass for UnusedSuppression {
+    fn name(&self) -> &str {
+        "UnusedSuppression"
+    }
+    fn description(&self) -> &str {
+        "Checks for shadowplay:disable comments which suppress nothing"
+    }
+}
+
+impl EarlyLintPass for UnusedSuppression {}
+
+impl UnusedSuppression {
+    pub fn check_suppressions(&self, suppressions: &Suppressions) -> Vec<LintError> {
+        let mut errors = Vec::new();
+
+        for suppression in &suppressions.list {
+            if suppression.is_used() {
+                continue;
+            }
+            errors.push(LintError::new(
+                Box::new(self.clone()),
+                &format!(
+                    "Suppression of {} does not suppress anything",
+                    suppression.lint
+                ),
+                &suppression.location,
+            ))
+        }
+
+        for (lint, location) in &suppressions.unmatched_enable {
+            errors.push(LintError::new(
+                Box::new(self.clone()),
+                &format!("{} is enabled without preceding shadowplay:disable", lint),
+                location,
+            ))
+        }
+
+        errors
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{puppet_lang::expression::Term, puppet_parser::range::Range};
 use serde::{Deserialize, Serialize};
 
-use crate::puppet_pp_lint::lint::{EarlyLintPass, LintError, LintPass};
+use crate::puppet_pp_lint::lint::{EarlyLintPass, LintError, LintPass, Severity};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct LowerCaseVariable;
@@ -97,6 +97,9 @@ impl LintPass for MagicNumber {
     fn description(&self) -> &str {
         "Warns if term contains magic number"
     }
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
 }
 
 impl EarlyLintPass for MagicNumber {
@@ -109,18 +112,18 @@ impl EarlyLintPass for MagicNumber {
         let magic_number = match &elt.value {
             crate::puppet_lang::expression::TermVariant::Integer(v) => {
                 if v.value > 10 || v.value < -10 {
-                    format!("{}", v.value)
+                    v.lexeme.clone()
                 } else {
                     return Vec::new();
                 }
             }
             crate::puppet_lang::expression::TermVariant::Float(v) => {
                 if v.value > 10.0 || v.value < -10.0 {
-                    format!("{}", v.value)
+                    v.lexeme.clone()
                 } else {
                     let s = format!("{}", v.value);
                     if s.len() > 2 {
-                        s
+                        v.lexeme.clone()
                     } else {
                         return Vec::new();
                     }
@@ -136,6 +139,7 @@ impl EarlyLintPass for MagicNumber {
             | crate::puppet_lang::expression::TermVariant::RegexpGroupID(_)
             | crate::puppet_lang::expression::TermVariant::Sensitive(_)
             | crate::puppet_lang::expression::TermVariant::TypeSpecitifaction(_)
+            | crate::puppet_lang::expression::TermVariant::ResourceReference(_)
             | crate::puppet_lang::expression::TermVariant::Regexp(_) => {
                 return Vec::new();
             }
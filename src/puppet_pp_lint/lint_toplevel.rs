@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use crate::puppet_parser::range::Range;
 use serde::{Deserialize, Serialize};
 
-use super::lint::{EarlyLintPass, LintError, LintPass};
+use super::lint::{EarlyLintPass, LintError, LintPass, Severity};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct OptionalArgumentsGoesFirst;
@@ -76,15 +76,18 @@ impl UniqueArgumentsNames {
             HashMap::new();
         for arg in args {
             match names.get(&arg.name) {
-                Some(prev) => errors.push(LintError::new(
-                    Box::new(self.clone()),
-                    &format!(
-                        "Argument '{}' was already defined earlier at line {}",
-                        arg.name,
-                        prev.extra.start().line()
-                    ),
-                    &arg.extra,
-                )),
+                Some(prev) => errors.push(
+                    LintError::new(
+                        Box::new(self.clone()),
+                        &format!(
+                            "Argument '{}' was already defined earlier at line {}",
+                            arg.name,
+                            prev.extra.start().line()
+                        ),
+                        &arg.extra,
+                    )
+                    .with_label("first defined here", &prev.extra),
+                ),
                 None => {
                     let _ = names.insert(arg.name.clone(), arg);
                 }
@@ -130,6 +133,9 @@ impl LintPass for TooManyArguments {
     fn description(&self) -> &str {
         "Checks if arguments list of definition is overloaded"
     }
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
 }
 
 impl TooManyArguments {
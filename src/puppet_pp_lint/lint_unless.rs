@@ -15,6 +15,51 @@ impl LintPass for DoNotUseUnless {
     }
 }
 
+impl DoNotUseUnless {
+    /// Replaces 'unless EXPR' with 'if !EXPR'
+    fn edits(
+        elt: &crate::puppet_lang::statement::ConditionAndStatement<Range>,
+    ) -> Vec<crate::puppet_pp_lint::fix::TextEdit> {
+        let keyword = elt.extra.start().offset();
+        let condition = &elt.condition;
+        // 'unless($a)' must not become 'if!($a)'
+        let replacement = if condition.extra.start().offset() == keyword + "unless".len() {
+            "if "
+        } else {
+            "if"
+        };
+        let mut edits = vec![crate::puppet_pp_lint::fix::TextEdit::new(
+            keyword,
+            keyword + "unless".len(),
+            crate::puppet_pp_lint::fix::Replacement::Text(replacement.to_string()),
+        )];
+
+        match &condition.value {
+            crate::puppet_lang::expression::ExpressionVariant::Not(inner) => {
+                edits.push(crate::puppet_pp_lint::fix::TextEdit::replace_with_source(
+                    &condition.extra,
+                    &inner.extra,
+                ))
+            }
+            crate::puppet_lang::expression::ExpressionVariant::Term(_) => edits.push(
+                crate::puppet_pp_lint::fix::TextEdit::insert(condition.extra.start().offset(), "!"),
+            ),
+            _ => {
+                edits.push(crate::puppet_pp_lint::fix::TextEdit::insert(
+                    condition.extra.start().offset(),
+                    "!(",
+                ));
+                edits.push(crate::puppet_pp_lint::fix::TextEdit::insert(
+                    condition.extra.end().offset() + 1,
+                    ")",
+                ))
+            }
+        }
+
+        edits
+    }
+}
+
 impl EarlyLintPass for DoNotUseUnless {
     fn check_unless(
         &self,
@@ -24,6 +69,7 @@ impl EarlyLintPass for DoNotUseUnless {
             Box::new(self.clone()),
             "Use 'if !EXPR { ... }' instead of 'unless EXPR { ... }'",
             &elt.extra,
-        )]
+        )
+        .with_edits(Self::edits(elt))]
     }
 }
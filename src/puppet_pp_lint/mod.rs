@@ -1,14 +1,19 @@
 pub mod ctx;
+pub mod fix;
 pub mod lint;
 mod lint_argument;
 mod lint_builtin;
 mod lint_case_statement;
 mod lint_ctx;
+pub mod lint_custom;
 mod lint_expression;
+mod lint_repository;
 mod lint_resource_set;
 mod lint_statement;
 mod lint_string_expr;
+mod lint_suppression;
 mod lint_term;
 mod lint_toplevel;
 mod lint_unless;
+pub mod repository;
 mod tool;
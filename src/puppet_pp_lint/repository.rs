@@ -0,0 +1,193 @@
+use crate::puppet_parser::range::Range;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Kind {
+    Class,
+    /// Defined resource type
+    Definition,
+    Plan,
+    Function,
+    TypeAlias,
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Class => write!(f, "Class"),
+            Kind::Definition => write!(f, "Defined type"),
+            Kind::Plan => write!(f, "Plan"),
+            Kind::Function => write!(f, "Function"),
+            Kind::TypeAlias => write!(f, "Type alias"),
+        }
+    }
+}
+
+/// Definition or use site of named entity
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entity {
+    pub kind: Kind,
+    /// Lower case name without leading '::'
+    pub name: String,
+    pub file: std::path::PathBuf,
+    pub location: Range,
+}
+
+/// Converts 'Foo::Bar' or '::foo::bar' into 'foo::bar'
+pub fn normalize_name<S: AsRef<str>>(name: &[S]) -> String {
+    name.iter()
+        .map(|v| v.as_ref().trim_start_matches("::").to_lowercase())
+        .filter(|v| !v.is_empty())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Definitions and use sites of all files checked in the run
+#[derive(Default)]
+pub struct Repository {
+    pub definitions: Vec<Entity>,
+    pub uses: Vec<Entity>,
+}
+
+impl Repository {
+    /// Forgets everything known about the file
+    pub fn remove_file(&mut self, file: &std::path::Path) {
+        self.definitions.retain(|v| v.file != file);
+        self.uses.retain(|v| v.file != file);
+    }
+
+    pub fn add_definition(
+        &mut self,
+        kind: Kind,
+        name: &str,
+        file: &std::path::Path,
+        location: &Range,
+    ) {
+        self.definitions.push(Entity {
+            kind,
+            name: name.to_string(),
+            file: file.to_path_buf(),
+            location: location.clone(),
+        })
+    }
+
+    pub fn add_use(&mut self, kind: Kind, name: &str, file: &std::path::Path, location: &Range) {
+        self.uses.push(Entity {
+            kind,
+            name: name.to_string(),
+            file: file.to_path_buf(),
+            location: location.clone(),
+        })
+    }
+
+    pub fn definitions_of<'a>(
+        &'a self,
+        kind: Kind,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a Entity> {
+        self.definitions
+            .iter()
+            .filter(move |v| v.kind == kind && v.name == name)
+    }
+
+    pub fn uses_of<'a>(&'a self, kind: Kind, name: &'a str) -> impl Iterator<Item = &'a Entity> {
+        self.uses
+            .iter()
+            .filter(move |v| v.kind == kind && v.name == name)
+    }
+}
+
+#[test]
+fn test_repository() {
+    let range = Range::new(0, 1, 1, 0, 1, 1);
+    let mut repository = Repository::default();
+    repository.add_definition(
+        Kind::Class,
+        &normalize_name(&["::Foo", "Bar"]),
+        std::path::Path::new("a.pp"),
+        &range,
+    );
+    repository.add_definition(
+        Kind::Class,
+        "foo::bar",
+        std::path::Path::new("b.pp"),
+        &range,
+    );
+    repository.add_use(
+        Kind::Class,
+        "foo::bar",
+        std::path::Path::new("b.pp"),
+        &range,
+    );
+
+    assert_eq!(
+        repository.definitions_of(Kind::Class, "foo::bar").count(),
+        2
+    );
+    assert_eq!(
+        repository
+            .definitions_of(Kind::Function, "foo::bar")
+            .count(),
+        0
+    );
+
+    repository.remove_file(std::path::Path::new("b.pp"));
+    assert_eq!(
+        repository.definitions_of(Kind::Class, "foo::bar").count(),
+        1
+    );
+    assert_eq!(repository.uses_of(Kind::Class, "foo::bar").count(), 0);
+}
+
+#[test]
+fn test_use_sites() {
+    let (_, statements) = crate::puppet_parser::toplevel::parse_file(
+        crate::puppet_parser::Span::new_extra(
+            "class a inherits b {
+  include c
+  contain('d')
+  class { 'e': }
+  f::g { 'x': }
+  $v = h(Class['i'], J::K['y'])
+  $t = Foo::Bar
+  Class['l'] -> Class['m']
+}",
+            &Default::default(),
+        ),
+    )
+    .unwrap();
+
+    let mut ctx = crate::puppet_pp_lint::ctx::Ctx::new(std::path::Path::new("."));
+    ctx.new_file(std::path::Path::new("a.pp"));
+    let _ = crate::puppet_pp_lint::lint::AstLinter.check_file(
+        &crate::puppet_pp_lint::lint::Storage::default(),
+        &ctx,
+        &statements,
+    );
+
+    let repository = ctx.repository.borrow();
+    assert_eq!(repository.definitions_of(Kind::Class, "a").count(), 1);
+
+    let mut uses: Vec<_> = repository
+        .uses
+        .iter()
+        .map(|v| (v.kind, v.name.as_str()))
+        .collect();
+    uses.sort();
+    assert_eq!(
+        uses,
+        vec![
+            (Kind::Class, "b"),
+            (Kind::Class, "c"),
+            (Kind::Class, "d"),
+            (Kind::Class, "e"),
+            (Kind::Class, "i"),
+            (Kind::Class, "l"),
+            (Kind::Class, "m"),
+            (Kind::Definition, "f::g"),
+            (Kind::Definition, "j::k"),
+            (Kind::Function, "contain"),
+            (Kind::Function, "h"),
+            (Kind::TypeAlias, "foo::bar"),
+        ]
+    );
+}
@@ -7,6 +7,8 @@ pub fn is_constant<EXTRA>(f: &crate::puppet_lang::builtin::BuiltinVariant<EXTRA>
         | crate::puppet_lang::builtin::BuiltinVariant::Include(_)
         | crate::puppet_lang::builtin::BuiltinVariant::Realize(_)
         | crate::puppet_lang::builtin::BuiltinVariant::Template(_)
+        | crate::puppet_lang::builtin::BuiltinVariant::Epp(_)
+        | crate::puppet_lang::builtin::BuiltinVariant::InlineEpp(_)
         | crate::puppet_lang::builtin::BuiltinVariant::CreateResources(_) => {
             // TODO check lambda statement
             false
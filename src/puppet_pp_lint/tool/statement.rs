@@ -35,5 +35,8 @@ pub fn has_side_effect<EXTRA>(statement: &crate::puppet_lang::statement::Stateme
             .value
             .iter()
             .any(|(_k, v)| crate::puppet_pp_lint::tool::expression::has_side_effect(v)),
+        crate::puppet_lang::statement::StatementVariant::ResourceOverride(_) => true,
+        // Unknown, assume the worst
+        crate::puppet_lang::statement::StatementVariant::SyntaxError(_) => true,
     }
 }
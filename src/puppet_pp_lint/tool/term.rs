@@ -27,6 +27,10 @@ pub fn is_constant<EXTRA>(term: &crate::puppet_lang::expression::Term<EXTRA>) ->
             // TODO are there any cases when it's false?
             true
         }
+        crate::puppet_lang::expression::TermVariant::ResourceReference(v) => v
+            .titles
+            .iter()
+            .all(crate::puppet_pp_lint::tool::expression::is_constant),
         crate::puppet_lang::expression::TermVariant::Regexp(_) => true,
     }
 }
@@ -1 +1,115 @@
+#[derive(Clone, Copy, PartialEq)]
+enum LiteralKind {
+    Undef,
+    String,
+    Integer,
+    Float,
+    Boolean,
+    Array,
+    Hash,
+}
 
+fn literal_kind<EXTRA>(
+    expr: &crate::puppet_lang::expression::Expression<EXTRA>,
+) -> Option<LiteralKind> {
+    if expr.accessor.is_some() {
+        return None;
+    }
+    match &expr.value {
+        crate::puppet_lang::expression::ExpressionVariant::BuiltinFunction(
+            crate::puppet_lang::builtin::BuiltinVariant::Undef,
+        ) => Some(LiteralKind::Undef),
+        crate::puppet_lang::expression::ExpressionVariant::Term(term) => match &term.value {
+            crate::puppet_lang::expression::TermVariant::String(_) => Some(LiteralKind::String),
+            crate::puppet_lang::expression::TermVariant::Integer(_) => Some(LiteralKind::Integer),
+            crate::puppet_lang::expression::TermVariant::Float(_) => Some(LiteralKind::Float),
+            crate::puppet_lang::expression::TermVariant::Boolean(_) => Some(LiteralKind::Boolean),
+            crate::puppet_lang::expression::TermVariant::Array(_) => Some(LiteralKind::Array),
+            crate::puppet_lang::expression::TermVariant::Map(_) => Some(LiteralKind::Hash),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn kind_matches<EXTRA>(
+    t: &crate::puppet_lang::typing::TypeSpecification<EXTRA>,
+    kind: LiteralKind,
+) -> Option<bool> {
+    use crate::puppet_lang::typing::TypeSpecificationVariant;
+    match &t.data {
+        TypeSpecificationVariant::Any(_) => Some(true),
+        TypeSpecificationVariant::Data(_) => Some(true),
+        TypeSpecificationVariant::String(_)
+        | TypeSpecificationVariant::Pattern(_)
+        | TypeSpecificationVariant::Enum(_) => Some(kind == LiteralKind::String),
+        TypeSpecificationVariant::Integer(_) => Some(kind == LiteralKind::Integer),
+        TypeSpecificationVariant::Float(_) => Some(kind == LiteralKind::Float),
+        TypeSpecificationVariant::Numeric(_) => {
+            Some(kind == LiteralKind::Integer || kind == LiteralKind::Float)
+        }
+        TypeSpecificationVariant::Boolean(_) => Some(kind == LiteralKind::Boolean),
+        TypeSpecificationVariant::Array(_) | TypeSpecificationVariant::Tuple(_) => {
+            Some(kind == LiteralKind::Array)
+        }
+        TypeSpecificationVariant::Hash(_) | TypeSpecificationVariant::Struct(_) => {
+            Some(kind == LiteralKind::Hash)
+        }
+        TypeSpecificationVariant::Collection(_) => {
+            Some(kind == LiteralKind::Array || kind == LiteralKind::Hash)
+        }
+        TypeSpecificationVariant::Scalar(_) => Some(matches!(
+            kind,
+            LiteralKind::String | LiteralKind::Integer | LiteralKind::Float | LiteralKind::Boolean
+        )),
+        TypeSpecificationVariant::Undef(_) => Some(kind == LiteralKind::Undef),
+        TypeSpecificationVariant::Optional(v) => {
+            if kind == LiteralKind::Undef {
+                return Some(true);
+            }
+            match &v.value {
+                crate::puppet_lang::typing::TypeOptionalVariant::TypeSpecification(t) => {
+                    kind_matches(t, kind)
+                }
+                crate::puppet_lang::typing::TypeOptionalVariant::Term(_) => None,
+            }
+        }
+        TypeSpecificationVariant::NotUndef(v) => {
+            if kind == LiteralKind::Undef {
+                return Some(false);
+            }
+            match &v.value {
+                None => Some(true),
+                Some(crate::puppet_lang::typing::TypeNotUndefVariant::TypeSpecification(t)) => {
+                    kind_matches(t, kind)
+                }
+                Some(crate::puppet_lang::typing::TypeNotUndefVariant::Term(_)) => None,
+            }
+        }
+        TypeSpecificationVariant::Variant(v) => {
+            let mut result = Some(false);
+            for t in &v.list {
+                match kind_matches(t, kind) {
+                    Some(true) => return Some(true),
+                    Some(false) => (),
+                    None => result = None,
+                }
+            }
+            result
+        }
+        _ => None,
+    }
+}
+
+/// Checks literal value against type. Returns None if it can't be checked statically.
+pub fn literal_matches<EXTRA>(
+    t: &crate::puppet_lang::typing::TypeSpecification<EXTRA>,
+    expr: &crate::puppet_lang::expression::Expression<EXTRA>,
+) -> Option<bool> {
+    kind_matches(t, literal_kind(expr)?)
+}
+
+/// Checks if type accepts undef, i.e. argument of such type can be omitted.
+pub fn accepts_undef<EXTRA>(t: &crate::puppet_lang::typing::TypeSpecification<EXTRA>) -> bool {
+    kind_matches(t, LiteralKind::Undef).unwrap_or(false)
+}
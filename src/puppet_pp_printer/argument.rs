@@ -1,34 +1,47 @@
 use crate::puppet_pp_printer::Printer;
 use pretty::RcDoc;
 
-impl<EXTRA> Printer for crate::puppet_lang::argument::Argument<EXTRA> {
-    fn to_doc(&self) -> RcDoc<()> {
-        let type_spec = match &self.type_spec {
-            Some(v) => v.to_doc().append(RcDoc::softline()),
-            None => RcDoc::nil(),
-        };
+fn to_doc<EXTRA>(
+    elt: &crate::puppet_lang::argument::Argument<EXTRA>,
+    align_default: bool,
+) -> RcDoc<'_, ()> {
+    let type_spec = match &elt.type_spec {
+        Some(v) => v.to_doc().append(RcDoc::softline()),
+        None => RcDoc::nil(),
+    };
 
-        let default = match &self.default {
-            Some(v) => RcDoc::softline_()
-                .append(RcDoc::column(|w| {
-                    let offset = (w / crate::puppet_pp_printer::ARROW_STEP + 1)
-                        * crate::puppet_pp_printer::ARROW_STEP;
-                    RcDoc::text(format!("{}=", " ".repeat(offset - w)))
-                }))
-                .append(RcDoc::softline())
-                .append(crate::puppet_pp_printer::expression::to_doc(v, false)),
-            None => RcDoc::nil(),
-        };
+    let default = match &elt.default {
+        Some(v) if align_default => RcDoc::softline_()
+            .append(RcDoc::column(|w| {
+                let offset = (w / crate::puppet_pp_printer::ARROW_STEP + 1)
+                    * crate::puppet_pp_printer::ARROW_STEP;
+                RcDoc::text(format!("{}=", " ".repeat(offset - w)))
+            }))
+            .append(RcDoc::softline())
+            .append(crate::puppet_pp_printer::expression::to_doc(v, false)),
+        // Lambda parameters are printed in one line, no alignment needed
+        Some(v) => RcDoc::text(" = ").append(crate::puppet_pp_printer::expression::to_doc(v, false)),
+        None => RcDoc::nil(),
+    };
 
-        crate::puppet_pp_printer::comment::comment_or(
-            &self.comment,
-            RcDoc::hardline(),
-            RcDoc::nil(),
-        )
-        .append(type_spec)
-        .append(RcDoc::text("$"))
-        .append(RcDoc::text(&self.name))
-        .append(default)
+    crate::puppet_pp_printer::comment::comment_or(
+        &elt.comment,
+        RcDoc::hardline(),
+        RcDoc::nil(),
+    )
+    .append(type_spec)
+    .append(if elt.is_captures_rest {
+        RcDoc::text("*$")
+    } else {
+        RcDoc::text("$")
+    })
+    .append(RcDoc::text(&elt.name))
+    .append(default)
+}
+
+impl<EXTRA> Printer for crate::puppet_lang::argument::Argument<EXTRA> {
+    fn to_doc(&self) -> RcDoc<()> {
+        to_doc(self, true)
     }
 }
 
@@ -71,7 +84,7 @@ pub fn list_to_piped_doc<EXTRA>(
     }
 
     let list = RcDoc::intersperse(
-        elt.value.iter().map(|x| x.to_doc()),
+        elt.value.iter().map(|x| to_doc(x, false).group()),
         RcDoc::text(",").append(RcDoc::softline()),
     )
     .append(crate::puppet_pp_printer::comment::to_doc(&elt.last_comment));
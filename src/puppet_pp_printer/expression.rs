@@ -198,6 +198,12 @@ impl<EXTRA> Printer for crate::puppet_lang::builtin::BuiltinVariant<EXTRA> {
             crate::puppet_lang::builtin::BuiltinVariant::Template(v) => {
                 builtin_many1_to_doc("template", v, true)
             }
+            crate::puppet_lang::builtin::BuiltinVariant::Epp(v) => {
+                builtin_many1_to_doc("epp", v, true)
+            }
+            crate::puppet_lang::builtin::BuiltinVariant::InlineEpp(v) => {
+                builtin_many1_to_doc("inline_epp", v, true)
+            }
         }
     }
 }
@@ -303,6 +309,10 @@ fn test_idempotence_short() {
     let cases = vec![
         "123",
         "4.0 + 5.1",
+        "0755 + 0x1F",
+        "1e3",
+        "1.5E-3",
+        "3.1415926535897932",
         "'hello universe'",
         "\"hello\n universe\"",
         "\"hello ${universe}\"",
@@ -324,6 +334,7 @@ fn test_idempotence_short() {
         "(1 or 2)\nand (3 + 4\n  * 5)\nor (true\n  and (!true\n    and false))",
         "$v.call1()\n.call2(1,\n  2)\n.call3withlongname()",
         "fn(1, 2)\n|$a, $b| {\n  1\n}",
+        "fn()\n|Integer\n  $a = 1,\n  *$b| {\n  1\n}",
         "$v ? {\n  1 => a,\n  \n  #comment\n  2 => b,\n  default\n  => c,\n}",
         "undef",
         "require\na::b, c",
@@ -335,7 +346,7 @@ fn test_idempotence_short() {
 
     for case in cases {
         let (_, v) = crate::puppet_parser::expression::parse_expression(
-            crate::puppet_parser::Span::new(case),
+            crate::puppet_parser::Span::new_extra(case, &Default::default()),
         )
         .unwrap();
 
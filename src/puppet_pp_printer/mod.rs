@@ -18,3 +18,11 @@ use pretty::RcDoc;
 pub trait Printer {
     fn to_doc(&self) -> RcDoc<()>;
 }
+
+/// Renders document into string. Unlike plain rendering, places bodies of heredocs after the
+/// lines with their headers.
+pub fn render(doc: &RcDoc<()>, width: usize) -> String {
+    let mut w = Vec::new();
+    doc.render(width, &mut w).unwrap();
+    string::place_heredoc_bodies(&String::from_utf8(w).unwrap())
+}
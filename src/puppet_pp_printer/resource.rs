@@ -42,16 +42,45 @@ impl<EXTRA> Printer for crate::puppet_lang::resource_collection::SearchExpressio
     }
 }
 
+fn attributes_block_to_doc<EXTRA>(
+    list: &crate::puppet_lang::List<EXTRA, crate::puppet_lang::statement::ResourceAttribute<EXTRA>>,
+) -> RcDoc<'_, ()> {
+    RcDoc::text("{")
+        .append(RcDoc::hardline())
+        .append(RcDoc::intersperse(
+            list.value.iter().map(|elt| elt.to_doc()),
+            RcDoc::text(",").append(RcDoc::hardline()),
+        ))
+        .append(crate::puppet_pp_printer::comment::to_doc(&list.last_comment))
+        .nest(2)
+        .append(RcDoc::hardline())
+        .append(RcDoc::text("}"))
+}
+
 impl<EXTRA> Printer for crate::puppet_lang::resource_collection::ResourceCollection<EXTRA> {
     fn to_doc(&self) -> RcDoc<()> {
+        let (left_tag, right_tag) = if self.is_exported {
+            ("<<|", "|>>")
+        } else {
+            ("<|", "|>")
+        };
         let search_expression = match &self.search_expression {
             Some(v) => RcDoc::softline()
-                .append(RcDoc::text("<|"))
+                .append(RcDoc::text(left_tag))
                 .append(RcDoc::softline())
                 .append(v.to_doc())
                 .nest(2)
                 .append(RcDoc::softline())
-                .append(RcDoc::text("|>")),
+                .append(RcDoc::text(right_tag)),
+            None if self.is_exported => RcDoc::softline()
+                .append(RcDoc::text(left_tag))
+                .append(RcDoc::softline())
+                .append(RcDoc::text(right_tag)),
+            None => RcDoc::nil(),
+        };
+
+        let overrides = match &self.overrides {
+            Some(v) => RcDoc::softline().append(attributes_block_to_doc(v)),
             None => RcDoc::nil(),
         };
 
@@ -62,19 +91,32 @@ impl<EXTRA> Printer for crate::puppet_lang::resource_collection::ResourceCollect
         )
         .append(self.type_specification.to_doc())
         .append(search_expression)
+        .append(overrides)
     }
 }
 
+fn aligned_arrow<'a>(arrow: &'static str) -> RcDoc<'a, ()> {
+    RcDoc::column(move |w| {
+        let offset =
+            (w / crate::puppet_pp_printer::ARROW_STEP + 1) * crate::puppet_pp_printer::ARROW_STEP;
+        RcDoc::text(format!("{}{}", " ".repeat(offset - w), arrow))
+    })
+}
+
 impl<EXTRA> Printer for crate::puppet_lang::statement::ResourceAttribute<EXTRA> {
     fn to_doc(&self) -> RcDoc<()> {
         let value = match &self.value {
             crate::puppet_lang::statement::ResourceAttributeVariant::Name((k, v)) => {
                 RcDoc::text(&k.data)
-                    .append(RcDoc::column(|w| {
-                        let offset = (w / crate::puppet_pp_printer::ARROW_STEP + 1)
-                            * crate::puppet_pp_printer::ARROW_STEP;
-                        RcDoc::text(format!("{}=>", " ".repeat(offset - w)))
-                    }))
+                    .append(aligned_arrow("=>"))
+                    .append(RcDoc::softline())
+                    .append(crate::puppet_pp_printer::expression::to_doc(v, false))
+                    .group()
+                    .nest(2)
+            }
+            crate::puppet_lang::statement::ResourceAttributeVariant::Append((k, v)) => {
+                RcDoc::text(&k.data)
+                    .append(aligned_arrow("+>"))
                     .append(RcDoc::softline())
                     .append(crate::puppet_pp_printer::expression::to_doc(v, false))
                     .group()
@@ -82,11 +124,7 @@ impl<EXTRA> Printer for crate::puppet_lang::statement::ResourceAttribute<EXTRA>
             }
             crate::puppet_lang::statement::ResourceAttributeVariant::Group(v) => RcDoc::text("*")
                 .append(RcDoc::softline())
-                .append(RcDoc::column(|w| {
-                    let offset = (w / crate::puppet_pp_printer::ARROW_STEP + 1)
-                        * crate::puppet_pp_printer::ARROW_STEP;
-                    RcDoc::text(format!("{}=>", " ".repeat(offset - w)))
-                }))
+                .append(aligned_arrow("=>"))
                 .append(RcDoc::softline())
                 .append(crate::puppet_pp_printer::term::to_doc(v, false))
                 .group()
@@ -126,10 +164,10 @@ impl<EXTRA> Printer for crate::puppet_lang::statement::Resource<EXTRA> {
 
 impl<EXTRA> Printer for crate::puppet_lang::statement::ResourceSet<EXTRA> {
     fn to_doc(&self) -> RcDoc<()> {
-        let is_virtual = if self.is_virtual {
-            RcDoc::text("@")
-        } else {
-            RcDoc::nil()
+        let visibility = match self.visibility {
+            crate::puppet_lang::statement::ResourceVisibility::Normal => RcDoc::nil(),
+            crate::puppet_lang::statement::ResourceVisibility::Virtual => RcDoc::text("@"),
+            crate::puppet_lang::statement::ResourceVisibility::Exported => RcDoc::text("@@"),
         };
 
         // just one-liner
@@ -149,7 +187,7 @@ impl<EXTRA> Printer for crate::puppet_lang::statement::ResourceSet<EXTRA> {
                 RcDoc::hardline(),
                 RcDoc::nil(),
             )
-            .append(is_virtual)
+            .append(visibility)
             .append(self.name.to_doc())
             .append(RcDoc::softline())
             .append(RcDoc::text("{"))
@@ -176,7 +214,7 @@ impl<EXTRA> Printer for crate::puppet_lang::statement::ResourceSet<EXTRA> {
             RcDoc::hardline(),
             RcDoc::nil(),
         )
-        .append(is_virtual)
+        .append(visibility)
         .append(self.name.to_doc())
         .append(RcDoc::softline())
         .append(RcDoc::text("{"))
@@ -188,6 +226,15 @@ impl<EXTRA> Printer for crate::puppet_lang::statement::ResourceSet<EXTRA> {
     }
 }
 
+impl<EXTRA> Printer for crate::puppet_lang::statement::ResourceOverride<EXTRA> {
+    fn to_doc(&self) -> RcDoc<()> {
+        self.reference
+            .to_doc()
+            .append(RcDoc::softline())
+            .append(attributes_block_to_doc(&self.attributes))
+    }
+}
+
 impl<EXTRA> Printer for crate::puppet_lang::statement::RelationEltVariant<EXTRA> {
     fn to_doc(&self) -> RcDoc<()> {
         match self {
@@ -268,11 +315,19 @@ fn test_idempotence_short() {
         "file { '/etc/passwd':\n    ensure => file,\n    mode => '0644';\n  '/etc/group':\n    ensure => file\n}",
         // keyword test
         "file { '/etc/passwd':\n    unless => true\n}",
+        "@file { '/etc/passwd':\n    ensure => file\n}",
+        "@@nagios_service { 'check-ping':\n    ensure => present\n}",
+        "Nagios_service <<| tag == 'x-y' |>>",
+        "Nagios_service <<| |>>",
+        "Nagios_service <<| tag == 'x-y' |>> {\n  notify => Service[ 'nagios-host' ]\n}",
+        "Package <| tag == 'x-y' |> {\n  ensure => latest,\n  require +> File[ '/etc/x' ]\n}",
+        "File[ '/etc/motd' ] {\n  mode => '0600',\n  require +> Package[ 'nginx-full' ]\n}",
+        "Service[ 'php-fpm', 'nginx-full' ] {\n  ensure => running\n}",
     ];
 
     for case in cases {
         let (_, v) = crate::puppet_parser::statement::parse_statement_list(
-            crate::puppet_parser::Span::new(case),
+            crate::puppet_parser::Span::new_extra(case, &Default::default()),
         )
         .unwrap();
 
@@ -172,6 +172,10 @@ impl<EXTRA> Printer for crate::puppet_lang::statement::Statement<EXTRA> {
             crate::puppet_lang::statement::StatementVariant::Case(v) => v.to_doc(),
             crate::puppet_lang::statement::StatementVariant::Toplevel(v) => v.data.to_doc(),
             crate::puppet_lang::statement::StatementVariant::ResourceDefaults(v) => v.to_doc(),
+            crate::puppet_lang::statement::StatementVariant::ResourceOverride(v) => v.to_doc(),
+            crate::puppet_lang::statement::StatementVariant::SyntaxError(v) => {
+                RcDoc::text(v.data.trim_end())
+            }
         };
 
         crate::puppet_pp_printer::comment::comment_or(
@@ -199,19 +203,19 @@ fn test_idempotence_short() {
         "Exec\n{\n  command  => test,\n  provider  => shell,\n  # comment\n  #line2\n  #line3\n}",
         "require a",
         "require a, b",
+        "$a = @(END)\nhello\nEND",
+        "$a = @(\"END\":sh/t)\n  hello ${b}\\t\n\n  world\n  |- END",
+        "$a = @(END/L)\n  a\\\n  b\n- END",
+        "$a = [ @(A), @(B), ]\na\nA\nb\nB",
     ];
 
     for case in cases {
         let (_, v) = crate::puppet_parser::statement::parse_statement_list(
-            crate::puppet_parser::Span::new(case),
+            crate::puppet_parser::Span::new_extra(case, &Default::default()),
         )
         .unwrap();
 
-        let mut w = Vec::new();
-        statement_block_to_doc(&v, false)
-            .render(25, &mut w)
-            .unwrap();
-        let generated = String::from_utf8(w).unwrap();
+        let generated = crate::puppet_pp_printer::render(&statement_block_to_doc(&v, false), 25);
         println!("{} ==>\n------\n{}\n------", case, generated);
 
         assert_eq!(&generated, case)
@@ -227,7 +231,7 @@ fn test_idempotence_long() {
 
     for case in cases {
         let (_, v) = crate::puppet_parser::statement::parse_statement_list(
-            crate::puppet_parser::Span::new(case),
+            crate::puppet_parser::Span::new_extra(case, &Default::default()),
         )
         .unwrap();
 
@@ -241,3 +245,30 @@ fn test_idempotence_long() {
         assert_eq!(&generated, case)
     }
 }
+
+#[test]
+fn test_heredoc_followed_by_tokens() {
+    let case = "file { 'a': content => @(X), mode => '0644' }\nhello\nX\n";
+    let (_, v) = crate::puppet_parser::statement::parse_statement_list(
+        crate::puppet_parser::Span::new_extra(case, &Default::default()),
+    )
+    .unwrap();
+
+    let generated = crate::puppet_pp_printer::render(&statement_block_to_doc(&v, false), 80);
+    println!("{} ==>\n------\n{}\n------", case, generated);
+    assert_eq!(
+        generated,
+        "file { a:\n    content => @(X),\nhello\nX\n    mode => '0644'\n}"
+    );
+
+    let state = crate::puppet_parser::ParserState::default();
+    let (rest, reparsed) = crate::puppet_parser::statement::parse_statement_list(
+        crate::puppet_parser::Span::new_extra(&generated, &state),
+    )
+    .unwrap();
+    assert_eq!(*rest, "");
+    assert_eq!(
+        crate::puppet_pp_printer::render(&statement_block_to_doc(&reparsed, false), 80),
+        generated
+    );
+}
@@ -72,6 +72,112 @@ impl<EXTRA> Printer for crate::puppet_lang::string::StringExpr<EXTRA> {
                     RcDoc::nil(),
                 ))
                 .append(RcDoc::text("\"")),
+            crate::puppet_lang::string::StringVariant::Heredoc(elt) => heredoc_to_doc(elt),
         }
     }
 }
+
+fn heredoc_to_doc<EXTRA>(elt: &crate::puppet_lang::string::Heredoc<EXTRA>) -> RcDoc<'_, ()> {
+    let mut header = String::from("@(");
+    if elt.interpolated {
+        header.push_str(&format!("\"{}\"", elt.tag))
+    } else {
+        header.push_str(&elt.tag)
+    }
+    if let Some(syntax) = &elt.syntax {
+        header.push_str(&format!(":{}", syntax))
+    }
+    if let Some(escapes) = &elt.escapes {
+        header.push_str(&format!("/{}", escapes))
+    }
+    header.push(')');
+    let header_len = header.chars().count();
+
+    // Body is rendered as is, it must not be affected by nesting of surrounding code
+    let mut body = String::new();
+    for fragment in &elt.data {
+        body.push_str(&format!("{}", fragment.to_doc().pretty(usize::MAX)))
+    }
+    // Trimmed trailing newline still has to be printed before the closing tag
+    if !body.is_empty() && !body.ends_with('\n') {
+        body.push('\n')
+    }
+
+    let margin = " ".repeat(elt.margin.unwrap_or(0));
+    let mut text = String::new();
+    for line in body.split_inclusive('\n') {
+        if line != "\n" {
+            text.push_str(&margin)
+        }
+        text.push_str(line);
+    }
+
+    let mut end_marker = String::new();
+    if elt.margin.is_some() {
+        end_marker.push('|')
+    }
+    if elt.trim_newline {
+        end_marker.push('-')
+    }
+    if !end_marker.is_empty() {
+        end_marker.push(' ')
+    }
+    text.push_str(&format!("{}{}{}", margin, end_marker, elt.tag));
+
+    // Body is moved to the next line by place_heredoc_bodies, so only header affects layout of
+    // surrounding code
+    let text = format!(
+        "{}{}{}{}{}",
+        header,
+        BODY_MARKER,
+        text.len(),
+        BODY_MARKER,
+        text
+    );
+    RcDoc::new(pretty::Doc::RenderLen(header_len, RcDoc::text(text)))
+}
+
+/// Separates header of heredoc from length of its body and the length from the body itself
+const BODY_MARKER: char = '\u{0}';
+
+/// Moves bodies of heredocs to the lines following their headers, so tokens which follow a header
+/// stay on the same line with it
+pub fn place_heredoc_bodies(rendered: &str) -> String {
+    let mut res = String::with_capacity(rendered.len());
+    let mut pending = Vec::new();
+    let mut rest = rendered;
+    while let Some(pos) = rest.find([BODY_MARKER, '\n']) {
+        res.push_str(&rest[..pos]);
+        let tail = &rest[pos + 1..];
+        if rest[pos..].starts_with('\n') {
+            res.push('\n');
+            for body in pending.drain(..) {
+                res.push_str(body);
+                res.push('\n')
+            }
+            rest = tail;
+            continue;
+        }
+        let body = tail.split_once(BODY_MARKER).and_then(|(len, tail)| {
+            let len = len.parse::<usize>().ok()?;
+            Some((tail.get(..len)?, tail.get(len..)?))
+        });
+        match body {
+            Some((body, tail)) => {
+                pending.push(body);
+                rest = tail
+            }
+            None => {
+                // Not a marker produced by heredoc_to_doc
+                res.push(BODY_MARKER);
+                rest = tail
+            }
+        }
+    }
+    res.push_str(rest);
+    for body in pending {
+        res.push('\n');
+        res.push_str(body)
+    }
+    res
+}
@@ -3,13 +3,13 @@ use pretty::{Doc, RcDoc};
 
 impl<EXTRA> Printer for crate::puppet_lang::expression::Float<EXTRA> {
     fn to_doc(&self) -> RcDoc<()> {
-        RcDoc::text(format!("{:?}", self.value))
+        RcDoc::text(self.lexeme.clone())
     }
 }
 
 impl<EXTRA> Printer for crate::puppet_lang::expression::Integer<EXTRA> {
     fn to_doc(&self) -> RcDoc<()> {
-        RcDoc::as_string(self.value)
+        RcDoc::text(self.lexeme.clone())
     }
 }
 
@@ -123,6 +123,18 @@ impl<EXTRA> Printer for crate::puppet_lang::expression::Array<EXTRA> {
     }
 }
 
+impl<EXTRA> Printer for crate::puppet_lang::expression::ResourceReference<EXTRA> {
+    fn to_doc(&self) -> RcDoc<()> {
+        RcDoc::intersperse(self.name.iter().map(RcDoc::text), RcDoc::text("::"))
+            .append(RcDoc::text("["))
+            .append(super::common::multiline_list(&self.titles, Some(false), |x| {
+                crate::puppet_pp_printer::expression::to_doc(x, false)
+            }))
+            .append(RcDoc::text("]"))
+            .group()
+    }
+}
+
 pub fn to_doc<EXTRA>(
     term: &crate::puppet_lang::expression::Term<EXTRA>,
     hide_variable_tag: bool,
@@ -157,6 +169,7 @@ pub fn to_doc<EXTRA>(
             .append(RcDoc::text(")"))
             .group(),
         crate::puppet_lang::expression::TermVariant::TypeSpecitifaction(v) => v.to_doc(),
+        crate::puppet_lang::expression::TermVariant::ResourceReference(v) => v.to_doc(),
         crate::puppet_lang::expression::TermVariant::Regexp(v) => v.to_doc(),
         crate::puppet_lang::expression::TermVariant::String(v) => v.to_doc(),
     }
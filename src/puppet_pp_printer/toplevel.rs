@@ -40,6 +40,44 @@ fn definition_to_doc<'a, EXTRA>(
         ))
 }
 
+impl<EXTRA> Printer for crate::puppet_lang::toplevel::NodeMatcher<EXTRA> {
+    fn to_doc(&self) -> RcDoc<()> {
+        match self {
+            crate::puppet_lang::toplevel::NodeMatcher::Name(v) => v.to_doc(),
+            crate::puppet_lang::toplevel::NodeMatcher::Regexp(v) => v.to_doc(),
+            crate::puppet_lang::toplevel::NodeMatcher::Default(_) => RcDoc::text("default"),
+        }
+    }
+}
+
+impl<EXTRA> Printer for crate::puppet_lang::toplevel::Node<EXTRA> {
+    fn to_doc(&self) -> RcDoc<()> {
+        let inherits = match &self.inherits {
+            Some(v) => RcDoc::text("inherits")
+                .append(RcDoc::softline())
+                .append(v.to_doc())
+                .append(RcDoc::softline()),
+            None => RcDoc::nil(),
+        };
+
+        RcDoc::text("node")
+            .append(RcDoc::softline())
+            .append(
+                RcDoc::intersperse(
+                    self.matchers.iter().map(|v| v.to_doc()),
+                    RcDoc::text(",").append(RcDoc::softline()),
+                )
+                .group()
+                .nest(2),
+            )
+            .append(RcDoc::softline())
+            .append(inherits)
+            .append(crate::puppet_pp_printer::statement::statement_block_to_doc(
+                &self.body, true,
+            ))
+    }
+}
+
 impl<EXTRA> Printer for crate::puppet_lang::toplevel::ToplevelVariant<EXTRA> {
     fn to_doc(&self) -> RcDoc<()> {
         match self {
@@ -77,6 +115,7 @@ impl<EXTRA> Printer for crate::puppet_lang::toplevel::ToplevelVariant<EXTRA> {
                 &v.return_type,
                 &v.body,
             ),
+            crate::puppet_lang::toplevel::ToplevelVariant::Node(v) => v.to_doc(),
         }
     }
 }
@@ -88,11 +127,16 @@ fn test_idempotence_short() {
         "class aaa::bbb () inherits zzz {\n  if $a {\n    1\n  } else {\n    2\n  }\n}",
         "function aaa::bbb (\n  $empty,\n  $a = 1,\n  String $b = 'a b c',\n) >> String {\n  \n}",
         "type Aaa::Bbb = String[1, 2]",
+        "node default {\n  \n}",
+        "node 'web01.example.com', /^db\\d+/ {\n  include role\n}",
     ];
 
     for case in cases {
-        let (_, v) =
-            crate::puppet_parser::toplevel::parse(crate::puppet_parser::Span::new(case)).unwrap();
+        let (_, v) = crate::puppet_parser::toplevel::parse(crate::puppet_parser::Span::new_extra(
+            case,
+            &Default::default(),
+        ))
+        .unwrap();
 
         let mut w = Vec::new();
         v.data.to_doc().render(50, &mut w).unwrap();
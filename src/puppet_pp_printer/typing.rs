@@ -126,6 +126,43 @@ fn has_args<EXTRA>(t: &crate::puppet_lang::typing::TypeSpecification<EXTRA>) ->
         }
         crate::puppet_lang::typing::TypeSpecificationVariant::Sensitive(_) => true,
         crate::puppet_lang::typing::TypeSpecificationVariant::Tuple(_) => true,
+        crate::puppet_lang::typing::TypeSpecificationVariant::Type(v) => v.inner.is_some(),
+        crate::puppet_lang::typing::TypeSpecificationVariant::Class(v) => !v.titles.is_empty(),
+        crate::puppet_lang::typing::TypeSpecificationVariant::Resource(v) => {
+            v.resource_type.is_some()
+        }
+        crate::puppet_lang::typing::TypeSpecificationVariant::Callable(v) => {
+            !v.params.is_empty()
+                || v.min.is_some()
+                || v.max.is_some()
+                || v.block.is_some()
+                || v.return_type.is_some()
+        }
+        crate::puppet_lang::typing::TypeSpecificationVariant::Iterable(v) => v.inner.is_some(),
+        crate::puppet_lang::typing::TypeSpecificationVariant::Iterator(v) => v.inner.is_some(),
+        crate::puppet_lang::typing::TypeSpecificationVariant::NotUndef(v) => v.value.is_some(),
+        crate::puppet_lang::typing::TypeSpecificationVariant::Scalar(_) => false,
+        crate::puppet_lang::typing::TypeSpecificationVariant::Data(_) => false,
+        crate::puppet_lang::typing::TypeSpecificationVariant::Collection(v) => {
+            v.min.is_some() || v.max.is_some()
+        }
+        crate::puppet_lang::typing::TypeSpecificationVariant::SemVer(v) => !v.ranges.is_empty(),
+        crate::puppet_lang::typing::TypeSpecificationVariant::SemVerRange(_) => false,
+        crate::puppet_lang::typing::TypeSpecificationVariant::Timespan(v) => {
+            v.min.is_some() || v.max.is_some()
+        }
+        crate::puppet_lang::typing::TypeSpecificationVariant::Timestamp(v) => {
+            v.min.is_some() || v.max.is_some()
+        }
+        crate::puppet_lang::typing::TypeSpecificationVariant::Binary(_) => false,
+        crate::puppet_lang::typing::TypeSpecificationVariant::Init(v) => v.inner.is_some(),
+        crate::puppet_lang::typing::TypeSpecificationVariant::Deferred(_) => false,
+        crate::puppet_lang::typing::TypeSpecificationVariant::Runtime(v) => {
+            !v.arguments.is_empty()
+        }
+        crate::puppet_lang::typing::TypeSpecificationVariant::Object(v) => {
+            !v.arguments.is_empty()
+        }
     }
 }
 
@@ -340,6 +377,112 @@ impl<EXTRA> Printer for crate::puppet_lang::typing::TypeTuple<EXTRA> {
     }
 }
 
+/// Name[arg1, arg2, ...], or just Name if there are no arguments
+fn with_args<'a>(name: &'static str, args: Vec<RcDoc<'a, ()>>) -> RcDoc<'a, ()> {
+    if args.is_empty() {
+        return RcDoc::text(name);
+    }
+
+    RcDoc::text(name)
+        .append(RcDoc::text("["))
+        .append(super::common::multiline_docs_list(args, None))
+        .append(RcDoc::text("]"))
+        .group()
+}
+
+fn min_max_docs<'a, T>(
+    min: &'a Option<T>,
+    max: &'a Option<T>,
+    f: impl Fn(&'a T) -> RcDoc<'a, ()>,
+) -> Vec<RcDoc<'a, ()>> {
+    match (min, max) {
+        (None, None) => vec![],
+        (None, Some(max)) => vec![RcDoc::text("default"), f(max)],
+        (Some(min), None) => vec![f(min)],
+        (Some(min), Some(max)) => vec![f(min), f(max)],
+    }
+}
+
+fn expressions_docs<EXTRA>(
+    list: &[crate::puppet_lang::expression::Expression<EXTRA>],
+) -> Vec<RcDoc<'_, ()>> {
+    list.iter()
+        .map(|x| crate::puppet_pp_printer::expression::to_doc(x, false))
+        .collect()
+}
+
+impl<EXTRA> Printer for crate::puppet_lang::typing::TypeResource<EXTRA> {
+    fn to_doc(&self) -> RcDoc<()> {
+        let mut args = Vec::new();
+        if let Some(resource_type) = &self.resource_type {
+            args.push(crate::puppet_pp_printer::expression::to_doc(
+                resource_type,
+                false,
+            ));
+        }
+        args.extend(expressions_docs(&self.titles));
+
+        with_args("Resource", args)
+    }
+}
+
+impl<EXTRA> Printer for crate::puppet_lang::typing::TypeCallable<EXTRA> {
+    fn to_doc(&self) -> RcDoc<()> {
+        let mut args: Vec<_> = self.params.iter().map(|v| v.to_doc()).collect();
+        match (&self.min, &self.max, &self.block) {
+            (None, None, Some(_)) => {
+                args.extend(vec![RcDoc::text("default"), RcDoc::text("default")])
+            }
+            (min, max, _) => args.extend(min_max_docs(min, max, |v| v.to_doc())),
+        }
+        if let Some(block) = &self.block {
+            args.push(block.to_doc())
+        }
+
+        match &self.return_type {
+            None => with_args("Callable", args),
+            Some(return_type) => with_args(
+                "Callable",
+                vec![
+                    RcDoc::text("[")
+                        .append(super::common::multiline_docs_list(args, None))
+                        .append(RcDoc::text("]"))
+                        .group(),
+                    return_type.to_doc(),
+                ],
+            ),
+        }
+    }
+}
+
+impl<EXTRA> Printer for crate::puppet_lang::typing::TypeNotUndef<EXTRA> {
+    fn to_doc(&self) -> RcDoc<()> {
+        let args = match &self.value {
+            None => vec![],
+            Some(crate::puppet_lang::typing::TypeNotUndefVariant::TypeSpecification(v)) => {
+                vec![v.to_doc()]
+            }
+            Some(crate::puppet_lang::typing::TypeNotUndefVariant::Term(v)) => {
+                vec![crate::puppet_pp_printer::term::to_doc(v, false)]
+            }
+        };
+
+        with_args("NotUndef", args)
+    }
+}
+
+impl<EXTRA> Printer for crate::puppet_lang::typing::TypeInit<EXTRA> {
+    fn to_doc(&self) -> RcDoc<()> {
+        let mut args = Vec::new();
+        if let Some(inner) = &self.inner {
+            args.push(inner.to_doc());
+        }
+        args.extend(expressions_docs(&self.arguments));
+
+        with_args("Init", args)
+    }
+}
+
 impl<EXTRA> Printer for crate::puppet_lang::typing::TypeSpecificationVariant<EXTRA> {
     fn to_doc(&self) -> RcDoc<()> {
         match self {
@@ -371,6 +514,59 @@ impl<EXTRA> Printer for crate::puppet_lang::typing::TypeSpecificationVariant<EXT
             crate::puppet_lang::typing::TypeSpecificationVariant::ExternalType(v) => v.to_doc(),
             crate::puppet_lang::typing::TypeSpecificationVariant::Sensitive(v) => v.to_doc(),
             crate::puppet_lang::typing::TypeSpecificationVariant::Tuple(v) => v.to_doc(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Type(v) => {
+                with_args("Type", v.inner.iter().map(|v| v.to_doc()).collect())
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Class(v) => {
+                with_args("Class", expressions_docs(&v.titles))
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Resource(v) => v.to_doc(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Callable(v) => v.to_doc(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Iterable(v) => {
+                with_args("Iterable", v.inner.iter().map(|v| v.to_doc()).collect())
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Iterator(v) => {
+                with_args("Iterator", v.inner.iter().map(|v| v.to_doc()).collect())
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::NotUndef(v) => v.to_doc(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Scalar(_) => {
+                RcDoc::text("Scalar")
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Data(_) => RcDoc::text("Data"),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Collection(v) => {
+                with_min_max("Collection", &v.min, &v.max)
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::SemVer(v) => {
+                with_args("SemVer", expressions_docs(&v.ranges))
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::SemVerRange(_) => {
+                RcDoc::text("SemVerRange")
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Timespan(v) => with_args(
+                "Timespan",
+                min_max_docs(&v.min, &v.max, |v| {
+                    crate::puppet_pp_printer::term::to_doc(v, false)
+                }),
+            ),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Timestamp(v) => with_args(
+                "Timestamp",
+                min_max_docs(&v.min, &v.max, |v| {
+                    crate::puppet_pp_printer::term::to_doc(v, false)
+                }),
+            ),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Binary(_) => {
+                RcDoc::text("Binary")
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Init(v) => v.to_doc(),
+            crate::puppet_lang::typing::TypeSpecificationVariant::Deferred(_) => {
+                RcDoc::text("Deferred")
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Runtime(v) => {
+                with_args("Runtime", expressions_docs(&v.arguments))
+            }
+            crate::puppet_lang::typing::TypeSpecificationVariant::Object(v) => {
+                with_args("Object", expressions_docs(&v.arguments))
+            }
         }
     }
 }
@@ -413,11 +609,33 @@ fn test_idempotence_short() {
         "Sensitive[\n  1 ]",
         "Sensitive[\n  String ]",
         "Tuple[\n  String,\n  Integer,\n  default,\n  100\n]",
+        "Type",
+        "Type[ Any ]",
+        "Class",
+        "Resource[\n  file,\n  '/x'\n]",
+        "Callable",
+        "Callable[\n  String,\n  1,\n  2\n]",
+        "Callable[\n  [\n    String,\n    Integer\n  ],\n  Boolean\n]",
+        "Iterable[\n  String ]",
+        "Iterator",
+        "NotUndef[\n  String ]",
+        "Scalar",
+        "Collection[\n  1]",
+        "SemVer[\n  '>=1' ]",
+        "SemVerRange",
+        "Timespan[\n  default,\n  10\n]",
+        "Timestamp",
+        "Binary",
+        "Init[\n  Integer ]",
+        "Deferred",
+        "Runtime[\n  ruby ]",
+        "Object",
+        "Database",
     ];
 
     for case in cases {
         let (_, v) = crate::puppet_parser::typing::parse_type_specification(
-            crate::puppet_parser::Span::new(case),
+            crate::puppet_parser::Span::new_extra(case, &Default::default()),
         )
         .unwrap();
 
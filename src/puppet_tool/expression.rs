@@ -17,6 +17,7 @@ pub fn string_constant_value<EXTRA>(
             | TermVariant::RegexpGroupID(_)
             | TermVariant::Sensitive(_)
             | TermVariant::TypeSpecitifaction(_)
+            | TermVariant::ResourceReference(_)
             | TermVariant::Regexp(_) => None,
         },
         ExpressionVariant::Assign(_)
@@ -15,7 +15,11 @@ pub fn raw_content<EXTRA>(expr: &crate::puppet_lang::string::StringExpr<EXTRA>)
             }
             r
         }
-        crate::puppet_lang::string::StringVariant::DoubleQuoted(list) => {
+        crate::puppet_lang::string::StringVariant::DoubleQuoted(list)
+        | crate::puppet_lang::string::StringVariant::Heredoc(crate::puppet_lang::string::Heredoc {
+            data: list,
+            ..
+        }) => {
             let mut r = String::new();
             for elt in list {
                 match elt {
@@ -42,6 +46,23 @@ fn string_fragment_constant_value<EXTRA>(
     }
 }
 
+fn heredoc_fragment_constant_value<EXTRA>(
+    expr: &crate::puppet_lang::string::StringFragment<EXTRA>,
+) -> String {
+    match expr {
+        crate::puppet_lang::string::StringFragment::Escaped(c) => match c.data {
+            'n' => "\n".to_string(),
+            'r' => "\r".to_string(),
+            't' => "\t".to_string(),
+            's' => " ".to_string(),
+            // Line continuation
+            '\n' => String::new(),
+            c => c.to_string(),
+        },
+        _ => string_fragment_constant_value(expr),
+    }
+}
+
 pub fn constant_value<EXTRA>(expr: &crate::puppet_lang::string::StringExpr<EXTRA>) -> Option<String> {
     match &expr.data {
         crate::puppet_lang::string::StringVariant::SingleQuoted(list) => {
@@ -63,5 +84,17 @@ pub fn constant_value<EXTRA>(expr: &crate::puppet_lang::string::StringExpr<EXTRA
             }
             Some(r)
         }
+        crate::puppet_lang::string::StringVariant::Heredoc(heredoc) => {
+            let mut r = String::new();
+            for elt in &heredoc.data {
+                match elt {
+                    crate::puppet_lang::string::DoubleQuotedFragment::StringFragment(elt) => {
+                        r.push_str(&heredoc_fragment_constant_value(elt))
+                    }
+                    crate::puppet_lang::string::DoubleQuotedFragment::Expression(_) => return None,
+                }
+            }
+            Some(r)
+        }
     }
 }
@@ -0,0 +1,277 @@
+use anyhow::{format_err, Result};
+use serde::{Deserialize, Serialize};
+use shadowplay::puppet_lang::statement::Statement;
+use shadowplay::puppet_lang::ExtraGetter;
+use shadowplay::puppet_parser::range::Range;
+
+/// Recorded finding. Location is not stored, so entry survives unrelated edits of the file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+pub struct Entry {
+    /// Path relative to repository root
+    pub file: String,
+    pub lint: String,
+    /// For readers of baseline only. Messages may refer to line numbers, so they are not matched.
+    pub message: String,
+    pub fingerprint: String,
+}
+
+impl Entry {
+    /// Only lint errors with fingerprints can be recorded
+    pub fn new(file: &str, err: &crate::check::error::Error) -> Option<Self> {
+        Some(Self {
+            file: file.to_string(),
+            lint: err.error_subtype.clone()?,
+            message: err.message.clone().unwrap_or_default(),
+            fingerprint: err.fingerprint.clone()?,
+        })
+    }
+
+    fn key(&self) -> (String, String, String) {
+        (
+            self.file.clone(),
+            self.lint.clone(),
+            self.fingerprint.clone(),
+        )
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Baseline {
+    pub entries: Vec<Entry>,
+}
+
+impl Baseline {
+    pub fn read(file: &std::path::Path) -> Result<Self> {
+        let baseline = std::fs::read_to_string(file)
+            .map_err(|err| format_err!("Failed to load baseline file {:?}: {}", file, err))?;
+        serde_json::from_str(&baseline)
+            .map_err(|err| format_err!("Failed to parse baseline file {:?}: {}", file, err))
+    }
+
+    pub fn write(mut self, file: &std::path::Path) -> Result<()> {
+        self.entries.sort();
+        let baseline = serde_json::to_string_pretty(&self).unwrap();
+        std::fs::write(file, baseline)
+            .map_err(|err| format_err!("Failed to write baseline file {:?}: {}", file, err))
+    }
+}
+
+/// Baseline entries which are not matched by findings yet
+#[derive(Default)]
+pub struct Unmatched {
+    /// Number of entries by file, lint and fingerprint
+    entries: std::collections::HashMap<(String, String, String), usize>,
+}
+
+impl Unmatched {
+    pub fn new(baseline: Baseline) -> Self {
+        let mut entries = std::collections::HashMap::new();
+        for entry in baseline.entries {
+            *entries.entry(entry.key()).or_insert(0) += 1;
+        }
+        Self { entries }
+    }
+
+    /// Returns true if finding is recorded in baseline. Every entry matches single finding.
+    pub fn take(&mut self, entry: &Entry) -> bool {
+        match self.entries.get_mut(&entry.key()) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Number of entries of checked files which no longer match any finding
+    pub fn fixed_count(&self, checked_files: &std::collections::HashSet<String>) -> usize {
+        self.entries
+            .iter()
+            .filter(|((file, _, _), _)| checked_files.contains(file))
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+/// Statements of blocks nested into statement
+fn nested_statements(statement: &Statement<Range>) -> Vec<&Statement<Range>> {
+    use shadowplay::puppet_lang::statement::StatementVariant;
+    use shadowplay::puppet_lang::toplevel::ToplevelVariant;
+
+    let lists: Vec<&[Statement<Range>]> = match &statement.value {
+        StatementVariant::IfElse(v) => std::iter::once(&v.condition)
+            .chain(v.elsif_list.iter())
+            .map(|v| v.body.value.as_slice())
+            .chain(v.else_block.iter().map(|v| v.value.as_slice()))
+            .collect(),
+        StatementVariant::Unless(v) => vec![&v.body.value],
+        StatementVariant::Case(v) => v
+            .elements
+            .value
+            .iter()
+            .map(|v| v.body.value.as_slice())
+            .collect(),
+        StatementVariant::Toplevel(v) => match &v.data {
+            ToplevelVariant::Class(v) => vec![&v.body.value],
+            ToplevelVariant::Definition(v) => vec![&v.body.value],
+            ToplevelVariant::Plan(v) => vec![&v.body.value],
+            ToplevelVariant::Node(v) => vec![&v.body.value],
+            ToplevelVariant::FunctionDef(v) => vec![&v.body.value],
+            ToplevelVariant::TypeDef(_) => Vec::new(),
+        },
+        StatementVariant::Expression(_)
+        | StatementVariant::RelationList(_)
+        | StatementVariant::ResourceDefaults(_)
+        | StatementVariant::ResourceOverride(_)
+        | StatementVariant::SyntaxError(_) => Vec::new(),
+    };
+    lists.into_iter().flatten().collect()
+}
+
+fn contains(range: &Range, location: &Range) -> bool {
+    range.start().offset() <= location.start().offset()
+        && location.end().offset() <= range.end().offset()
+}
+
+/// Byte range of source text of the innermost statement enclosing location. Nested blocks are
+/// excluded, e.g. only header is taken for class.
+fn enclosing_statement(
+    statements: &[Statement<Range>],
+    location: &Range,
+) -> Option<std::ops::Range<usize>> {
+    let mut statement = statements.iter().find(|v| contains(v.extra(), location))?;
+    loop {
+        let nested = nested_statements(statement);
+        match nested.iter().find(|v| contains(v.extra(), location)) {
+            Some(v) => statement = v,
+            None => {
+                let end = match nested.first() {
+                    Some(v) => v.extra().start().offset(),
+                    None => statement.extra().end().offset() + 1,
+                };
+                return Some(statement.extra().start().offset()..end);
+            }
+        }
+    }
+}
+
+/// Location-independent fingerprint of error. Source text of the node which error is reported
+/// for is hashed together with text of the enclosing statement, so same errors of different
+/// statements are distinguished. Whitespace differences are ignored.
+pub fn fingerprint(
+    linter: &str,
+    source: &str,
+    statements: &[Statement<Range>],
+    location: &Range,
+) -> String {
+    let normalized = |range: std::ops::Range<usize>| {
+        source
+            .get(range)
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let text = normalized(location.start().offset()..location.end().offset() + 1);
+    let statement = enclosing_statement(statements, location)
+        .map(normalized)
+        .unwrap_or_default();
+    hash(&[linter, &statement, &text])
+}
+
+/// Hash of strings separated by zero bytes. FNV-1a, stable between builds unlike std hasher.
+pub fn hash(parts: &[&str]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for (i, part) in parts.iter().enumerate() {
+        let separator = if i > 0 { Some(0) } else { None };
+        for byte in separator.into_iter().chain(part.bytes()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+fn test_entries(source: &str) -> Vec<Entry> {
+    let ast = crate::check::PuppetAst::parse(source).unwrap();
+    let ctx = shadowplay::puppet_pp_lint::ctx::Ctx::new(std::path::Path::new("."));
+    let path = std::path::Path::new("init.pp");
+    shadowplay::puppet_pp_lint::lint::AstLinter
+        .check_file(
+            &shadowplay::puppet_pp_lint::lint::Storage::default(),
+            &ctx,
+            &ast.data,
+        )
+        .iter()
+        .filter_map(|err| {
+            let err = crate::check::error::Error::from((path, err)).with_fingerprint(fingerprint(
+                err.linter.name(),
+                source,
+                &ast.data.value,
+                &err.location,
+            ));
+            Entry::new("init.pp", &err)
+        })
+        .collect()
+}
+
+#[test]
+fn test_fingerprint() {
+    let entries = test_entries(
+        "file { '/a': mode => 644 }\nfile { '/b': mode => 644 }\nfile { '/a': mode => 644 }\n",
+    );
+    let fingerprints: Vec<_> = entries
+        .iter()
+        .filter(|v| v.lint == "MagicNumber")
+        .map(|v| v.fingerprint.as_str())
+        .collect();
+    assert_eq!(fingerprints.len(), 3);
+    assert_ne!(fingerprints[0], fingerprints[1]);
+    assert_eq!(fingerprints[0], fingerprints[2]);
+}
+
+#[test]
+fn test_write_read() {
+    let entries = test_entries("file { '/a': mode => 644 }\n");
+    assert!(!entries.is_empty());
+
+    let path =
+        std::env::temp_dir().join(format!("shadowplay-baseline-{}.json", std::process::id()));
+    Baseline {
+        entries: entries.iter().rev().cloned().collect(),
+    }
+    .write(&path)
+    .unwrap();
+    let baseline = Baseline::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut expected = entries;
+    expected.sort();
+    assert_eq!(baseline.entries, expected);
+}
+
+#[test]
+fn test_shifted_lines() {
+    let source = "case $facts['os']['family'] {\n  default: {}\n  'Debian': {}\n}\n";
+    let baseline = test_entries(source);
+    assert!(baseline
+        .iter()
+        .any(|v| v.message.contains("defined earlier at line 2")));
+
+    let mut unmatched = Unmatched::new(Baseline {
+        entries: baseline.clone(),
+    });
+    let shifted = test_entries(&format!("\n\n{}", source));
+    assert!(shifted
+        .iter()
+        .any(|v| v.message.contains("defined earlier at line 4")));
+    assert_eq!(shifted.len(), baseline.len());
+    for entry in &shifted {
+        assert!(unmatched.take(entry));
+    }
+
+    let checked_files = std::iter::once("init.pp".to_string()).collect();
+    assert_eq!(unmatched.fixed_count(&checked_files), 0);
+}
@@ -1,19 +1,34 @@
 use std::fmt::Display;
 
 use serde::Serialize;
+use shadowplay::puppet_pp_lint::lint::Severity;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
 pub enum OutputFormat {
     OneLine,
     Json,
+    /// Multi-line output with source snippets
+    Pretty,
+    /// SARIF 2.1.0 log
+    Sarif,
+    /// Checkstyle XML report
+    Checkstyle,
+    /// JUnit XML report with test case per file
+    Junit,
+    /// GitLab Code Quality report
+    Gitlab,
 }
 
 impl OutputFormat {
     pub fn is_human(&self) -> bool {
         match self {
-            OutputFormat::OneLine => true,
-            OutputFormat::Json => false,
+            OutputFormat::OneLine | OutputFormat::Pretty => true,
+            OutputFormat::Json
+            | OutputFormat::Sarif
+            | OutputFormat::Checkstyle
+            | OutputFormat::Junit
+            | OutputFormat::Gitlab => false,
         }
     }
 }
@@ -31,6 +46,11 @@ impl std::str::FromStr for OutputFormat {
         match s {
             "one-line" => Ok(Self::OneLine),
             "json" => Ok(Self::Json),
+            "pretty" => Ok(Self::Pretty),
+            "sarif" => Ok(Self::Sarif),
+            "checkstyle" => Ok(Self::Checkstyle),
+            "junit" => Ok(Self::Junit),
+            "gitlab" => Ok(Self::Gitlab),
             _ => anyhow::bail!("Invalid format: {}", s),
         }
     }
@@ -60,18 +80,18 @@ impl Display for Type {
 #[derive(Debug, Serialize)]
 pub struct Location {
     #[serde(skip_serializing_if = "Option::is_none")]
-    line: Option<usize>,
+    pub line: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    column: Option<usize>,
+    pub column: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    index: Option<usize>,
+    pub index: Option<usize>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct Range {
-    path: std::path::PathBuf,
-    start: Location,
-    end: Location,
+    pub path: std::path::PathBuf,
+    pub start: Location,
+    pub end: Location,
 }
 
 impl Display for Location {
@@ -97,15 +117,25 @@ impl Display for Range {
     }
 }
 
-impl<'a> From<(&std::path::Path, &shadowplay::puppet_parser::Span<'a>)> for Range {
-    fn from(pair: (&std::path::Path, &shadowplay::puppet_parser::Span)) -> Self {
-        let (path, span) = pair;
+impl
+    From<(
+        &std::path::Path,
+        &shadowplay::puppet_parser::range::Location,
+    )> for Range
+{
+    fn from(
+        pair: (
+            &std::path::Path,
+            &shadowplay::puppet_parser::range::Location,
+        ),
+    ) -> Self {
+        let (path, location) = pair;
         Range {
             path: std::path::PathBuf::from(path),
             start: Location {
-                line: Some(span.location_line() as usize),
-                column: Some(span.get_utf8_column()),
-                index: Some(span.location_offset()),
+                line: Some(location.line() as usize),
+                column: Some(location.column()),
+                index: Some(location.offset()),
             },
             end: Location {
                 line: None,
@@ -191,26 +221,79 @@ impl<'a> From<&std::path::Path> for Range {
     }
 }
 
+/// Secondary location of error
+#[derive(Debug, Serialize)]
+pub struct Label {
+    pub message: String,
+    pub range: Range,
+}
+
 #[derive(Debug, Serialize)]
 pub struct Error {
     pub error_type: Type,
+    pub severity: Severity,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_subtype: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
+    /// Description of lint, used as rule metadata of reports
+    #[serde(skip)]
+    pub lint_description: Option<String>,
     pub range: Range,
+    /// Location-independent identity of lint finding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<Label>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
 }
 
-impl<'a> From<(&std::path::Path, &shadowplay::puppet_parser::ParseError<'a>)> for Error {
+impl From<(&std::path::Path, &shadowplay::puppet_parser::ParseError)> for Error {
     fn from(pair: (&std::path::Path, &shadowplay::puppet_parser::ParseError)) -> Self {
         let (path, parse_error) = pair;
         Self {
             error_type: Type::ManifestSyntax,
+            severity: Severity::Error,
+            fingerprint: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
             message: parse_error.message().clone(),
             url: parse_error.url().clone(),
-            range: Range::from((path, parse_error.span())),
+            lint_description: None,
+            range: Range::from((path, parse_error.location())),
+            error_subtype: None,
+        }
+    }
+}
+
+impl
+    From<(
+        &std::path::Path,
+        &shadowplay::puppet_lang::statement::SyntaxError<shadowplay::puppet_parser::range::Range>,
+    )> for Error
+{
+    fn from(
+        pair: (
+            &std::path::Path,
+            &shadowplay::puppet_lang::statement::SyntaxError<
+                shadowplay::puppet_parser::range::Range,
+            >,
+        ),
+    ) -> Self {
+        let (path, syntax_error) = pair;
+        Self {
+            error_type: Type::ManifestSyntax,
+            severity: Severity::Error,
+            fingerprint: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            message: Some(syntax_error.message.clone()),
+            url: syntax_error.url.clone(),
+            lint_description: None,
+            range: Range::from((path, &syntax_error.location)),
             error_subtype: None,
         }
     }
@@ -221,8 +304,20 @@ impl From<(&std::path::Path, &shadowplay::puppet_pp_lint::lint::LintError)> for
         let (path, lint_error) = pair;
         Self {
             error_type: Type::ManifestLint,
+            severity: lint_error.severity,
+            fingerprint: None,
+            labels: lint_error
+                .labels
+                .iter()
+                .map(|label| Label {
+                    message: label.message.clone(),
+                    range: Range::from((path, &label.location)),
+                })
+                .collect(),
+            notes: lint_error.notes.clone(),
             message: Some(lint_error.message.clone()),
             url: lint_error.url.clone(),
+            lint_description: Some(lint_error.linter.description().to_string()),
             range: Range::from((path, &lint_error.location)),
             error_subtype: Some(lint_error.linter.name().to_string()),
         }
@@ -234,8 +329,13 @@ impl From<(&std::path::Path, &located_yaml::error::Error)> for Error {
         let (path, yaml_error) = pair;
         Self {
             error_type: Type::Yaml,
+            severity: Severity::Error,
+            fingerprint: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
             message: Some(yaml_error.to_string()),
             url: None,
+            lint_description: None,
             range: Range::from((path, &yaml_error.mark())),
             error_subtype: None,
         }
@@ -247,8 +347,13 @@ impl From<(&std::path::Path, Type, &str, &located_yaml::Marker)> for Error {
         let (path, error_type, message, marker) = tuple;
         Self {
             error_type,
+            severity: Severity::Error,
+            fingerprint: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
             message: Some(message.to_string()),
             url: None,
+            lint_description: None,
             range: Range::from((path, marker)),
             error_subtype: None,
         }
@@ -260,8 +365,13 @@ impl From<(&std::path::Path, &yaml_rust::scanner::ScanError)> for Error {
         let (path, yaml_error) = pair;
         Self {
             error_type: Type::Yaml,
+            severity: Severity::Error,
+            fingerprint: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
             message: Some(yaml_error.to_string()),
             url: None,
+            lint_description: None,
             range: Range::from((path, yaml_error.marker())),
             error_subtype: Some("scan_error".to_string()),
         }
@@ -272,13 +382,23 @@ impl Error {
     pub fn of_file(path: &std::path::Path, error_type: Type, message: &str) -> Self {
         Self {
             error_type,
+            severity: Severity::Error,
+            fingerprint: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
             message: Some(message.to_string()),
             url: None,
+            lint_description: None,
             range: Range::from(path),
             error_subtype: None,
         }
     }
 
+    pub fn with_fingerprint(mut self, fingerprint: String) -> Self {
+        self.fingerprint = Some(fingerprint);
+        self
+    }
+
     pub fn one_line(&self) -> String {
         let url = match &self.url {
             None => "".to_owned(),
@@ -288,20 +408,405 @@ impl Error {
             Some(message) => message,
             None => "(no error message)",
         };
-        format!(
-            "{} error in {}: {}{}",
-            self.error_type, self.range, message, url
-        )
+        let mut res = format!(
+            "{} {} in {}: {}{}",
+            self.error_type, self.severity, self.range, message, url
+        );
+        for label in &self.labels {
+            res.push_str(&format!("\n    {}: {}", label.range, label.message))
+        }
+        for note in &self.notes {
+            res.push_str(&format!("\n    note: {}", note))
+        }
+        res
     }
 
     pub fn as_json(&self) -> String {
         serde_json::to_string(self).unwrap()
     }
 
-    pub fn output(&self, format: &OutputFormat) -> String {
-        match format {
-            OutputFormat::OneLine => self.one_line(),
-            OutputFormat::Json => self.as_json(),
+    /// Error message with annotated source lines. Colored if stdout is a terminal.
+    pub fn pretty(&self) -> String {
+        use std::io::IsTerminal;
+        let colored = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        let source = std::fs::read_to_string(&self.range.path).unwrap_or_default();
+        self.render_pretty(&source, colored)
+    }
+
+    fn render_pretty(&self, source: &str, colored: bool) -> String {
+        let paint = |code: &str, text: &str| {
+            if colored {
+                format!("\x1b[{}m{}\x1b[0m", code, text)
+            } else {
+                text.to_owned()
+            }
+        };
+        let severity_color = match self.severity {
+            Severity::Error => "1;31",
+            Severity::Warning => "1;33",
+            Severity::Info => "1;36",
+            Severity::Hint => "1;32",
+        };
+
+        let lines: Vec<&str> = source.lines().collect();
+
+        let mut marks = snippet_marks(&self.range, &lines, true, None);
+        let mut foreign_labels = Vec::new();
+        for label in &self.labels {
+            if label.range.path == self.range.path {
+                marks.append(&mut snippet_marks(
+                    &label.range,
+                    &lines,
+                    false,
+                    Some(&label.message),
+                ))
+            } else {
+                foreign_labels.push(label)
+            }
+        }
+        marks.sort_by_key(|(line, mark)| (*line, !mark.primary, mark.from));
+
+        let width = marks
+            .iter()
+            .map(|(line, _)| line.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let gutter = |text: &str| paint("1;34", &format!("{:>width$} |", text, width = width));
+
+        let message = match &self.message {
+            Some(message) => message,
+            None => "(no error message)",
+        };
+        let mut res = format!(
+            "{}: {}",
+            paint(
+                severity_color,
+                &format!("{} {}", self.error_type, self.severity)
+            ),
+            paint("1", message)
+        );
+
+        let location = match (self.range.start.line, self.range.start.column) {
+            (Some(line), Some(column)) => {
+                format!("{}:{}:{}", self.range.path.display(), line, column)
+            }
+            (Some(line), None) => format!("{}:{}", self.range.path.display(), line),
+            _ => format!("{}", self.range.path.display()),
+        };
+        res.push_str(&format!(
+            "\n{:width$}{} {}",
+            "",
+            paint("1;34", "-->"),
+            location,
+            width = width
+        ));
+
+        if !marks.is_empty() {
+            res.push_str(&format!("\n{}", gutter("")));
+        }
+        let mut prev_line: Option<usize> = None;
+        for (line, mark) in &marks {
+            let text = lines[line - 1];
+            if prev_line != Some(*line) {
+                if let Some(prev_line) = prev_line {
+                    if *line > prev_line + 1 {
+                        res.push_str(&format!("\n{}", paint("1;34", "...")));
+                    }
+                }
+                res.push_str(&format!("\n{} {}", gutter(&line.to_string()), text));
+                prev_line = Some(*line);
+            }
+            // Keep tabs so marks are aligned with source text
+            let indent: String = text
+                .chars()
+                .take(mark.from - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let (ch, color) = if mark.primary {
+                ("^", severity_color)
+            } else {
+                ("-", "1;34")
+            };
+            let underline = ch.repeat(mark.to - mark.from + 1);
+            let underline = match mark.message {
+                Some(message) => format!("{} {}", underline, message),
+                None => underline,
+            };
+            res.push_str(&format!(
+                "\n{} {}{}",
+                gutter(""),
+                indent,
+                paint(color, &underline)
+            ));
+        }
+
+        let footer = |name: &str, value: &str| {
+            format!(
+                "\n{:width$} {} {}: {}",
+                "",
+                paint("1;34", "="),
+                paint("1", name),
+                value,
+                width = width
+            )
+        };
+        for label in foreign_labels {
+            res.push_str(&footer(&label.message, &label.range.to_string()))
+        }
+        for note in &self.notes {
+            res.push_str(&footer("note", note))
+        }
+        if let Some(lint) = &self.error_subtype {
+            res.push_str(&footer("lint", lint))
+        }
+        if let Some(url) = &self.url {
+            res.push_str(&footer("see", url))
+        }
+        res.push('\n');
+        res
+    }
+}
+
+/// Underline of range on single source line
+struct Mark<'a> {
+    /// First and last underlined characters, 1-based
+    from: usize,
+    to: usize,
+    primary: bool,
+    message: Option<&'a str>,
+}
+
+/// Marks of source lines covered by range. Only first and last lines of long ranges are shown.
+fn snippet_marks<'a>(
+    range: &Range,
+    lines: &[&str],
+    primary: bool,
+    message: Option<&'a str>,
+) -> Vec<(usize, Mark<'a>)> {
+    let start_line = match range.start.line {
+        Some(v) if v >= 1 && v <= lines.len() => v,
+        _ => return Vec::new(),
+    };
+    let end_line = range
+        .end
+        .line
+        .unwrap_or(start_line)
+        .clamp(start_line, lines.len());
+    let shown: Vec<usize> = if end_line - start_line > 3 {
+        vec![start_line, end_line]
+    } else {
+        (start_line..=end_line).collect()
+    };
+
+    shown
+        .iter()
+        .map(|line_no| {
+            let line = lines[line_no - 1];
+            let len = line.chars().count().max(1);
+            let from = if *line_no == start_line {
+                range.start.column.unwrap_or(1)
+            } else {
+                line.chars().take_while(|c| c.is_whitespace()).count() + 1
+            };
+            let to = if *line_no != end_line {
+                len
+            } else if range.end.line.is_some() {
+                range.end.column.unwrap_or(len)
+            } else {
+                from
+            };
+            let from = from.clamp(1, len);
+            let to = to.clamp(from, len);
+            let message = if Some(line_no) == shown.last() {
+                message
+            } else {
+                None
+            };
+            (
+                *line_no,
+                Mark {
+                    from,
+                    to,
+                    primary,
+                    message,
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+fn test_range(path: &str, start: (usize, usize), end: Option<(usize, usize)>) -> Range {
+    Range {
+        path: std::path::PathBuf::from(path),
+        start: Location {
+            line: Some(start.0),
+            column: Some(start.1),
+            index: None,
+        },
+        end: Location {
+            line: end.map(|v| v.0),
+            column: end.map(|v| v.1),
+            index: None,
+        },
+    }
+}
+
+#[test]
+fn test_pretty() {
+    let source = "class a {\n  $x = 1\n  notice($y)\n}\n";
+    let mut err = Error::of_file(
+        std::path::Path::new("a.pp"),
+        Type::ManifestLint,
+        "Unknown variable",
+    );
+    err.severity = Severity::Warning;
+    err.error_subtype = Some("ReferenceToUndefinedValue".to_string());
+    err.url = Some("https://example.com".to_string());
+    err.range = test_range("a.pp", (3, 10), Some((3, 11)));
+    err.labels = vec![
+        Label {
+            message: "similar variable".to_string(),
+            range: test_range("a.pp", (2, 3), Some((2, 4))),
+        },
+        Label {
+            message: "defined here".to_string(),
+            range: test_range("b.pp", (7, 1), None),
+        },
+    ];
+    err.notes = vec!["variables are case sensitive".to_string()];
+
+    assert_eq!(
+        err.render_pretty(source, false),
+        "Puppet manifest lint warning: Unknown variable
+ --> a.pp:3:10
+  |
+2 |   $x = 1
+  |   -- similar variable
+3 |   notice($y)
+  |          ^^
+  = defined here: \"b.pp\" at line 7 column 1
+  = note: variables are case sensitive
+  = lint: ReferenceToUndefinedValue
+  = see: https://example.com
+"
+    );
+
+    let colored = err.render_pretty(source, true);
+    assert!(colored.contains("\x1b[1;33mPuppet manifest lint warning\x1b[0m"));
+    assert!(colored.contains("\x1b[1;33m^^\x1b[0m"));
+}
+
+#[test]
+fn test_pretty_multiline() {
+    let source = (1..=12)
+        .map(|v| format!("line{}", v))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut err = Error::of_file(
+        std::path::Path::new("a.pp"),
+        Type::ManifestLint,
+        "Huge block",
+    );
+    // Long range shows only its first and last lines
+    err.range = test_range("a.pp", (2, 3), Some((10, 2)));
+    assert_eq!(
+        err.render_pretty(&source, false),
+        "Puppet manifest lint error: Huge block
+  --> a.pp:2:3
+   |
+ 2 | line2
+   |   ^^^
+...
+10 | line10
+   | ^^
+"
+    );
+
+    // Short range shows all lines
+    err.range = test_range("a.pp", (11, 5), Some((12, 3)));
+    assert_eq!(
+        err.render_pretty(&source, false),
+        "Puppet manifest lint error: Huge block
+  --> a.pp:11:5
+   |
+11 | line11
+   |     ^^
+12 | line12
+   | ^^^
+"
+    );
+}
+
+#[test]
+fn test_pretty_last_line() {
+    // Syntax error at EOF of file without trailing newline
+    let source = "class a {\n  $x = (";
+    let mut err = Error::of_file(
+        std::path::Path::new("a.pp"),
+        Type::ManifestSyntax,
+        "Unexpected EOF",
+    );
+    err.range = test_range("a.pp", (2, 9), None);
+    assert_eq!(
+        err.render_pretty(source, false),
+        "Puppet manifest syntax error: Unexpected EOF
+ --> a.pp:2:9
+  |
+2 |   $x = (
+  |        ^
+"
+    );
+
+    // Location out of source is shown without snippet
+    err.range = test_range("a.pp", (3, 1), None);
+    assert_eq!(
+        err.render_pretty(source, false),
+        "Puppet manifest syntax error: Unexpected EOF\n --> a.pp:3:1\n"
+    );
+}
+
+#[test]
+fn test_labels_and_notes_json() {
+    struct Lint;
+    impl shadowplay::puppet_pp_lint::lint::LintPass for Lint {
+        fn name(&self) -> &str {
+            "Lint"
+        }
+        fn description(&self) -> &str {
+            "Test lint"
         }
     }
+
+    let lint_error = shadowplay::puppet_pp_lint::lint::LintError::new(
+        Box::new(Lint),
+        "Duplicate",
+        &shadowplay::puppet_parser::range::Range::new(20, 3, 3, 24, 3, 7),
+    )
+    .with_label(
+        "first defined here",
+        &shadowplay::puppet_parser::range::Range::new(10, 2, 3, 14, 2, 7),
+    )
+    .with_note("names must be unique");
+    let err = Error::from((std::path::Path::new("a.pp"), &lint_error));
+
+    let json: serde_json::Value = serde_json::from_str(&err.as_json()).unwrap();
+    assert_eq!(
+        json["labels"],
+        serde_json::json!([{
+            "message": "first defined here",
+            "range": {
+                "path": "a.pp",
+                "start": {"line": 2, "column": 3, "index": 10},
+                "end": {"line": 2, "column": 7, "index": 14},
+            },
+        }])
+    );
+    assert_eq!(json["notes"], serde_json::json!(["names must be unique"]));
+
+    // Empty lists are omitted
+    let err = Error::of_file(std::path::Path::new("a.pp"), Type::ManifestLint, "x");
+    let json: serde_json::Value = serde_json::from_str(&err.as_json()).unwrap();
+    assert!(json.get("labels").is_none());
+    assert!(json.get("notes").is_none());
 }
@@ -7,10 +7,14 @@ use shadowplay::puppet_lang::toplevel::ToplevelVariant;
 #[derive(Default)]
 pub struct State {
     pp_ast_cache: std::collections::HashMap<std::path::PathBuf, Option<super::PuppetAst>>,
+    /// Top-level keys of all data files of hierarchy, None if hiera.yaml is not available
+    hiera_keys: Option<std::collections::HashSet<String>>,
 }
 
 #[derive(Debug, StructOpt)]
 pub struct Check {
+    /// Data files to check. If none specified, all data files of hierarchy in hiera.yaml are
+    /// checked.
     paths: Vec<std::path::PathBuf>,
 }
 
@@ -186,6 +190,41 @@ impl Check {
             _ => return errors,
         };
 
+        crate::hiera_interpolation::for_each_string(doc, &mut |yaml, s| {
+            let interpolations = match crate::hiera_interpolation::check(s) {
+                Ok(v) => v,
+                Err(err) => {
+                    errors.push(error::Error::from((
+                        file_path,
+                        error::Type::Hiera,
+                        err.as_str(),
+                        &yaml.marker,
+                    )));
+                    return;
+                }
+            };
+            let hiera_keys = match &state.hiera_keys {
+                Some(v) => v,
+                None => return,
+            };
+            for key in interpolations.iter().filter_map(|v| v.key()) {
+                // Keys of nested values are separated with dots
+                let root = key.split_once('.').map(|(root, _)| root);
+                if !hiera_keys.contains(key) && !root.is_some_and(|v| hiera_keys.contains(v)) {
+                    errors.push(error::Error::from((
+                        file_path,
+                        error::Type::Hiera,
+                        format!(
+                            "Interpolated key {:?} is not defined in any data file of hierarchy",
+                            key
+                        )
+                        .as_str(),
+                        &yaml.marker,
+                    )));
+                }
+            }
+        });
+
         let doc = match &doc.yaml {
             located_yaml::YamlElt::Hash(h) => h,
             _ => {
@@ -277,21 +316,200 @@ impl Check {
         &self,
         repo_path: &std::path::Path,
         config: &crate::config::Config,
-        format: &error::OutputFormat,
+        output: &mut crate::check::report::Output,
     ) -> crate::check::Summary {
         let mut state = State::default();
         let mut errors = 0;
-        for file_path in &self.paths {
-            let file_errors = self.check_file(repo_path, file_path, &mut state, config);
-            for err in &file_errors {
-                println!("{}", err.output(format))
+        let mut max_severity = None;
+
+        let hiera_path = repo_path.join("hiera.yaml");
+        let hiera_config = crate::hiera_config::HieraConfig::read(&hiera_path);
+        if let Ok(hiera_config) = &hiera_config {
+            state.hiera_keys = Some(hiera_keys(hiera_config, repo_path))
+        }
+
+        let paths = if self.paths.is_empty() {
+            match hiera_config {
+                Ok(hiera_config) => hiera_data_files(&hiera_config, repo_path),
+                Err(err) => {
+                    output.add(error::Error::of_file(
+                        &hiera_path,
+                        error::Type::Hiera,
+                        &err.to_string(),
+                    ));
+                    return crate::check::Summary {
+                        errors_count: 1,
+                        files_checked: 1,
+                        max_severity: Some(shadowplay::puppet_pp_lint::lint::Severity::Error),
+                    };
+                }
             }
+        } else {
+            // Explicitly given files are checked without hiera.yaml, but broken one is reported
+            if let Err(err) = hiera_config {
+                if hiera_path.exists() {
+                    output.add(error::Error::of_file(
+                        &hiera_path,
+                        error::Type::Hiera,
+                        &err.to_string(),
+                    ));
+                    errors += 1;
+                    max_severity = Some(shadowplay::puppet_pp_lint::lint::Severity::Error);
+                }
+            }
+            self.paths.clone()
+        };
+
+        for file_path in &paths {
+            output.add_file(file_path);
+            let file_errors = self.check_file(repo_path, file_path, &mut state, config);
             errors += file_errors.len();
+            max_severity = file_errors
+                .iter()
+                .map(|err| err.severity)
+                .chain(max_severity)
+                .max();
+            for err in file_errors {
+                output.add(err)
+            }
         }
 
         crate::check::Summary {
             errors_count: errors,
-            files_checked: self.paths.len(),
+            files_checked: paths.len(),
+            max_severity,
+        }
+    }
+}
+
+/// Data files of YAML backends referenced by hierarchy, without duplicates
+fn hiera_data_files(
+    hiera_config: &crate::hiera_config::HieraConfig,
+    repo_path: &std::path::Path,
+) -> Vec<std::path::PathBuf> {
+    let mut seen = std::collections::HashSet::new();
+    let mut res = Vec::new();
+    for file in hiera_config.all_data_files(repo_path) {
+        if !file.backend.is_yaml() {
+            log::debug!(
+                "Skipping {:?}, backend {} is not supported",
+                file.full_path,
+                file.backend
+            );
+            continue;
+        }
+        if seen.insert(file.full_path.clone()) {
+            res.push(file.full_path)
+        }
+    }
+    res
+}
+
+/// Top-level keys of data files of hierarchy
+fn hiera_keys(
+    hiera_config: &crate::hiera_config::HieraConfig,
+    repo_path: &std::path::Path,
+) -> std::collections::HashSet<String> {
+    let mut res = std::collections::HashSet::new();
+    for path in hiera_data_files(hiera_config, repo_path) {
+        let yaml = match std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|v| located_yaml::YamlLoader::load_from_str(&v).ok())
+        {
+            Some(v) => v,
+            None => continue,
+        };
+        for doc in &yaml.docs {
+            if let located_yaml::YamlElt::Hash(hash) = &doc.yaml {
+                res.extend(hash.keys().filter_map(|k| k.get_string()))
+            }
         }
     }
+    res
+}
+
+#[test]
+fn test_interpolated_keys() {
+    let repo = std::env::temp_dir().join(format!("shadowplay-hiera-keys-{}", std::process::id()));
+    std::fs::create_dir_all(repo.join("data/nodes")).unwrap();
+    std::fs::write(
+        repo.join("hiera.yaml"),
+        "version: 5\nhierarchy:\n  - name: nodes\n    path: \"nodes/%{trusted.certname}.yaml\"\n  - name: common\n    path: common.yaml\n",
+    )
+    .unwrap();
+    std::fs::write(repo.join("data/nodes/h1.yaml"), "node_key: 1\n").unwrap();
+    let common = repo.join("data/common.yaml");
+    std::fs::write(
+        &common,
+        r#"---
+nested:
+  key: 1
+defined: "%{lookup('node_key')} %{lookup('nested.key')}"
+aliased: "%{alias('nested')}"
+undefined: "%{hiera('missing')}"
+not_only_alias: "x %{alias('nested')}"
+"#,
+    )
+    .unwrap();
+
+    let hiera_config = crate::hiera_config::HieraConfig::read(&repo.join("hiera.yaml")).unwrap();
+    let mut state = State {
+        hiera_keys: Some(hiera_keys(&hiera_config, &repo)),
+        ..State::default()
+    };
+    let errors: Vec<_> = Check { paths: Vec::new() }
+        .check_file(
+            &repo,
+            &common,
+            &mut state,
+            &crate::config::Config::default(),
+        )
+        .into_iter()
+        .map(|v| (v.range.start.line, v.message.unwrap_or_default()))
+        .collect();
+    std::fs::remove_dir_all(&repo).unwrap();
+
+    assert_eq!(
+        errors,
+        vec![
+            (
+                Some(6),
+                "Interpolated key \"missing\" is not defined in any data file of hierarchy"
+                    .to_owned()
+            ),
+            (
+                Some(7),
+                "alias() must be the only content of string, found in \"x %{alias('nested')}\""
+                    .to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_broken_hiera_config_with_paths() {
+    let repo = std::env::temp_dir().join(format!("shadowplay-hiera-v3-{}", std::process::id()));
+    std::fs::create_dir_all(repo.join("data")).unwrap();
+    std::fs::write(
+        repo.join("hiera.yaml"),
+        ":backends:\n  - yaml\n:hierarchy:\n  - common\n",
+    )
+    .unwrap();
+    let common = repo.join("data/common.yaml");
+    std::fs::write(&common, "key: 1\n").unwrap();
+
+    let format = crate::check::error::OutputFormat::Json;
+    let mut output = crate::check::report::Output::new(&format);
+    let summary = Check {
+        paths: vec![common],
+    }
+    .check(&repo, &crate::config::Config::default(), &mut output);
+    std::fs::remove_dir_all(&repo).unwrap();
+
+    assert_eq!(summary.errors_count, 1);
+    assert_eq!(summary.files_checked, 1);
+    assert_eq!(
+        summary.max_severity,
+        Some(shadowplay::puppet_pp_lint::lint::Severity::Error)
+    );
 }
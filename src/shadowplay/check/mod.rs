@@ -1,8 +1,10 @@
 use shadowplay::puppet_parser::range::Range;
 
+pub mod baseline;
 pub mod error;
 pub mod hiera_yaml;
 pub mod pp;
+pub mod report;
 pub mod yaml;
 
 #[derive(Debug, Clone)]
@@ -14,7 +16,22 @@ pub struct PuppetAst {
 impl PuppetAst {
     pub fn parse(i: &str) -> Result<Self, nom::Err<shadowplay::puppet_parser::ParseError>> {
         let input = i.to_string();
-        let (_remaining, data) = shadowplay::puppet_parser::toplevel::parse_file(shadowplay::puppet_parser::Span::new(i))?;
+        let state = shadowplay::puppet_parser::ParserState::default();
+        let (_remaining, data) = shadowplay::puppet_parser::toplevel::parse_file(
+            shadowplay::puppet_parser::Span::new_extra(i, &state),
+        )?;
+        Ok(Self { data, input })
+    }
+
+    /// Never fails on syntax errors, broken statements are kept in AST as SyntaxError nodes
+    pub fn parse_with_recovery(
+        i: &str,
+    ) -> Result<Self, nom::Err<shadowplay::puppet_parser::ParseError>> {
+        let input = i.to_string();
+        let state = shadowplay::puppet_parser::ParserState::default();
+        let (_remaining, data) = shadowplay::puppet_parser::toplevel::parse_file_with_recovery(
+            shadowplay::puppet_parser::Span::new_extra(i, &state),
+        )?;
         Ok(Self { data, input })
     }
 }
@@ -22,4 +39,13 @@ impl PuppetAst {
 pub struct Summary {
     pub errors_count: usize,
     pub files_checked: usize,
+    /// Severity of the most important detected error
+    pub max_severity: Option<shadowplay::puppet_pp_lint::lint::Severity>,
+}
+
+impl Summary {
+    /// Whether issue of threshold or higher severity was detected
+    pub fn is_failed(&self, threshold: shadowplay::puppet_pp_lint::lint::Severity) -> bool {
+        self.max_severity >= Some(threshold)
+    }
 }
@@ -1,21 +1,105 @@
 use crate::check::error;
 use structopt::StructOpt;
 
+/// Limit of fix-and-check iterations
+const MAX_FIX_PASSES: usize = 10;
+
 #[derive(Debug, StructOpt)]
 pub struct Check {
+    /// Apply automatic fixes in place
+    #[structopt(long)]
+    fix: bool,
+    /// Print unified diff of automatic fixes instead of applying them
+    #[structopt(long, conflicts_with = "fix")]
+    fix_dry_run: bool,
+    /// Record current findings into baseline file
+    #[structopt(long)]
+    write_baseline: Option<std::path::PathBuf>,
+    /// Report only findings which are not recorded in baseline file
+    #[structopt(long, conflicts_with = "write-baseline")]
+    baseline: Option<std::path::PathBuf>,
     paths: Vec<std::path::PathBuf>,
 }
 
+struct Linted {
+    errors: Vec<error::Error>,
+    /// Manifest has syntax errors
+    is_broken: bool,
+    /// Manifest with automatic fixes applied. None if there is nothing to fix.
+    fixed: Option<String>,
+}
+
 impl Check {
+    fn lint(
+        &self,
+        storage: &shadowplay::puppet_pp_lint::lint::Storage,
+        ctx: &mut shadowplay::puppet_pp_lint::ctx::Ctx<shadowplay::puppet_parser::range::Range>,
+        file_path: &std::path::Path,
+        pp: &str,
+    ) -> Linted {
+        ctx.new_file(file_path);
+
+        let ast = match super::PuppetAst::parse_with_recovery(pp) {
+            Err(err) => {
+                let err = match err {
+                    nom::Err::Incomplete(_) => {
+                        // nom::complete doesn't generate this state
+                        unreachable!()
+                    }
+                    nom::Err::Error(v) => v,
+                    nom::Err::Failure(v) => v,
+                };
+                return Linted {
+                    errors: vec![error::Error::from((file_path, &err))],
+                    is_broken: true,
+                    fixed: None,
+                };
+            }
+            Ok(v) => v,
+        };
+
+        let linter = shadowplay::puppet_pp_lint::lint::AstLinter;
+
+        let errors = linter.check_file(storage, ctx, &ast.data);
+
+        let syntax_errors = shadowplay::puppet_parser::toplevel::syntax_errors(&ast.data.value);
+        let is_broken = !syntax_errors.is_empty();
+
+        let fixed = if (self.fix || self.fix_dry_run) && !is_broken {
+            shadowplay::puppet_pp_lint::fix::apply(pp, &errors)
+        } else {
+            None
+        };
+
+        let errors = syntax_errors
+            .into_iter()
+            .map(|err| error::Error::from((file_path, err)))
+            .chain(errors.into_iter().map(|err| {
+                error::Error::from((file_path, &err)).with_fingerprint(
+                    crate::check::baseline::fingerprint(
+                        err.linter.name(),
+                        pp,
+                        &ast.data.value,
+                        &err.location,
+                    ),
+                )
+            }))
+            .collect();
+
+        Linted {
+            errors,
+            is_broken,
+            fixed,
+        }
+    }
+
     pub fn check_file(
         &self,
         config: &crate::config::Config,
         ctx: &mut shadowplay::puppet_pp_lint::ctx::Ctx<shadowplay::puppet_parser::range::Range>,
-        _repo_path: &std::path::Path,
+        repo_path: &std::path::Path,
         file_path: &std::path::Path,
     ) -> Vec<error::Error> {
-        ctx.new_file();
-
         let pp = match std::fs::read_to_string(file_path) {
             Err(err) => {
                 return vec![error::Error::of_file(
@@ -27,54 +111,211 @@ impl Check {
             Ok(v) => v,
         };
 
-        let ast = match super::PuppetAst::parse(&pp) {
-            Err(err) => {
-                let err = match err {
-                    nom::Err::Incomplete(_) => {
-                        // nom::complete doesn't generate this state
-                        unreachable!()
-                    }
-                    nom::Err::Error(v) => v,
-                    nom::Err::Failure(v) => v,
-                };
-                return vec![error::Error::from((file_path, &err))];
+        let storage = config.pp(repo_path, file_path);
+
+        let linted = self.lint(&storage, ctx, file_path, &pp);
+        let mut errors = linted.errors;
+        let mut fixed = linted.fixed;
+
+        let mut source = pp.clone();
+        let mut passes = 0;
+        while let Some(text) = fixed.take() {
+            let linted = self.lint(&storage, ctx, file_path, &text);
+            if linted.is_broken {
+                // Fixes broke the manifest, keep the previous version
+                break;
             }
-            Ok(v) => v,
-        };
+            source = text;
+            passes += 1;
+            if !self.fix_dry_run {
+                // Report errors which are left after fixing
+                errors = linted.errors;
+            }
+            if passes < MAX_FIX_PASSES {
+                fixed = linted.fixed;
+            }
+        }
 
+        if source != pp {
+            if self.fix_dry_run {
+                let path = file_path.to_string_lossy();
+                print!(
+                    "{}",
+                    similar::TextDiff::from_lines(&pp, &source)
+                        .unified_diff()
+                        .header(&path, &path)
+                );
+            } else if let Err(err) = std::fs::write(file_path, &source) {
+                errors.push(error::Error::of_file(
+                    file_path,
+                    error::Type::FileError,
+                    &format!("Cannot write fixed manifest: {}", err),
+                ));
+            }
+        }
+
+        errors
+    }
+
+    /// Runs late lint passes. Lints disabled for the file by path overrides are not reported.
+    fn check_repository(
+        &self,
+        config: &crate::config::Config,
+        ctx: &shadowplay::puppet_pp_lint::ctx::Ctx<shadowplay::puppet_parser::range::Range>,
+        repo_path: &std::path::Path,
+    ) -> Vec<(std::path::PathBuf, Vec<error::Error>)> {
         let linter = shadowplay::puppet_pp_lint::lint::AstLinter;
+        let repository_errors =
+            linter.check_repository(&config.checks.pp, &ctx.repository.borrow());
 
-        let mut errors = Vec::new();
-        for statement in &ast.data.value {
-            errors.append(&mut linter.check_statement(&config.checks.pp, ctx, statement));
+        let mut files: std::collections::BTreeMap<std::path::PathBuf, Vec<_>> =
+            std::collections::BTreeMap::new();
+        for err in repository_errors {
+            files.entry(err.file).or_default().push(err.error)
         }
 
-        errors
-            .into_iter()
-            .map(|err| error::Error::from((file_path, &err)))
-            .collect()
+        let mut r = Vec::new();
+        for (file_path, lint_errors) in files {
+            let storage = config.pp(repo_path, &file_path);
+            let pp = std::fs::read_to_string(&file_path).unwrap_or_default();
+            let statements = super::PuppetAst::parse_with_recovery(&pp)
+                .map(|v| v.data.value)
+                .unwrap_or_default();
+            let errors = lint_errors
+                .into_iter()
+                .filter(|err| storage.is_enabled(err.linter.name()))
+                .map(|mut err| {
+                    err.severity = storage.severity(err.linter.as_ref());
+                    error::Error::from((file_path.as_path(), &err)).with_fingerprint(
+                        crate::check::baseline::fingerprint(
+                            err.linter.name(),
+                            &pp,
+                            &statements,
+                            &err.location,
+                        ),
+                    )
+                })
+                .collect();
+            r.push((file_path, errors))
+        }
+        r
     }
 
     pub fn check(
         &self,
         repo_path: &std::path::Path,
         config: &crate::config::Config,
-        format: &error::OutputFormat,
+        output: &mut crate::check::report::Output,
     ) -> crate::check::Summary {
         let mut ctx = shadowplay::puppet_pp_lint::ctx::Ctx::new(repo_path);
 
-        let mut errors = 0;
+        let unmatched = match &self.baseline {
+            Some(path) => match crate::check::baseline::Baseline::read(path) {
+                Ok(v) => Some(crate::check::baseline::Unmatched::new(v)),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1)
+                }
+            },
+            None => None,
+        };
+        let mut report = Report {
+            unmatched,
+            recorded: None,
+            errors: 0,
+            max_severity: None,
+        };
+        if self.write_baseline.is_some() {
+            report.recorded = Some(crate::check::baseline::Baseline::default())
+        }
+        let mut checked_files = std::collections::HashSet::new();
+
         for file_path in &self.paths {
+            output.add_file(file_path);
             let file_errors = self.check_file(config, &mut ctx, repo_path, file_path);
-            for err in &file_errors {
-                println!("{}", err.output(format))
+            let file = crate::config::Config::relative_path(repo_path, file_path)
+                .to_string_lossy()
+                .to_string();
+            report.add(&file, file_errors, output);
+            let _ = checked_files.insert(file);
+        }
+
+        for (file_path, file_errors) in self.check_repository(config, &ctx, repo_path) {
+            let file = crate::config::Config::relative_path(repo_path, &file_path)
+                .to_string_lossy()
+                .to_string();
+            report.add(&file, file_errors, output);
+        }
+
+        if let Some(unmatched) = &report.unmatched {
+            let fixed = unmatched.fixed_count(&checked_files);
+            if output.format.is_human() && fixed > 0 {
+                println!("{} baseline entries are fixed and can be removed", fixed)
+            }
+        }
+
+        if let (Some(path), Some(recorded)) = (&self.write_baseline, report.recorded) {
+            let count = recorded.entries.len();
+            match recorded.write(path) {
+                Ok(()) if output.format.is_human() => {
+                    println!("Recorded {} findings into baseline {:?}", count, path)
+                }
+                Ok(()) => (),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1)
+                }
             }
-            errors += file_errors.len();
         }
 
         crate::check::Summary {
-            errors_count: errors,
+            errors_count: report.errors,
             files_checked: self.paths.len(),
+            max_severity: report.max_severity,
+        }
+    }
+}
+
+/// Accumulates findings of the run
+struct Report {
+    /// Baseline entries not matched yet, if baseline is used
+    unmatched: Option<crate::check::baseline::Unmatched>,
+    /// Findings to be recorded into new baseline
+    recorded: Option<crate::check::baseline::Baseline>,
+    errors: usize,
+    max_severity: Option<shadowplay::puppet_pp_lint::lint::Severity>,
+}
+
+impl Report {
+    /// Outputs findings of file which are not recorded in baseline
+    fn add(
+        &mut self,
+        file: &str,
+        mut errors: Vec<error::Error>,
+        output: &mut crate::check::report::Output,
+    ) {
+        if let Some(unmatched) = &mut self.unmatched {
+            errors.retain(|err| match crate::check::baseline::Entry::new(file, err) {
+                Some(entry) => !unmatched.take(&entry),
+                None => true,
+            });
+        }
+        if let Some(recorded) = &mut self.recorded {
+            recorded.entries.extend(
+                errors
+                    .iter()
+                    .filter_map(|err| crate::check::baseline::Entry::new(file, err)),
+            );
+        }
+
+        self.errors += errors.len();
+        self.max_severity = errors
+            .iter()
+            .map(|err| err.severity)
+            .chain(self.max_severity)
+            .max();
+        for err in errors {
+            output.add(err)
         }
     }
 }
@@ -0,0 +1,470 @@
+use serde_json::json;
+use shadowplay::puppet_pp_lint::lint::Severity;
+
+use crate::check::error::{Error, OutputFormat, Type};
+
+/// Destination of findings. Line-oriented formats are printed as findings come, report formats
+/// need the whole result set and are printed by `finish`.
+pub struct Output<'a> {
+    pub format: &'a OutputFormat,
+    /// Checked files in order of checking
+    files: Vec<std::path::PathBuf>,
+    known_files: std::collections::HashSet<std::path::PathBuf>,
+    errors: Vec<Error>,
+}
+
+impl<'a> Output<'a> {
+    pub fn new(format: &'a OutputFormat) -> Self {
+        Self {
+            format,
+            files: Vec::new(),
+            known_files: std::collections::HashSet::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Registers checked file, so reports list files without findings too
+    pub fn add_file(&mut self, path: &std::path::Path) {
+        if self.known_files.insert(path.to_path_buf()) {
+            self.files.push(path.to_path_buf())
+        }
+    }
+
+    pub fn add(&mut self, err: Error) {
+        match self.format {
+            OutputFormat::OneLine => println!("{}", err.one_line()),
+            OutputFormat::Json => println!("{}", err.as_json()),
+            OutputFormat::Pretty => println!("{}", err.pretty()),
+            OutputFormat::Sarif
+            | OutputFormat::Checkstyle
+            | OutputFormat::Junit
+            | OutputFormat::Gitlab => {
+                self.add_file(&err.range.path);
+                self.errors.push(err)
+            }
+        }
+    }
+
+    /// Prints report of collected findings
+    pub fn finish(self) {
+        let report = match self.format {
+            OutputFormat::OneLine | OutputFormat::Json | OutputFormat::Pretty => return,
+            OutputFormat::Sarif => self.sarif(),
+            OutputFormat::Checkstyle => self.checkstyle(),
+            OutputFormat::Junit => self.junit(),
+            OutputFormat::Gitlab => self.gitlab(),
+        };
+        println!("{}", report)
+    }
+
+    fn errors_of<'b>(&'b self, path: &'b std::path::Path) -> impl Iterator<Item = &'b Error> {
+        self.errors.iter().filter(move |err| err.range.path == path)
+    }
+
+    fn sarif(&self) -> String {
+        let mut rules: Vec<serde_json::Value> = Vec::new();
+        let mut rule_index = std::collections::HashMap::new();
+        let mut results = Vec::new();
+
+        for err in &self.errors {
+            let id = rule_id(err);
+            let index = *rule_index.entry(id.clone()).or_insert_with(|| {
+                let mut rule = json!({
+                    "id": id,
+                    "shortDescription": {"text": rule_description(err)},
+                });
+                if let Some(url) = &err.url {
+                    rule["helpUri"] = json!(url)
+                }
+                rules.push(rule);
+                rules.len() - 1
+            });
+
+            let mut result = json!({
+                "ruleId": id,
+                "ruleIndex": index,
+                "level": match err.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Info | Severity::Hint => "note",
+                },
+                "message": {"text": message(err)},
+                "locations": [sarif_location(&err.range)],
+            });
+            if !err.labels.is_empty() {
+                result["relatedLocations"] = err
+                    .labels
+                    .iter()
+                    .enumerate()
+                    .map(|(i, label)| {
+                        let mut location = sarif_location(&label.range);
+                        location["id"] = json!(i);
+                        location["message"] = json!({ "text": label.message });
+                        location
+                    })
+                    .collect();
+            }
+            if let Some(fingerprint) = &err.fingerprint {
+                result["partialFingerprints"] = json!({ "shadowplay/v1": fingerprint });
+            }
+            results.push(result)
+        }
+
+        let log = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "shadowplay",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": "https://github.com/mailru/shadowplay",
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }]
+        });
+        serde_json::to_string_pretty(&log).unwrap()
+    }
+
+    fn checkstyle(&self) -> String {
+        let mut res = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        res.push_str("<checkstyle version=\"4.3\">\n");
+        for path in &self.files {
+            res.push_str(&format!(
+                "  <file name=\"{}\">\n",
+                xml_escape(&path.to_string_lossy())
+            ));
+            for err in self.errors_of(path) {
+                res.push_str("    <error");
+                if let Some(line) = err.range.start.line {
+                    res.push_str(&format!(" line=\"{}\"", line))
+                }
+                if let Some(column) = err.range.start.column {
+                    res.push_str(&format!(" column=\"{}\"", column))
+                }
+                let severity = match err.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Info | Severity::Hint => "info",
+                };
+                res.push_str(&format!(
+                    " severity=\"{}\" message=\"{}\" source=\"shadowplay.{}\"/>\n",
+                    severity,
+                    xml_escape(message(err)),
+                    xml_escape(&rule_id(err))
+                ));
+            }
+            res.push_str("  </file>\n");
+        }
+        res.push_str("</checkstyle>");
+        res
+    }
+
+    fn junit(&self) -> String {
+        let failures = self
+            .files
+            .iter()
+            .filter(|path| self.errors_of(path).next().is_some())
+            .count();
+        let counters = format!(
+            "name=\"shadowplay\" tests=\"{}\" failures=\"{}\"",
+            self.files.len(),
+            failures
+        );
+
+        let mut res = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        res.push_str(&format!("<testsuites {}>\n", counters));
+        res.push_str(&format!("  <testsuite {}>\n", counters));
+        for path in &self.files {
+            let name = xml_escape(&path.to_string_lossy());
+            let errors: Vec<_> = self.errors_of(path).collect();
+            let severity = match errors.iter().map(|err| err.severity).max() {
+                None => {
+                    res.push_str(&format!(
+                        "    <testcase classname=\"shadowplay\" name=\"{}\"/>\n",
+                        name
+                    ));
+                    continue;
+                }
+                Some(v) => v,
+            };
+            res.push_str(&format!(
+                "    <testcase classname=\"shadowplay\" name=\"{}\">\n",
+                name
+            ));
+            let details: Vec<_> = errors.iter().map(|err| err.one_line()).collect();
+            res.push_str(&format!(
+                "      <failure message=\"{} issues\" type=\"{}\">{}</failure>\n",
+                errors.len(),
+                severity,
+                xml_escape(&details.join("\n"))
+            ));
+            res.push_str("    </testcase>\n");
+        }
+        res.push_str("  </testsuite>\n");
+        res.push_str("</testsuites>");
+        res
+    }
+
+    fn gitlab(&self) -> String {
+        // Fingerprints must be unique, so identical findings are numbered
+        let mut seen: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+        let issues: Vec<_> = self
+            .errors
+            .iter()
+            .map(|err| {
+                let path = err.range.path.to_string_lossy().to_string();
+                let check_name = rule_id(err);
+                let identity = err
+                    .fingerprint
+                    .clone()
+                    .unwrap_or_else(|| message(err).to_string());
+                let key = crate::check::baseline::hash(&[&path, &check_name, &identity]);
+                let count = seen.entry(key.clone()).or_insert(0);
+                *count += 1;
+
+                json!({
+                    "description": message(err),
+                    "check_name": check_name,
+                    "fingerprint": crate::check::baseline::hash(&[&key, &count.to_string()]),
+                    "severity": match err.severity {
+                        Severity::Error => "critical",
+                        Severity::Warning => "major",
+                        Severity::Info => "minor",
+                        Severity::Hint => "info",
+                    },
+                    "location": {
+                        "path": path,
+                        "lines": {"begin": err.range.start.line.unwrap_or(1)},
+                    },
+                })
+            })
+            .collect();
+        serde_json::to_string_pretty(&issues).unwrap()
+    }
+}
+
+/// Lint name or type of non-lint error
+fn rule_id(err: &Error) -> String {
+    match (&err.error_type, &err.error_subtype) {
+        (Type::ManifestLint, Some(lint)) => lint.clone(),
+        (error_type, _) => format!("{:?}", error_type),
+    }
+}
+
+fn rule_description(err: &Error) -> String {
+    match &err.lint_description {
+        Some(v) => v.clone(),
+        None => format!("{} error", err.error_type),
+    }
+}
+
+fn message(err: &Error) -> &str {
+    match &err.message {
+        Some(message) => message,
+        None => "(no error message)",
+    }
+}
+
+fn sarif_location(range: &crate::check::error::Range) -> serde_json::Value {
+    let mut location = json!({
+        "physicalLocation": {
+            "artifactLocation": {"uri": range.path.to_string_lossy().replace('\\', "/")},
+        }
+    });
+    if let Some(line) = range.start.line {
+        let mut region = json!({ "startLine": line });
+        if let Some(column) = range.start.column {
+            region["startColumn"] = json!(column)
+        }
+        if let Some(line) = range.end.line {
+            region["endLine"] = json!(line);
+            // SARIF end column is exclusive
+            if let Some(column) = range.end.column {
+                region["endColumn"] = json!(column + 1)
+            }
+        }
+        location["physicalLocation"]["region"] = region;
+    }
+    location
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '\'' => res.push_str("&apos;"),
+            '\n' | '\t' => res.push(c),
+            // Not allowed in XML 1.0
+            c if (c as u32) < 0x20 => (),
+            c => res.push(c),
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+fn test_error(path: &str, lint: Option<&str>, message: &str, severity: Severity) -> Error {
+    let mut err = Error::of_file(
+        std::path::Path::new(path),
+        if lint.is_some() {
+            Type::ManifestLint
+        } else {
+            Type::Hiera
+        },
+        message,
+    );
+    err.severity = severity;
+    err.error_subtype = lint.map(|v| v.to_string());
+    err.lint_description = lint.map(|v| format!("Description of {}", v));
+    err.range.start.line = Some(3);
+    err.range.start.column = Some(5);
+    err
+}
+
+#[test]
+fn test_xml_escaping() {
+    let format = OutputFormat::Checkstyle;
+    let mut output = Output::new(&format);
+    output.add_file(std::path::Path::new("ok.pp"));
+    output.add(test_error(
+        "a&b<c>.pp",
+        Some("Lint"),
+        "'x' is \"<bad>\" & \u{1}wrong",
+        Severity::Warning,
+    ));
+
+    assert_eq!(
+        output.checkstyle(),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<checkstyle version=\"4.3\">
+  <file name=\"ok.pp\">
+  </file>
+  <file name=\"a&amp;b&lt;c&gt;.pp\">
+    <error line=\"3\" column=\"5\" severity=\"warning\" message=\"&apos;x&apos; is &quot;&lt;bad&gt;&quot; &amp; wrong\" source=\"shadowplay.Lint\"/>
+  </file>
+</checkstyle>"
+    );
+
+    assert_eq!(
+        output.junit(),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<testsuites name=\"shadowplay\" tests=\"2\" failures=\"1\">
+  <testsuite name=\"shadowplay\" tests=\"2\" failures=\"1\">
+    <testcase classname=\"shadowplay\" name=\"ok.pp\"/>
+    <testcase classname=\"shadowplay\" name=\"a&amp;b&lt;c&gt;.pp\">
+      <failure message=\"1 issues\" type=\"warning\">Puppet manifest lint warning in &quot;a&amp;b&lt;c&gt;.pp&quot; at line 3 column 5: &apos;x&apos; is &quot;&lt;bad&gt;&quot; &amp; wrong</failure>
+    </testcase>
+  </testsuite>
+</testsuites>"
+    );
+}
+
+#[test]
+fn test_sarif_rules() {
+    let format = OutputFormat::Sarif;
+    let mut output = Output::new(&format);
+    output.add(test_error("a.pp", Some("First"), "1", Severity::Error));
+    let mut err = test_error("a.pp", Some("Second"), "2", Severity::Info);
+    err.url = Some("https://example.com/second".to_string());
+    output.add(err);
+    output.add(test_error("b.pp", Some("First"), "3", Severity::Warning));
+    output.add(test_error("hiera.yaml", None, "4", Severity::Hint));
+
+    let log: serde_json::Value = serde_json::from_str(&output.sarif()).unwrap();
+    let run = &log["runs"][0];
+    assert_eq!(
+        run["tool"]["driver"]["rules"],
+        json!([
+            {"id": "First", "shortDescription": {"text": "Description of First"}},
+            {
+                "id": "Second",
+                "shortDescription": {"text": "Description of Second"},
+                "helpUri": "https://example.com/second",
+            },
+            {"id": "Hiera", "shortDescription": {"text": "Hiera error"}},
+        ])
+    );
+
+    let results: Vec<_> = run["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| {
+            (
+                v["ruleId"].as_str().unwrap(),
+                v["ruleIndex"].as_u64().unwrap(),
+                v["level"].as_str().unwrap(),
+                v["message"]["text"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            ("First", 0, "error", "1"),
+            ("Second", 1, "note", "2"),
+            ("First", 0, "warning", "3"),
+            ("Hiera", 2, "note", "4"),
+        ]
+    );
+    assert_eq!(
+        run["results"][0]["locations"][0]["physicalLocation"],
+        json!({
+            "artifactLocation": {"uri": "a.pp"},
+            "region": {"startLine": 3, "startColumn": 5},
+        })
+    );
+}
+
+#[test]
+fn test_gitlab() {
+    let format = OutputFormat::Gitlab;
+    let mut output = Output::new(&format);
+    output.add(test_error("a.pp", Some("Lint"), "same", Severity::Error));
+    output.add(test_error("a.pp", Some("Lint"), "same", Severity::Warning));
+    output.add(
+        test_error("a.pp", Some("Lint"), "moved", Severity::Info)
+            .with_fingerprint("statement".to_string()),
+    );
+    output.add(test_error("hiera.yaml", None, "broken", Severity::Hint));
+
+    let issues: serde_json::Value = serde_json::from_str(&output.gitlab()).unwrap();
+    let issues = issues.as_array().unwrap();
+    let severities: Vec<_> = issues
+        .iter()
+        .map(|v| v["severity"].as_str().unwrap())
+        .collect();
+    assert_eq!(severities, vec!["critical", "major", "minor", "info"]);
+
+    let fingerprints: Vec<_> = issues
+        .iter()
+        .map(|v| v["fingerprint"].as_str().unwrap())
+        .collect();
+    let key = crate::check::baseline::hash(&["a.pp", "Lint", "same"]);
+    // Identical findings are numbered to keep fingerprints unique
+    assert_eq!(fingerprints[0], crate::check::baseline::hash(&[&key, "1"]));
+    assert_eq!(fingerprints[1], crate::check::baseline::hash(&[&key, "2"]));
+    // Fingerprint of finding does not depend on its message
+    let key = crate::check::baseline::hash(&["a.pp", "Lint", "statement"]);
+    assert_eq!(fingerprints[2], crate::check::baseline::hash(&[&key, "1"]));
+    let key = crate::check::baseline::hash(&["hiera.yaml", "Hiera", "broken"]);
+    assert_eq!(fingerprints[3], crate::check::baseline::hash(&[&key, "1"]));
+
+    assert_eq!(
+        issues[3],
+        json!({
+            "description": "broken",
+            "check_name": "Hiera",
+            "fingerprint": fingerprints[3],
+            "severity": "info",
+            "location": {"path": "hiera.yaml", "lines": {"begin": 3}},
+        })
+    );
+}
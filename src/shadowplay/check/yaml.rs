@@ -94,20 +94,28 @@ impl Check {
         &self,
         repo_path: &std::path::Path,
         _config: &crate::config::Config,
-        format: &error::OutputFormat,
+        output: &mut crate::check::report::Output,
     ) -> crate::check::Summary {
         let mut errors = 0;
+        let mut max_severity = None;
         for file_path in &self.paths {
+            output.add_file(file_path);
             let file_errors = self.check_file(repo_path, file_path);
-            for err in &file_errors {
-                println!("{}", err.output(format))
-            }
             errors += file_errors.len();
+            max_severity = file_errors
+                .iter()
+                .map(|err| err.severity)
+                .chain(max_severity)
+                .max();
+            for err in file_errors {
+                output.add(err)
+            }
         }
 
         crate::check::Summary {
             errors_count: errors,
             files_checked: self.paths.len(),
+            max_severity,
         }
     }
 }
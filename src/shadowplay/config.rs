@@ -1,6 +1,9 @@
 use anyhow::{format_err, Result};
 use serde::{Deserialize, Serialize};
 
+/// Name of repository-local config file
+pub const REPO_CONFIG: &str = ".shadowplay.yaml";
+
 #[derive(Deserialize, Clone, Default, Serialize)]
 pub struct ChecksHieraYaml {
     #[serde(default)]
@@ -15,17 +18,381 @@ pub struct Checks {
     pub pp: shadowplay::puppet_pp_lint::lint::Storage,
 }
 
+/// Changes of *.pp lints configuration
+#[derive(Deserialize, Clone, Default, Serialize)]
+pub struct PpOverride {
+    /// Lints to enable or reconfigure
+    #[serde(default)]
+    pub early_pass: Vec<shadowplay::puppet_pp_lint::lint::EarlyLintPassVariant>,
+    #[serde(default)]
+    pub late_pass: Vec<shadowplay::puppet_pp_lint::lint::LateLintPassVariant>,
+    /// Names of lints to disable
+    #[serde(default)]
+    pub disable: Vec<String>,
+    #[serde(default)]
+    pub severity: std::collections::BTreeMap<String, shadowplay::puppet_pp_lint::lint::Severity>,
+}
+
+/// Configuration changes applied to files matching any of glob patterns. Patterns are matched
+/// against paths relative to repository root.
+#[derive(Deserialize, Clone, Serialize)]
+pub struct Override {
+    #[serde(with = "glob_patterns")]
+    pub paths: Vec<glob::Pattern>,
+    #[serde(default)]
+    pub pp: PpOverride,
+}
+
+/// Glob patterns are compiled once when config is read, invalid ones are reported as config errors
+mod glob_patterns {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        patterns: &[glob::Pattern],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(patterns.iter().map(|v| v.as_str()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<glob::Pattern>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|pattern| {
+                glob::Pattern::new(pattern).map_err(|err| {
+                    D::Error::custom(format!("invalid glob pattern {:?}: {}", pattern, err))
+                })
+            })
+            .collect()
+    }
+}
+
+impl Override {
+    pub fn matches(&self, path: &std::path::Path) -> bool {
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        self.paths
+            .iter()
+            .any(|pattern| pattern.matches_path_with(path, options))
+    }
+}
+
+/// Site-specific variable used in hiera.yaml paths. Value is taken from the first available
+/// source: constant `value`, variable `from` or key `hiera_key` of hiera data. Then it is
+/// transformed by `regex` and `replace` if specified.
+#[derive(Deserialize, Clone, Serialize)]
+pub struct HieraVariable {
+    /// Name as used in hiera.yaml, e.g. "::extgrp"
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Fact ("facts.networking.fqdn", "::fqdn"), variable passed with --set or variable
+    /// defined earlier in the list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// Top-level key of hiera data. Value found first in hierarchy is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hiera_key: Option<String>,
+    /// Value must match. Unless `replace` is specified, first capture group or the whole match
+    /// becomes the value.
+    #[serde(default, with = "serde_regex", skip_serializing_if = "Option::is_none")]
+    pub regex: Option<regex::Regex>,
+    /// Replacement for `regex` match, e.g. "$1/"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replace: Option<String>,
+    /// Value used if variable has no source or `regex` does not match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+impl HieraVariable {
+    pub fn resolve(
+        &self,
+        variables: &std::collections::HashMap<String, String>,
+        hiera_values: &std::collections::HashMap<String, String>,
+    ) -> Option<String> {
+        let value = self
+            .value
+            .clone()
+            .or_else(|| self.from.as_ref().and_then(|v| variables.get(v).cloned()))
+            .or_else(|| {
+                self.hiera_key
+                    .as_ref()
+                    .and_then(|v| hiera_values.get(v).cloned())
+            });
+
+        let value = match (&self.regex, value) {
+            (Some(regex), Some(value)) => regex.captures(&value).map(|caps| match &self.replace {
+                Some(replace) => {
+                    let mut res = String::new();
+                    caps.expand(replace, &mut res);
+                    res
+                }
+                None => caps
+                    .get(1)
+                    .or_else(|| caps.get(0))
+                    .unwrap()
+                    .as_str()
+                    .to_owned(),
+            }),
+            (None, value) => value,
+            (Some(_), None) => None,
+        };
+
+        value.or_else(|| self.default.clone())
+    }
+}
+
+#[derive(Deserialize, Clone, Default, Serialize)]
+pub struct Hiera {
+    #[serde(default)]
+    pub variables: Vec<HieraVariable>,
+}
+
+impl Hiera {
+    /// Adds site-specific variables to facts and variables passed from command line. Variables
+    /// are resolved in order, so they can refer to each other.
+    pub fn substitutions(
+        &self,
+        variables: &std::collections::HashMap<String, String>,
+        hiera_values: &std::collections::HashMap<String, String>,
+    ) -> std::collections::HashMap<String, String> {
+        let mut res = variables.clone();
+        for variable in &self.variables {
+            if let Some(value) = variable.resolve(&res, hiera_values) {
+                let _ = res.insert(variable.name.clone(), value);
+            }
+        }
+        res
+    }
+
+    /// Keys of hiera data used as sources of variables
+    pub fn hiera_keys(&self) -> impl Iterator<Item = &String> {
+        self.variables.iter().filter_map(|v| v.hiera_key.as_ref())
+    }
+}
+
 #[derive(Deserialize, Clone, Default, Serialize)]
 pub struct Config {
     pub checks: Checks,
+    #[serde(default)]
+    pub overrides: Vec<Override>,
+    #[serde(default)]
+    pub hiera: Hiera,
+}
+
+/// Repository-local config. Replaces checks and hiera settings of global config if specified,
+/// overrides are applied after global ones.
+#[derive(Deserialize, Clone, Default, Serialize)]
+pub struct RepoConfig {
+    #[serde(default)]
+    pub checks: Option<Checks>,
+    #[serde(default)]
+    pub overrides: Vec<Override>,
+    #[serde(default)]
+    pub hiera: Option<Hiera>,
+}
+
+fn read_yaml<T: serde::de::DeserializeOwned>(file: &std::path::Path) -> Result<T> {
+    let config = std::fs::read_to_string(file)
+        .map_err(|err| format_err!("Failed to load config file {:?}: {}", file, err))?;
+    serde_yaml::from_str(&config)
+        .map_err(|err| format_err!("Failed to parse config file {:?}: {}", file, err))
 }
 
 impl Config {
     pub fn read(file: &std::path::Path) -> Result<Self> {
-        let config = std::fs::read_to_string(file)
-            .map_err(|err| format_err!("Failed to load config file {:?}: {}", file, err))?;
-        let config: Self = serde_yaml::from_str(&config)
-            .map_err(|err| format_err!("Failed to parse config file {:?}: {}", file, err))?;
-        Ok(config)
+        read_yaml(file)
+    }
+
+    /// Finds repository-local config walking up from repository path
+    pub fn find_repo_config(repo_path: &std::path::Path) -> Option<std::path::PathBuf> {
+        let repo_path = repo_path
+            .canonicalize()
+            .unwrap_or_else(|_| repo_path.to_path_buf());
+        repo_path
+            .ancestors()
+            .map(|dir| dir.join(REPO_CONFIG))
+            .find(|path| path.is_file())
+    }
+
+    /// Merges repository-local config into this one
+    pub fn merge_repo_config(mut self, file: &std::path::Path) -> Result<Self> {
+        let repo_config: RepoConfig = read_yaml(file)?;
+        if let Some(checks) = repo_config.checks {
+            self.checks = checks
+        }
+        if let Some(hiera) = repo_config.hiera {
+            self.hiera = hiera
+        }
+        self.overrides.extend(repo_config.overrides);
+        Ok(self)
+    }
+
+    /// Path of file relative to repository root, used for matching overrides
+    pub fn relative_path(
+        repo_path: &std::path::Path,
+        file_path: &std::path::Path,
+    ) -> std::path::PathBuf {
+        if let (Ok(repo_path), Ok(file_path)) = (repo_path.canonicalize(), file_path.canonicalize())
+        {
+            if let Ok(v) = file_path.strip_prefix(&repo_path) {
+                return v.to_path_buf();
+            }
+        }
+        file_path
+            .strip_prefix(repo_path)
+            .unwrap_or(file_path)
+            .to_path_buf()
+    }
+
+    /// Overrides which match the file
+    pub fn matching_overrides<'a>(
+        &'a self,
+        repo_path: &std::path::Path,
+        file_path: &std::path::Path,
+    ) -> Vec<&'a Override> {
+        let path = Self::relative_path(repo_path, file_path);
+        self.overrides.iter().filter(|v| v.matches(&path)).collect()
+    }
+
+    /// Configuration of *.pp lints in effect for the file
+    pub fn pp(
+        &self,
+        repo_path: &std::path::Path,
+        file_path: &std::path::Path,
+    ) -> shadowplay::puppet_pp_lint::lint::Storage {
+        let mut storage = self.checks.pp.clone();
+        for elt in self.matching_overrides(repo_path, file_path) {
+            for name in &elt.pp.disable {
+                storage.disable(name)
+            }
+            for lint in &elt.pp.early_pass {
+                storage.set_early_pass(lint.clone())
+            }
+            for lint in &elt.pp.late_pass {
+                storage.set_late_pass(lint.clone())
+            }
+            for (name, severity) in &elt.pp.severity {
+                storage.set_severity(name, *severity)
+            }
+        }
+        storage
+    }
+}
+
+#[test]
+fn test_override_paths() {
+    let elt: Override = serde_yaml::from_str("paths: ['modules/*/manifests/*.pp']").unwrap();
+    assert!(elt.matches(std::path::Path::new("modules/a/manifests/init.pp")));
+    assert!(!elt.matches(std::path::Path::new("modules/a/manifests/b/init.pp")));
+
+    let err = serde_yaml::from_str::<Override>("paths: ['modules/[a/*.pp']")
+        .err()
+        .unwrap();
+    assert!(err
+        .to_string()
+        .contains("invalid glob pattern \"modules/[a/*.pp\""));
+}
+
+#[test]
+fn test_severity() {
+    use shadowplay::puppet_pp_lint::lint::Severity;
+
+    let config: Config = serde_yaml::from_str(
+        "checks:
+  hiera_yaml: {}
+  pp:
+    early_pass: []
+    severity:
+      DoNotUseUnless: hint
+overrides:
+  - paths: ['modules/legacy/**/*.pp']
+    pp:
+      severity:
+        DoNotUseUnless: error
+        UselessParens: info
+",
+    )
+    .unwrap();
+
+    let repo = std::path::Path::new("/repo");
+    let storage = config.pp(repo, &repo.join("modules/a/manifests/init.pp"));
+    struct Lint(&'static str);
+    impl shadowplay::puppet_pp_lint::lint::LintPass for Lint {
+        fn name(&self) -> &str {
+            self.0
+        }
+        fn description(&self) -> &str {
+            ""
+        }
+    }
+    let unless = Lint("DoNotUseUnless");
+    let parens = Lint("UselessParens");
+    assert_eq!(storage.severity(&unless), Severity::Hint);
+    // Default severity of lint
+    assert_eq!(storage.severity(&parens), Severity::Error);
+
+    let storage = config.pp(repo, &repo.join("modules/legacy/manifests/init.pp"));
+    assert_eq!(storage.severity(&unless), Severity::Error);
+    assert_eq!(storage.severity(&parens), Severity::Info);
+
+    assert!(serde_yaml::from_str::<PpOverride>("severity:\n  DoNotUseUnless: fatal\n").is_err());
+}
+
+#[test]
+fn test_hiera_variables() {
+    let hiera: Hiera = serde_yaml::from_str(
+        r#"
+variables:
+  - name: "::extgrp"
+    from: "::hostname"
+    regex: "^(\\D+)"
+  - name: "::dc"
+    hiera_key: "dc"
+    default: "unknown"
+  - name: "::role"
+    value: "web"
+  - name: "::group_path"
+    from: "::extgrp"
+    regex: "^(.)(.*)$"
+    replace: "$1/$2"
+  - name: "::numeric"
+    from: "::hostname"
+    regex: "^\\d+$"
+    default: "none"
+  - name: "::missing"
+    from: "::undefined"
+"#,
+    )
+    .unwrap();
+    assert_eq!(hiera.hiera_keys().collect::<Vec<_>>(), vec!["dc"]);
+
+    let variables: std::collections::HashMap<_, _> =
+        std::iter::once(("::hostname".to_owned(), "web12".to_owned())).collect();
+    let hiera_values: std::collections::HashMap<_, _> =
+        std::iter::once(("dc".to_owned(), "msk".to_owned())).collect();
+
+    let cases = [
+        (&hiera_values, "::extgrp", Some("web")),
+        (&hiera_values, "::dc", Some("msk")),
+        (&Default::default(), "::dc", Some("unknown")),
+        (&hiera_values, "::role", Some("web")),
+        (&hiera_values, "::group_path", Some("w/eb")),
+        (&hiera_values, "::numeric", Some("none")),
+        (&hiera_values, "::missing", None),
+        (&hiera_values, "::hostname", Some("web12")),
+    ];
+    for (hiera_values, name, expected) in cases {
+        let substitutions = hiera.substitutions(&variables, hiera_values);
+        assert_eq!(
+            substitutions.get(name).map(|v| v.as_str()),
+            expected,
+            "{}",
+            name
+        );
     }
 }
@@ -0,0 +1,211 @@
+use anyhow::{format_err, Result};
+
+/// Facts of a host, as printed by `puppet facts` or `facter --json`
+#[derive(Debug, Clone, Default)]
+pub struct Facts {
+    pub values: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Facts {
+    /// Reads JSON or YAML facts file
+    pub fn read(path: &std::path::Path) -> Result<Self> {
+        let str = std::fs::read_to_string(path)
+            .map_err(|err| format_err!("Failed to read facts file {:?}: {}", path, err))?;
+        let value: serde_json::Value = match path.extension().and_then(|v| v.to_str()) {
+            Some("json") => serde_json::from_str(&str)
+                .map_err(|err| format_err!("Failed to parse facts file {:?}: {}", path, err))?,
+            _ => serde_yaml::from_str(&str)
+                .map_err(|err| format_err!("Failed to parse facts file {:?}: {}", path, err))?,
+        };
+        Self::of_value(value)
+            .ok_or_else(|| format_err!("Facts file {:?} does not contain a map of facts", path))
+    }
+
+    /// Accepts both plain map of facts and `puppet facts` output, where facts are wrapped into
+    /// {"name": ..., "values": {...}}
+    pub fn of_value(value: serde_json::Value) -> Option<Self> {
+        let mut values = match value {
+            serde_json::Value::Object(v) => v,
+            _ => return None,
+        };
+        if values.contains_key("name") {
+            if let Some(serde_json::Value::Object(v)) = values.remove("values") {
+                values = v
+            }
+        }
+        Some(Self { values })
+    }
+
+    /// Sets fact, creating intermediate maps of structured facts
+    pub fn set(&mut self, path: &[&str], value: &str) {
+        let (last, path) = match path.split_last() {
+            Some(v) => v,
+            None => return,
+        };
+        let mut map = &mut self.values;
+        for elt in path {
+            let entry = map
+                .entry(elt.to_string())
+                .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+            if !entry.is_object() {
+                *entry = serde_json::Value::Object(serde_json::Map::new())
+            }
+            map = match entry {
+                serde_json::Value::Object(v) => v,
+                _ => unreachable!(),
+            };
+        }
+        let _ = map.insert(
+            last.to_string(),
+            serde_json::Value::String(value.to_owned()),
+        );
+    }
+
+    /// Variables available for interpolation in hiera.yaml: 'facts.os.family', legacy
+    /// '::osfamily' and 'osfamily', and 'trusted.certname' with its parts
+    pub fn variables(&self, certname: &str) -> std::collections::HashMap<String, String> {
+        let mut flat = Vec::new();
+        flatten("", &self.values, &mut flat, &mut Vec::new());
+
+        let mut variables = std::collections::HashMap::new();
+        for (path, value) in flat {
+            let _ = variables.insert(format!("facts.{}", path), value.clone());
+            let _ = variables.insert(format!("::{}", path), value.clone());
+            let _ = variables.insert(path, value);
+        }
+
+        for name in ["facts.fqdn", "::fqdn", "fqdn"] {
+            if !variables.contains_key(name) {
+                let _ = variables.insert(name.to_owned(), certname.to_owned());
+            }
+        }
+
+        let (hostname, domain) = certname.split_once('.').unwrap_or((certname, ""));
+        let _ = variables.insert("trusted.certname".to_owned(), certname.to_owned());
+        let _ = variables.insert("trusted.hostname".to_owned(), hostname.to_owned());
+        let _ = variables.insert("trusted.domain".to_owned(), domain.to_owned());
+
+        variables
+    }
+
+    /// Array facts for `mapped_paths` of hiera.yaml, named like in `variables`
+    pub fn lists(&self) -> std::collections::HashMap<String, Vec<String>> {
+        let mut lists = Vec::new();
+        flatten("", &self.values, &mut Vec::new(), &mut lists);
+
+        let mut res = std::collections::HashMap::new();
+        for (path, values) in lists {
+            let _ = res.insert(format!("facts.{}", path), values.clone());
+            let _ = res.insert(format!("::{}", path), values.clone());
+            let _ = res.insert(path, values);
+        }
+        res
+    }
+}
+
+/// Collects scalar facts and arrays of scalars with dot-separated paths. Nulls can not be
+/// interpolated into paths, so they are skipped.
+fn flatten(
+    prefix: &str,
+    map: &serde_json::Map<String, serde_json::Value>,
+    res: &mut Vec<(String, String)>,
+    lists: &mut Vec<(String, Vec<String>)>,
+) {
+    for (k, v) in map {
+        let path = if prefix.is_empty() {
+            k.clone()
+        } else {
+            format!("{}.{}", prefix, k)
+        };
+        match v {
+            serde_json::Value::Object(v) => flatten(&path, v, res, lists),
+            serde_json::Value::String(v) => res.push((path, v.clone())),
+            serde_json::Value::Number(v) => res.push((path, v.to_string())),
+            serde_json::Value::Bool(v) => res.push((path, v.to_string())),
+            serde_json::Value::Array(v) => lists.push((
+                path,
+                v.iter()
+                    .filter_map(|elt| match elt {
+                        serde_json::Value::String(v) => Some(v.clone()),
+                        serde_json::Value::Number(v) => Some(v.to_string()),
+                        serde_json::Value::Bool(v) => Some(v.to_string()),
+                        _ => None,
+                    })
+                    .collect(),
+            )),
+            serde_json::Value::Null => (),
+        }
+    }
+}
+
+#[test]
+fn test_variables() {
+    let facts = Facts::of_value(serde_json::json!({
+        "name": "web1.example.com",
+        "values": {
+            "os": {"family": "Debian", "release": {"major": "11"}},
+            "processorcount": 4,
+            "is_virtual": true,
+            "ec2_metadata": null,
+            "ipaddresses": ["10.0.0.1", "10.0.0.2"],
+        }
+    }))
+    .unwrap();
+    let variables = facts.variables("web1.example.com");
+
+    let cases = [
+        ("facts.os.family", Some("Debian")),
+        ("::os.family", Some("Debian")),
+        ("os.family", Some("Debian")),
+        ("facts.os.release.major", Some("11")),
+        ("::processorcount", Some("4")),
+        ("facts.is_virtual", Some("true")),
+        ("facts.ec2_metadata", None),
+        ("facts.ipaddresses", None),
+        ("facts.fqdn", Some("web1.example.com")),
+        ("::fqdn", Some("web1.example.com")),
+        ("trusted.certname", Some("web1.example.com")),
+        ("trusted.hostname", Some("web1")),
+        ("trusted.domain", Some("example.com")),
+    ];
+    for (name, expected) in cases {
+        assert_eq!(
+            variables.get(name).map(|v| v.as_str()),
+            expected,
+            "{}",
+            name
+        );
+    }
+
+    assert_eq!(
+        facts.lists().get("facts.ipaddresses"),
+        Some(&vec!["10.0.0.1".to_owned(), "10.0.0.2".to_owned()])
+    );
+}
+
+#[test]
+fn test_set() {
+    let mut facts =
+        Facts::of_value(serde_json::json!({"fqdn": "a.example.com", "os": "linux"})).unwrap();
+    facts.set(&["os", "family"], "RedHat");
+    facts.set(&["fqdn"], "b.example.com");
+    let variables = facts.variables("c.example.com");
+
+    let cases = [
+        ("facts.os.family", Some("RedHat")),
+        ("facts.os", None),
+        ("::fqdn", Some("b.example.com")),
+        ("trusted.certname", Some("c.example.com")),
+        ("trusted.domain", Some("example.com")),
+    ];
+    for (name, expected) in cases {
+        assert_eq!(
+            variables.get(name).map(|v| v.as_str()),
+            expected,
+            "{}",
+            name
+        );
+    }
+
+    assert!(Facts::of_value(serde_json::json!(["a"])).is_none());
+}
@@ -1,27 +1,114 @@
 use anyhow::Result;
 use serde::Deserialize;
 
-#[derive(Deserialize, Debug, Clone)]
+/// Settings inherited by hierarchy levels which do not specify them
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct Defaults {
-    pub data_hash: String,
-    pub datadir: std::path::PathBuf,
+    #[serde(default)]
+    pub datadir: Option<std::path::PathBuf>,
+    #[serde(default)]
+    pub data_hash: Option<String>,
+    #[serde(default)]
+    pub lookup_key: Option<String>,
+    #[serde(default)]
+    pub data_dig: Option<String>,
+    /// Backend specific, values can be of any type
+    #[serde(default)]
+    pub options: Option<serde_yaml::Value>,
 }
 
+/// `[variable, key, template]`: template is expanded for every element of variable, which is
+/// available as key
+#[derive(Deserialize, Debug, Clone)]
+pub struct MappedPaths(pub String, pub String, pub String);
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct HierarchyElt {
     pub name: String,
     #[serde(default)]
+    pub datadir: Option<std::path::PathBuf>,
+    #[serde(default)]
+    pub data_hash: Option<String>,
+    #[serde(default)]
     pub lookup_key: Option<String>,
     #[serde(default)]
+    pub data_dig: Option<String>,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
     pub paths: Option<Vec<String>>,
-    // TODO format is not strict. There can be arrays in values
-    // #[serde(default)]
-    // pub options: std::collections::HashMap<String, String>,
+    #[serde(default)]
+    pub glob: Option<String>,
+    #[serde(default)]
+    pub globs: Option<Vec<String>>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub uris: Option<Vec<String>>,
+    #[serde(default)]
+    pub mapped_paths: Option<MappedPaths>,
+    /// Backend specific, values can be of any type
+    #[serde(default)]
+    pub options: Option<serde_yaml::Value>,
+}
+
+/// Function which provides data of hierarchy level
+#[derive(Debug, Clone, PartialEq)]
+pub enum Backend {
+    DataHash(String),
+    LookupKey(String),
+    DataDig(String),
+}
+
+impl Backend {
+    fn of_settings(
+        data_hash: &Option<String>,
+        lookup_key: &Option<String>,
+        data_dig: &Option<String>,
+    ) -> Option<Self> {
+        match (data_hash, lookup_key, data_dig) {
+            (Some(v), _, _) => Some(Backend::DataHash(v.clone())),
+            (_, Some(v), _) => Some(Backend::LookupKey(v.clone())),
+            (_, _, Some(v)) => Some(Backend::DataDig(v.clone())),
+            _ => None,
+        }
+    }
+
+    /// Data files of built-in YAML, JSON and eyaml backends can be read as YAML
+    pub fn is_yaml(&self) -> bool {
+        match self {
+            Backend::DataHash(v) => v == "yaml_data" || v == "json_data",
+            Backend::LookupKey(v) => v == "eyaml_lookup_key",
+            Backend::DataDig(_) => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Backend::DataHash(v) => write!(f, "data_hash: {}", v),
+            Backend::LookupKey(v) => write!(f, "lookup_key: {}", v),
+            Backend::DataDig(v) => write!(f, "data_dig: {}", v),
+        }
+    }
+}
+
+/// Data file of hierarchy level
+#[derive(Debug, Clone)]
+pub struct DataFile {
+    /// Name of hierarchy level
+    pub level: String,
+    /// Path relative to datadir
+    pub path: String,
+    pub full_path: std::path::PathBuf,
+    pub backend: Backend,
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct HieraConfig {
     pub version: u16,
+    #[serde(default)]
     pub defaults: Defaults,
     pub hierarchy: Vec<HierarchyElt>,
 }
@@ -30,53 +117,565 @@ lazy_static! {
     static ref INTERPOLATION_RE: regex::Regex = regex::Regex::new("%\\{([^\\}]+)\\}").unwrap();
 }
 
+fn interpolate(path: &str, substitutions: &std::collections::HashMap<String, String>) -> String {
+    let mut all_replaced = true;
+    let new_path = INTERPOLATION_RE.replace_all(path, |caps: &regex::Captures| {
+        let key: String = caps[1].to_string();
+        match substitutions.get(&key) {
+            None => {
+                log::warn!(
+                    "Failed to substitude hiera value, key {:?} not found in substitutions",
+                    key
+                );
+                all_replaced = false;
+                format!("%{{{}}}", &key)
+            }
+            Some(replacement) => {
+                log::debug!(
+                    "Substituting {:?} with {:?} in path {:?}",
+                    &key,
+                    replacement,
+                    path
+                );
+                replacement.to_string()
+            }
+        }
+    });
+    if !all_replaced {
+        log::warn!("Some substitutions failed in path {:?}", path)
+    }
+    new_path.to_string()
+}
+
+/// Glob pattern matching path with any values of interpolated variables
+fn interpolation_pattern(path: &str, is_glob: bool) -> String {
+    let mut res = String::new();
+    let mut last = 0;
+    for m in INTERPOLATION_RE.find_iter(path) {
+        let literal = &path[last..m.start()];
+        if is_glob {
+            res.push_str(literal)
+        } else {
+            res.push_str(&glob::Pattern::escape(literal))
+        }
+        res.push('*');
+        last = m.end();
+    }
+    let literal = &path[last..];
+    if is_glob {
+        res.push_str(literal)
+    } else {
+        res.push_str(&glob::Pattern::escape(literal))
+    }
+    res
+}
+
+impl HierarchyElt {
+    fn validate(&self) -> Result<()> {
+        let locations = [
+            self.path.is_some(),
+            self.paths.is_some(),
+            self.glob.is_some(),
+            self.globs.is_some(),
+            self.uri.is_some(),
+            self.uris.is_some(),
+            self.mapped_paths.is_some(),
+        ];
+        if locations.iter().filter(|v| **v).count() > 1 {
+            anyhow::bail!(
+                "Hierarchy level {:?} must have only one of path, paths, glob, globs, uri, uris, mapped_paths",
+                self.name
+            )
+        }
+        let backends = [
+            self.data_hash.is_some(),
+            self.lookup_key.is_some(),
+            self.data_dig.is_some(),
+        ];
+        if backends.iter().filter(|v| **v).count() > 1 {
+            anyhow::bail!(
+                "Hierarchy level {:?} must have only one of data_hash, lookup_key, data_dig",
+                self.name
+            )
+        }
+        Ok(())
+    }
+
+    /// Paths and glob patterns with their kind, true for globs
+    fn locations(&self) -> Vec<(&str, bool)> {
+        let mut res = Vec::new();
+        res.extend(self.path.iter().map(|v| (v.as_str(), false)));
+        res.extend(self.paths.iter().flatten().map(|v| (v.as_str(), false)));
+        res.extend(self.glob.iter().map(|v| (v.as_str(), true)));
+        res.extend(self.globs.iter().flatten().map(|v| (v.as_str(), true)));
+        res
+    }
+}
+
 impl HieraConfig {
     pub fn read(path: &std::path::Path) -> Result<Self> {
         log::debug!("Reading hiera config {:?}", path);
         let str = std::fs::read_to_string(path)
             .map_err(|err| anyhow::format_err!("Failed to read {:?}: {}", path, err))?;
-        let r: Self = serde_yaml::from_str(&str)
+        let value: serde_yaml::Value = serde_yaml::from_str(&str)
+            .map_err(|err| anyhow::format_err!("Failed to parse {:?}: {}", path, err))?;
+        match value.get("version") {
+            Some(version) if version.as_u64() == Some(5) => (),
+            Some(version) => anyhow::bail!(
+                "Hiera config {:?} has version {:?}, only version 5 is supported",
+                path,
+                version
+            ),
+            None if value.get(":backends").is_some() || value.get(":hierarchy").is_some() => {
+                anyhow::bail!(
+                    "Hiera config {:?} is in version 3 format, which is not supported. Convert it to version 5",
+                    path
+                )
+            }
+            None => anyhow::bail!("Hiera config {:?} does not specify version", path),
+        }
+        let r: Self = serde_yaml::from_value(value)
             .map_err(|err| anyhow::format_err!("Failed to parse {:?}: {}", path, err))?;
+        for elt in &r.hierarchy {
+            elt.validate()
+                .map_err(|err| anyhow::format_err!("Invalid {:?}: {}", path, err))?;
+        }
         Ok(r)
     }
 
-    pub fn substitude_paths(
+    pub fn datadir(&self, elt: &HierarchyElt) -> std::path::PathBuf {
+        elt.datadir
+            .clone()
+            .or_else(|| self.defaults.datadir.clone())
+            .unwrap_or_else(|| std::path::PathBuf::from("data"))
+    }
+
+    pub fn backend(&self, elt: &HierarchyElt) -> Backend {
+        Backend::of_settings(&elt.data_hash, &elt.lookup_key, &elt.data_dig)
+            .or_else(|| {
+                Backend::of_settings(
+                    &self.defaults.data_hash,
+                    &self.defaults.lookup_key,
+                    &self.defaults.data_dig,
+                )
+            })
+            .unwrap_or_else(|| Backend::DataHash("yaml_data".to_owned()))
+    }
+
+    fn data_file(
         &self,
+        repo_path: &std::path::Path,
+        elt: &HierarchyElt,
+        full_path: std::path::PathBuf,
+    ) -> DataFile {
+        let datadir = repo_path.join(self.datadir(elt));
+        // Glob results have leading "./" stripped
+        let path = full_path
+            .strip_prefix(&datadir)
+            .or_else(|_| full_path.strip_prefix(datadir.strip_prefix(".").unwrap_or(&datadir)))
+            .map(|v| v.to_path_buf());
+        match path {
+            Ok(path) => DataFile {
+                level: elt.name.clone(),
+                path: path.to_string_lossy().to_string(),
+                full_path: datadir.join(path),
+                backend: self.backend(elt),
+            },
+            Err(_) => DataFile {
+                level: elt.name.clone(),
+                path: full_path.to_string_lossy().to_string(),
+                full_path,
+                backend: self.backend(elt),
+            },
+        }
+    }
+
+    fn glob(
+        &self,
+        repo_path: &std::path::Path,
+        elt: &HierarchyElt,
+        pattern: &str,
+    ) -> Vec<DataFile> {
+        let datadir = repo_path.join(self.datadir(elt));
+        let pattern = format!(
+            "{}/{}",
+            glob::Pattern::escape(&datadir.to_string_lossy()),
+            pattern
+        );
+        match glob::glob(&pattern) {
+            Ok(paths) => paths
+                .filter_map(|v| v.ok())
+                .filter(|v| v.is_file())
+                .map(|v| self.data_file(repo_path, elt, v))
+                .collect(),
+            Err(err) => {
+                log::error!(
+                    "Invalid glob pattern {:?} in hierarchy level {:?}: {}",
+                    pattern,
+                    elt.name,
+                    err
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    /// Data files for the host, in order of priority. Paths are listed even if files do not
+    /// exist, globs are expanded to existing files only.
+    pub fn data_files(
+        &self,
+        repo_path: &std::path::Path,
         substitutions: &std::collections::HashMap<String, String>,
-    ) -> Self {
-        let mut hierarchy = Vec::new();
-        let default_paths = Vec::new();
+        lists: &std::collections::HashMap<String, Vec<String>>,
+    ) -> Vec<DataFile> {
+        let mut res = Vec::new();
         for elt in &self.hierarchy {
-            let mut paths = Vec::new();
-            for path in elt.paths.as_ref().unwrap_or(&default_paths) {
-                let mut all_replaced = true;
-                let new_path = INTERPOLATION_RE.replace_all(path, |caps: &regex::Captures| {
-                    let key: String = caps[1].to_string();
-                    match substitutions.get(&key) {
-                        None => {
-                            log::warn!("Failed to substitude hiera value, key {:?} not found in substitutions", key);
-                            all_replaced = false;
-                            format!("%{{{}}}", &key)
-                        },
-                        Some(replacement) => {
-                            log::debug!("Substituting {:?} with {:?} in path {:?}", &key, replacement, path);
-                            replacement.to_string()
-                        }
+            let datadir = repo_path.join(self.datadir(elt));
+            for (path, is_glob) in elt.locations() {
+                let path = interpolate(path, substitutions);
+                if is_glob {
+                    res.extend(self.glob(repo_path, elt, &path))
+                } else {
+                    res.push(self.data_file(repo_path, elt, datadir.join(path)))
+                }
+            }
+
+            if let Some(MappedPaths(variable, key, template)) = &elt.mapped_paths {
+                let values = match (lists.get(variable), substitutions.get(variable)) {
+                    (Some(values), _) => values.clone(),
+                    (None, Some(value)) => vec![value.clone()],
+                    (None, None) => {
+                        log::warn!(
+                            "Variable {:?} of mapped_paths in hierarchy level {:?} is not defined",
+                            variable,
+                            elt.name
+                        );
+                        Vec::new()
                     }
-                });
-                if !all_replaced {
-                    log::warn!("Some substitutions failed in path {:?}", path)
+                };
+                for value in values {
+                    let mut substitutions = substitutions.clone();
+                    let _ = substitutions.insert(key.clone(), value);
+                    let path = interpolate(template, &substitutions);
+                    res.push(self.data_file(repo_path, elt, datadir.join(path)))
                 }
-                paths.push(new_path.to_string())
             }
-            hierarchy.push(HierarchyElt {
-                paths: Some(paths),
-                ..elt.clone()
-            })
+
+            if elt.uri.is_some() || elt.uris.is_some() {
+                log::debug!("Skipping URIs of hierarchy level {:?}", elt.name)
+            }
         }
-        Self {
-            hierarchy,
-            ..self.clone()
+        res
+    }
+
+    /// Files of datadir matching path with interpolated variables. Values of variables may
+    /// contain '/', so all files of datadir are matched.
+    fn matching_files(
+        &self,
+        repo_path: &std::path::Path,
+        elt: &HierarchyElt,
+        path: &str,
+    ) -> Vec<DataFile> {
+        let pattern = match glob::Pattern::new(&interpolation_pattern(path, false)) {
+            Ok(v) => v,
+            Err(err) => {
+                log::error!(
+                    "Invalid path {:?} in hierarchy level {:?}: {}",
+                    path,
+                    elt.name,
+                    err
+                );
+                return Vec::new();
+            }
+        };
+        let options = glob::MatchOptions {
+            require_literal_separator: false,
+            ..glob::MatchOptions::default()
+        };
+        self.glob(repo_path, elt, "**/*")
+            .into_iter()
+            .filter(|file| pattern.matches_with(&file.path, options))
+            .collect()
+    }
+
+    /// Existing data files which hierarchy can refer to for any host. Interpolated variables
+    /// match any value.
+    pub fn all_data_files(&self, repo_path: &std::path::Path) -> Vec<DataFile> {
+        let mut res = Vec::new();
+        for elt in &self.hierarchy {
+            for (path, is_glob) in elt.locations() {
+                if is_glob {
+                    res.extend(self.glob(repo_path, elt, &interpolation_pattern(path, true)))
+                } else {
+                    res.extend(self.matching_files(repo_path, elt, path))
+                }
+            }
+            if let Some(MappedPaths(_, _, template)) = &elt.mapped_paths {
+                res.extend(self.matching_files(repo_path, elt, template))
+            }
         }
+        res
+    }
+}
+
+#[cfg(test)]
+fn test_repo(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+    let repo = std::env::temp_dir().join(format!("shadowplay-{}-{}", name, std::process::id()));
+    if repo.exists() {
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+    for (path, content) in files {
+        let path = repo.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    repo
+}
+
+#[cfg(test)]
+const TEST_CONFIG: &str = r#"
+version: 5
+defaults:
+  datadir: hieradata
+  data_hash: json_data
+hierarchy:
+  - name: node
+    path: "nodes/%{trusted.certname}.yaml"
+  - name: os
+    paths: ["os/%{facts.os.family}.yaml", "common.yaml"]
+    data_hash: yaml_data
+  - name: roles
+    mapped_paths: [roles, role, "roles/%{role}.yaml"]
+  - name: secrets
+    datadir: secrets
+    lookup_key: eyaml_lookup_key
+    glob: "%{facts.env}/*.eyaml"
+  - name: extra
+    globs: ["extra/*.yaml", "more/*.yaml"]
+"#;
+
+#[cfg(test)]
+const TEST_DATA_FILES: [&str; 9] = [
+    "hieradata/nodes/h1.yaml",
+    "hieradata/os/Debian.yaml",
+    "hieradata/os/RedHat.yaml",
+    "hieradata/common.yaml",
+    "hieradata/roles/web.yaml",
+    "hieradata/extra/a.yaml",
+    "hieradata/extra/b.yaml",
+    "hieradata/more/c.yaml",
+    "secrets/prod/x.eyaml",
+];
+
+#[cfg(test)]
+fn summary(repo: &std::path::Path, files: &[DataFile]) -> Vec<(String, String, String, String)> {
+    files
+        .iter()
+        .map(|v| {
+            (
+                v.level.clone(),
+                v.path.clone(),
+                v.full_path
+                    .strip_prefix(repo)
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string(),
+                v.backend.to_string(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_data_files() {
+    let mut files: Vec<(&str, &str)> = TEST_DATA_FILES.iter().map(|v| (*v, "")).collect();
+    files.push(("hiera.yaml", TEST_CONFIG));
+    let repo = test_repo("hiera-data-files", &files);
+    let config = HieraConfig::read(&repo.join("hiera.yaml")).unwrap();
+
+    let substitutions: std::collections::HashMap<String, String> = [
+        ("trusted.certname", "h1"),
+        ("facts.os.family", "Debian"),
+        ("facts.env", "prod"),
+    ]
+    .iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+    let mut lists = std::collections::HashMap::new();
+    let _ = lists.insert("roles".to_owned(), vec!["web".to_owned(), "db".to_owned()]);
+
+    let cases = [
+        (
+            "node",
+            "nodes/h1.yaml",
+            "hieradata/nodes/h1.yaml",
+            "data_hash: json_data",
+        ),
+        (
+            "os",
+            "os/Debian.yaml",
+            "hieradata/os/Debian.yaml",
+            "data_hash: yaml_data",
+        ),
+        (
+            "os",
+            "common.yaml",
+            "hieradata/common.yaml",
+            "data_hash: yaml_data",
+        ),
+        (
+            "roles",
+            "roles/web.yaml",
+            "hieradata/roles/web.yaml",
+            "data_hash: json_data",
+        ),
+        (
+            "roles",
+            "roles/db.yaml",
+            "hieradata/roles/db.yaml",
+            "data_hash: json_data",
+        ),
+        (
+            "secrets",
+            "prod/x.eyaml",
+            "secrets/prod/x.eyaml",
+            "lookup_key: eyaml_lookup_key",
+        ),
+        (
+            "extra",
+            "extra/a.yaml",
+            "hieradata/extra/a.yaml",
+            "data_hash: json_data",
+        ),
+        (
+            "extra",
+            "extra/b.yaml",
+            "hieradata/extra/b.yaml",
+            "data_hash: json_data",
+        ),
+        (
+            "extra",
+            "more/c.yaml",
+            "hieradata/more/c.yaml",
+            "data_hash: json_data",
+        ),
+    ];
+    let expected: Vec<_> = cases
+        .iter()
+        .map(|(a, b, c, d)| (a.to_string(), b.to_string(), c.to_string(), d.to_string()))
+        .collect();
+    assert_eq!(
+        summary(&repo, &config.data_files(&repo, &substitutions, &lists)),
+        expected
+    );
+
+    // Scalar variable of mapped_paths is mapped as single element list
+    let mut substitutions = substitutions;
+    let _ = substitutions.insert("roles".to_owned(), "web".to_owned());
+    let roles: Vec<_> = config
+        .data_files(&repo, &substitutions, &std::collections::HashMap::new())
+        .into_iter()
+        .filter(|v| v.level == "roles")
+        .map(|v| v.path)
+        .collect();
+    assert_eq!(roles, vec!["roles/web.yaml"]);
+
+    // Undefined variables are left as is and mapped_paths is skipped
+    let paths: Vec<_> = config
+        .data_files(
+            &repo,
+            &std::collections::HashMap::new(),
+            &std::collections::HashMap::new(),
+        )
+        .into_iter()
+        .filter(|v| v.level != "extra")
+        .map(|v| v.path)
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            "nodes/%{trusted.certname}.yaml",
+            "os/%{facts.os.family}.yaml",
+            "common.yaml"
+        ]
+    );
+
+    let mut all: Vec<_> = config
+        .all_data_files(&repo)
+        .into_iter()
+        .map(|v| {
+            v.full_path
+                .strip_prefix(&repo)
+                .unwrap()
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect();
+    all.sort();
+    let mut expected: Vec<_> = TEST_DATA_FILES.to_vec();
+    expected.sort_unstable();
+    assert_eq!(all, expected);
+
+    std::fs::remove_dir_all(&repo).unwrap();
+}
+
+#[test]
+fn test_defaults() {
+    let repo = test_repo(
+        "hiera-defaults",
+        &[(
+            "hiera.yaml",
+            "version: 5\nhierarchy:\n  - name: common\n    path: common.yaml\n  - name: own\n    datadir: own\n    lookup_key: eyaml_lookup_key\n    path: own.eyaml\n",
+        )],
+    );
+    let config = HieraConfig::read(&repo.join("hiera.yaml")).unwrap();
+    let datadirs: Vec<_> = config.hierarchy.iter().map(|v| config.datadir(v)).collect();
+    assert_eq!(
+        datadirs,
+        vec![
+            std::path::PathBuf::from("data"),
+            std::path::PathBuf::from("own")
+        ]
+    );
+    let backends: Vec<_> = config.hierarchy.iter().map(|v| config.backend(v)).collect();
+    assert_eq!(
+        backends,
+        vec![
+            Backend::DataHash("yaml_data".to_owned()),
+            Backend::LookupKey("eyaml_lookup_key".to_owned())
+        ]
+    );
+    std::fs::remove_dir_all(&repo).unwrap();
+}
+
+#[test]
+fn test_read_errors() {
+    let cases = [
+        (
+            "version: 3\nhierarchy: []\n",
+            "only version 5 is supported",
+        ),
+        (
+            ":backends:\n  - yaml\n:hierarchy:\n  - common\n",
+            "is in version 3 format",
+        ),
+        ("hierarchy: []\n", "does not specify version"),
+        (
+            "version: 5\nhierarchy:\n  - name: common\n    path: a.yaml\n    glob: '*.yaml'\n",
+            "must have only one of path, paths",
+        ),
+        (
+            "version: 5\nhierarchy:\n  - name: common\n    path: a.yaml\n    data_hash: yaml_data\n    lookup_key: eyaml_lookup_key\n",
+            "must have only one of data_hash",
+        ),
+    ];
+    let repo = test_repo("hiera-errors", &[("hiera.yaml", "")]);
+    for (config, expected) in cases {
+        std::fs::write(repo.join("hiera.yaml"), config).unwrap();
+        let err = HieraConfig::read(&repo.join("hiera.yaml"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(expected), "{:?} for {:?}", err, config)
     }
+    std::fs::remove_dir_all(&repo).unwrap();
 }
@@ -0,0 +1,336 @@
+use located_yaml::{Yaml, YamlElt};
+
+/// Interpolation `%{...}` in string of hiera data
+#[derive(Debug, Clone, PartialEq)]
+pub enum Interpolation {
+    /// `%{lookup('key')}` or `%{hiera('key')}`
+    Lookup(String),
+    /// `%{alias('key')}`, replaces whole string with value of any type
+    Alias(String),
+    /// `%{literal('text')}`
+    Literal(String),
+    /// `%{scope('variable')}`
+    Scope(String),
+    /// `%{facts.os.family}`, `%{::fqdn}`
+    Variable(String),
+    /// `%{}`
+    Empty,
+}
+
+lazy_static! {
+    static ref INTERPOLATION_RE: regex::Regex = regex::Regex::new("%\\{([^\\}]*)\\}").unwrap();
+    static ref FUNCTION_RE: regex::Regex =
+        regex::Regex::new("^(\\w+)\\(\\s*(?:'([^']*)'|\"([^\"]*)\")\\s*\\)$").unwrap();
+}
+
+impl Interpolation {
+    fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Ok(Interpolation::Empty);
+        }
+        let caps = match FUNCTION_RE.captures(s) {
+            Some(v) => v,
+            None if s.contains('(') => {
+                return Err(format!("Invalid interpolation function call {:?}", s))
+            }
+            None => return Ok(Interpolation::Variable(s.to_owned())),
+        };
+        let arg = caps
+            .get(2)
+            .or_else(|| caps.get(3))
+            .map(|v| v.as_str().to_owned())
+            .unwrap_or_default();
+        match &caps[1] {
+            "lookup" | "hiera" => Ok(Interpolation::Lookup(arg)),
+            "alias" => Ok(Interpolation::Alias(arg)),
+            "literal" => Ok(Interpolation::Literal(arg)),
+            "scope" => Ok(Interpolation::Scope(arg)),
+            name => Err(format!("Unknown interpolation function {:?}", name)),
+        }
+    }
+
+    /// Key of hiera data referenced by interpolation
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            Interpolation::Lookup(v) | Interpolation::Alias(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// Interpolations of string with their byte ranges
+pub fn parse(s: &str) -> Vec<(std::ops::Range<usize>, Result<Interpolation, String>)> {
+    INTERPOLATION_RE
+        .captures_iter(s)
+        .map(|caps| {
+            let m = caps.get(0).unwrap();
+            (m.range(), Interpolation::parse(&caps[1]))
+        })
+        .collect()
+}
+
+/// Checks usage of interpolations in string, returns parsed interpolations
+pub fn check(s: &str) -> Result<Vec<Interpolation>, String> {
+    let parsed = parse(s);
+    let is_whole = parsed.len() == 1 && parsed[0].0 == (0..s.len());
+    let mut res = Vec::new();
+    for (_, interpolation) in parsed {
+        let interpolation = interpolation?;
+        if matches!(interpolation, Interpolation::Alias(_)) && !is_whole {
+            return Err(format!(
+                "alias() must be the only content of string, found in {:?}",
+                s
+            ));
+        }
+        res.push(interpolation)
+    }
+    Ok(res)
+}
+
+/// Calls `f` for every string of value, including keys of hashes
+pub fn for_each_string<F: FnMut(&Yaml, &str)>(yaml: &Yaml, f: &mut F) {
+    match &yaml.yaml {
+        YamlElt::String(v) => f(yaml, v),
+        YamlElt::Array(v) => {
+            for elt in v {
+                for_each_string(elt, f)
+            }
+        }
+        YamlElt::Hash(v) => {
+            for (k, v) in v {
+                for_each_string(k, f);
+                for_each_string(v, f)
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Moves value with all its elements to location of interpolation which produced it
+fn relocate(yaml: Yaml, marker: located_yaml::Marker) -> Yaml {
+    let value = match yaml.yaml {
+        YamlElt::Array(v) => YamlElt::Array(v.into_iter().map(|v| relocate(v, marker)).collect()),
+        YamlElt::Hash(v) => YamlElt::Hash(
+            v.into_iter()
+                .map(|(k, v)| (relocate(k, marker), relocate(v, marker)))
+                .collect(),
+        ),
+        v => v,
+    };
+    Yaml {
+        yaml: value,
+        marker,
+    }
+}
+
+fn to_string(yaml: &Yaml) -> Result<String, String> {
+    match &yaml.yaml {
+        YamlElt::String(v) | YamlElt::Real(v) => Ok(v.clone()),
+        YamlElt::Integer(v) => Ok(v.to_string()),
+        YamlElt::Boolean(v) => Ok(v.to_string()),
+        YamlElt::Null => Ok(String::new()),
+        v => Err(format!(
+            "{} can not be interpolated into string",
+            v.type_name()
+        )),
+    }
+}
+
+/// Replaces interpolations in values of hiera data
+pub struct Resolver<'a> {
+    /// Value of hiera key, without interpolations resolved
+    pub lookup: &'a dyn Fn(&str) -> Option<Yaml>,
+    pub variables: &'a std::collections::HashMap<String, String>,
+}
+
+impl<'a> Resolver<'a> {
+    /// Resolves interpolations in value of key
+    pub fn resolve(&self, key: &str, yaml: &Yaml) -> Result<Yaml, String> {
+        self.resolve_value(yaml, &mut vec![key.to_owned()])
+    }
+
+    fn lookup(&self, key: &str, stack: &mut Vec<String>) -> Result<Yaml, String> {
+        if stack.iter().any(|v| v == key) {
+            stack.push(key.to_owned());
+            return Err(format!("Interpolation cycle: {}", stack.join(" -> ")));
+        }
+        let value = match (self.lookup)(key) {
+            Some(v) => v,
+            None => return Err(format!("Interpolated key {:?} is not found", key)),
+        };
+        stack.push(key.to_owned());
+        let res = self.resolve_value(&value, stack);
+        let _ = stack.pop();
+        res
+    }
+
+    fn resolve_value(&self, yaml: &Yaml, stack: &mut Vec<String>) -> Result<Yaml, String> {
+        let value = match &yaml.yaml {
+            YamlElt::String(v) => return self.resolve_string(yaml, v, stack),
+            YamlElt::Array(v) => YamlElt::Array(
+                v.iter()
+                    .map(|v| self.resolve_value(v, stack))
+                    .collect::<Result<_, _>>()?,
+            ),
+            YamlElt::Hash(v) => YamlElt::Hash(
+                v.iter()
+                    .map(|(k, v)| {
+                        Ok((self.resolve_value(k, stack)?, self.resolve_value(v, stack)?))
+                    })
+                    .collect::<Result<_, String>>()?,
+            ),
+            _ => return Ok(yaml.clone()),
+        };
+        Ok(Yaml {
+            yaml: value,
+            marker: yaml.marker,
+        })
+    }
+
+    fn resolve_string(
+        &self,
+        yaml: &Yaml,
+        s: &str,
+        stack: &mut Vec<String>,
+    ) -> Result<Yaml, String> {
+        let interpolations = check(s)?;
+        if let [Interpolation::Alias(key)] = interpolations.as_slice() {
+            return Ok(relocate(self.lookup(key, stack)?, yaml.marker));
+        }
+
+        let mut res = String::new();
+        let mut last = 0;
+        for (range, interpolation) in parse(s) {
+            res.push_str(&s[last..range.start]);
+            last = range.end;
+            match interpolation? {
+                Interpolation::Lookup(key) => res.push_str(&to_string(&self.lookup(&key, stack)?)?),
+                Interpolation::Alias(_) => unreachable!(),
+                Interpolation::Literal(v) => res.push_str(&v),
+                Interpolation::Scope(name) | Interpolation::Variable(name) => {
+                    match self.variables.get(&name) {
+                        Some(v) => res.push_str(v),
+                        None => log::warn!("Interpolated variable {:?} is not defined", name),
+                    }
+                }
+                Interpolation::Empty => (),
+            }
+        }
+        res.push_str(&s[last..]);
+        Ok(Yaml {
+            yaml: YamlElt::String(res),
+            marker: yaml.marker,
+        })
+    }
+}
+
+#[test]
+fn test_check() {
+    let cases = [
+        ("plain", Ok(vec![])),
+        ("%{}", Ok(vec![Interpolation::Empty])),
+        (
+            "%{ ::fqdn }-%{facts.os.family}",
+            Ok(vec![
+                Interpolation::Variable("::fqdn".to_owned()),
+                Interpolation::Variable("facts.os.family".to_owned()),
+            ]),
+        ),
+        (
+            "%{lookup('a')}%{hiera(\"b\")}%{literal('%')}%{scope('c')}",
+            Ok(vec![
+                Interpolation::Lookup("a".to_owned()),
+                Interpolation::Lookup("b".to_owned()),
+                Interpolation::Literal("%".to_owned()),
+                Interpolation::Scope("c".to_owned()),
+            ]),
+        ),
+        (
+            "%{alias('a')}",
+            Ok(vec![Interpolation::Alias("a".to_owned())]),
+        ),
+        (
+            "x %{alias('a')}",
+            Err("alias() must be the only content of string, found in \"x %{alias('a')}\""),
+        ),
+        (
+            "%{alias('a')}%{alias('b')}",
+            Err("alias() must be the only content of string, found in \"%{alias('a')}%{alias('b')}\""),
+        ),
+        ("%{dig('a')}", Err("Unknown interpolation function \"dig\"")),
+        (
+            "%{lookup(a)}",
+            Err("Invalid interpolation function call \"lookup(a)\""),
+        ),
+    ];
+    for (s, expected) in cases {
+        assert_eq!(check(s), expected.map_err(|v| v.to_owned()), "{:?}", s)
+    }
+}
+
+#[test]
+fn test_resolve() {
+    let data = located_yaml::YamlLoader::load_from_str(
+        r#"
+string: "%{lookup('family')}/%{hiera('number')}%{literal('%')}"
+family: "%{facts.os.family}"
+number: 5
+list: [1, "%{scope('role')}"]
+alias: "%{alias('list')}"
+nested: {"%{::role}": "%{lookup('family')}", undefined: "[%{::undefined}]"}
+hash: "%{lookup('nested')}"
+cycle_a: "%{lookup('cycle_b')}"
+cycle_b: "%{alias('cycle_a')}"
+not_found: "%{lookup('missing')}"
+"#,
+    )
+    .unwrap()
+    .docs[0]
+        .clone();
+    let lookup = |key: &str| data.get_string_key(key);
+    let variables: std::collections::HashMap<String, String> = [
+        ("facts.os.family", "Debian"),
+        ("::role", "web"),
+        ("role", "db"),
+    ]
+    .iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+    let resolver = Resolver {
+        lookup: &lookup,
+        variables: &variables,
+    };
+    let yaml = |s: &str| located_yaml::YamlLoader::load_from_str(s).unwrap().docs[0].clone();
+
+    let cases = [
+        ("string", Ok(yaml("Debian/5%"))),
+        ("list", Ok(yaml("[1, db]"))),
+        ("alias", Ok(yaml("[1, db]"))),
+        ("nested", Ok(yaml("{web: Debian, undefined: '[]'}"))),
+        (
+            "hash",
+            Err("map can not be interpolated into string".to_owned()),
+        ),
+        (
+            "cycle_a",
+            Err("Interpolation cycle: cycle_a -> cycle_b -> cycle_a".to_owned()),
+        ),
+        (
+            "not_found",
+            Err("Interpolated key \"missing\" is not found".to_owned()),
+        ),
+    ];
+    for (key, expected) in cases {
+        let value = data.get_string_key(key).unwrap();
+        assert_eq!(resolver.resolve(key, &value), expected, "{}", key)
+    }
+
+    // Alias moves value to location of interpolation
+    let value = data.get_string_key("alias").unwrap();
+    let resolved = resolver.resolve("alias", &value).unwrap();
+    match resolved.yaml {
+        YamlElt::Array(v) => assert!(v.iter().all(|v| v.marker.line == value.marker.line)),
+        v => panic!("{:?}", v),
+    }
+}
@@ -0,0 +1,690 @@
+use located_yaml::{Yaml, YamlElt};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Strategy {
+    /// Value of the highest priority level
+    First,
+    /// Flattened arrays and scalars of all levels without duplicates
+    Unique,
+    /// Top-level keys of hashes of all levels, higher priority wins
+    Hash,
+    /// Recursively merged hashes and arrays
+    Deep,
+}
+
+impl std::str::FromStr for Strategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "first" => Ok(Self::First),
+            "unique" => Ok(Self::Unique),
+            "hash" => Ok(Self::Hash),
+            "deep" => Ok(Self::Deep),
+            _ => anyhow::bail!("Invalid merge strategy: {}", s),
+        }
+    }
+}
+
+impl std::fmt::Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Strategy::First => write!(f, "first"),
+            Strategy::Unique => write!(f, "unique"),
+            Strategy::Hash => write!(f, "hash"),
+            Strategy::Deep => write!(f, "deep"),
+        }
+    }
+}
+
+/// Merge behaviour of key as specified in `lookup_options`
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub strategy: Option<Strategy>,
+    /// Keys and array elements with this prefix remove corresponding values of lower levels.
+    /// Deep merge only.
+    pub knockout_prefix: Option<String>,
+    pub sort_merged_arrays: bool,
+    /// Arrays of hashes are merged element by element
+    pub merge_hash_arrays: bool,
+}
+
+impl Options {
+    /// Parses `{merge: deep}` or `{merge: {strategy: deep, knockout_prefix: "--"}}`
+    pub fn of_yaml(yaml: &Yaml) -> Result<Self, String> {
+        let merge = match yaml.get_string_key("merge") {
+            None => return Ok(Self::default()),
+            Some(v) => v,
+        };
+        if let Some(strategy) = merge.get_string() {
+            return Ok(Self {
+                strategy: Some(parse_strategy(&strategy)?),
+                ..Self::default()
+            });
+        }
+
+        let strategy = match merge
+            .get_string_key("strategy")
+            .and_then(|v| v.get_string())
+        {
+            Some(v) => parse_strategy(&v)?,
+            None => return Err("merge.strategy is not specified".to_owned()),
+        };
+        let flag = |name: &str| match merge.get_string_key(name).map(|v| v.yaml) {
+            None => Ok(false),
+            Some(YamlElt::Boolean(v)) => Ok(v),
+            Some(v) => Err(format!(
+                "merge.{} must be boolean, got {}",
+                name,
+                v.type_name()
+            )),
+        };
+        Ok(Self {
+            strategy: Some(strategy),
+            knockout_prefix: merge
+                .get_string_key("knockout_prefix")
+                .and_then(|v| v.get_string()),
+            sort_merged_arrays: flag("sort_merged_arrays")?,
+            merge_hash_arrays: flag("merge_hash_arrays")?,
+        })
+    }
+}
+
+fn parse_strategy(s: &str) -> Result<Strategy, String> {
+    s.parse().map_err(|err: anyhow::Error| err.to_string())
+}
+
+/// Options of key from `lookup_options` of data files, highest priority first. Returns index of
+/// data file. Exact key names take precedence over regular expressions, which start with '^'.
+pub fn lookup_options(docs: &[&Yaml], key: &str) -> Option<(usize, Yaml)> {
+    let maps: Vec<(usize, linked_hash_map::LinkedHashMap<Yaml, Yaml>)> = docs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, doc)| match doc.get_string_key("lookup_options") {
+            Some(Yaml {
+                yaml: YamlElt::Hash(v),
+                ..
+            }) => Some((i, v)),
+            _ => None,
+        })
+        .collect();
+
+    for (i, map) in &maps {
+        for (k, v) in map.iter() {
+            if k.yaml == YamlElt::String(key.to_owned()) {
+                return Some((*i, v.clone()));
+            }
+        }
+    }
+
+    for (i, map) in &maps {
+        for (k, v) in map.iter() {
+            let pattern = match &k.yaml {
+                YamlElt::String(v) if v.starts_with('^') => v,
+                _ => continue,
+            };
+            match regex::Regex::new(pattern) {
+                Ok(regex) if regex.is_match(key) => return Some((*i, v.clone())),
+                Ok(_) => (),
+                Err(err) => log::warn!("Invalid lookup_options pattern {:?}: {}", pattern, err),
+            }
+        }
+    }
+
+    None
+}
+
+/// Merged value with data file of every fragment
+#[derive(Debug, Clone)]
+pub enum Merged {
+    /// Value as is in data file with index `source`
+    Value {
+        source: usize,
+        value: Yaml,
+    },
+    Array(located_yaml::Marker, Vec<Merged>),
+    Hash(located_yaml::Marker, Vec<(Yaml, Merged)>),
+}
+
+impl Merged {
+    /// Splits single-source array or hash into elements
+    fn explode(self) -> Self {
+        match self {
+            Merged::Value { source, value } => match value.yaml {
+                YamlElt::Hash(v) => Merged::Hash(
+                    value.marker,
+                    v.into_iter()
+                        .map(|(k, value)| (k, Merged::Value { source, value }))
+                        .collect(),
+                ),
+                YamlElt::Array(v) => Merged::Array(
+                    value.marker,
+                    v.into_iter()
+                        .map(|value| Merged::Value { source, value })
+                        .collect(),
+                ),
+                yaml => Merged::Value {
+                    source,
+                    value: Yaml {
+                        yaml,
+                        marker: value.marker,
+                    },
+                },
+            },
+            v => v,
+        }
+    }
+
+    /// Same as `explode`, but keeps empty arrays and hashes with their source
+    fn expand(self) -> Self {
+        match &self {
+            Merged::Value { value, .. } => match &value.yaml {
+                YamlElt::Hash(v) if v.is_empty() => self,
+                YamlElt::Array(v) if v.is_empty() => self,
+                _ => self.explode(),
+            },
+            _ => self,
+        }
+    }
+
+    fn is_hash(&self) -> bool {
+        match self {
+            Merged::Value { value, .. } => matches!(value.yaml, YamlElt::Hash(_)),
+            Merged::Hash(..) => true,
+            Merged::Array(..) => false,
+        }
+    }
+
+    /// Indexes of data files with values present in merged value
+    pub fn sources(&self) -> std::collections::BTreeSet<usize> {
+        let mut res = std::collections::BTreeSet::new();
+        self.collect_sources(&mut res);
+        res
+    }
+
+    fn collect_sources(&self, res: &mut std::collections::BTreeSet<usize>) {
+        match self {
+            Merged::Value { source, .. } => {
+                let _ = res.insert(*source);
+            }
+            Merged::Array(_, v) => v.iter().for_each(|v| v.collect_sources(res)),
+            Merged::Hash(_, v) => v.iter().for_each(|(_, v)| v.collect_sources(res)),
+        }
+    }
+
+    pub fn to_yaml(&self) -> Yaml {
+        match self {
+            Merged::Value { value, .. } => value.clone(),
+            Merged::Array(marker, v) => Yaml {
+                yaml: YamlElt::Array(v.iter().map(|v| v.to_yaml()).collect()),
+                marker: *marker,
+            },
+            Merged::Hash(marker, v) => Yaml {
+                yaml: YamlElt::Hash(v.iter().map(|(k, v)| (k.clone(), v.to_yaml())).collect()),
+                marker: *marker,
+            },
+        }
+    }
+
+    /// Renders value as YAML with data file and line of every scalar in comments
+    pub fn annotated(&self, sources: &[std::path::PathBuf]) -> String {
+        let mut res = String::new();
+        match self.clone().expand() {
+            Merged::Value { source, value } => {
+                res.push_str(&format!(
+                    "{}  # {}\n",
+                    scalar(&value),
+                    location(sources, source, &value)
+                ));
+            }
+            v => render(&v, 0, sources, &mut res),
+        }
+        res
+    }
+}
+
+fn scalar(yaml: &Yaml) -> String {
+    match &yaml.yaml {
+        YamlElt::String(v) => format!("{:?}", v),
+        YamlElt::Real(v) => v.clone(),
+        YamlElt::Integer(v) => v.to_string(),
+        YamlElt::Boolean(v) => v.to_string(),
+        YamlElt::Null => "null".to_owned(),
+        YamlElt::Array(v) if v.is_empty() => "[]".to_owned(),
+        YamlElt::Hash(v) if v.is_empty() => "{}".to_owned(),
+        YamlElt::Array(_) => "<ARRAY VALUE>".to_owned(),
+        YamlElt::Hash(_) => "<HASH VALUE>".to_owned(),
+        YamlElt::Alias(_) => "<YAML ALIAS VALUE>".to_owned(),
+        YamlElt::BadValue => "<BAD VALUE>".to_owned(),
+    }
+}
+
+fn location(sources: &[std::path::PathBuf], source: usize, yaml: &Yaml) -> String {
+    format!("{}:{}", sources[source].display(), yaml.marker.line)
+}
+
+/// Renders exploded compound value starting from new line
+fn render(merged: &Merged, indent: usize, sources: &[std::path::PathBuf], res: &mut String) {
+    let pad = " ".repeat(indent);
+    let (children, marker): (Vec<(Option<&Yaml>, Merged)>, _) = match merged {
+        Merged::Hash(marker, v) => (
+            v.iter()
+                .map(|(k, v)| (Some(k), v.clone().expand()))
+                .collect(),
+            marker,
+        ),
+        Merged::Array(marker, v) => (
+            v.iter().map(|v| (None, v.clone().expand())).collect(),
+            marker,
+        ),
+        Merged::Value { .. } => unreachable!(),
+    };
+    if children.is_empty() {
+        let empty = if matches!(merged, Merged::Hash(..)) {
+            "{}"
+        } else {
+            "[]"
+        };
+        res.push_str(&format!("{}{}  # line {}\n", pad, empty, marker.line));
+        return;
+    }
+
+    for (key, child) in children {
+        let prefix = match key {
+            Some(Yaml {
+                yaml: YamlElt::String(k),
+                ..
+            }) => format!("{}{}:", pad, k),
+            Some(k) => format!("{}{}:", pad, scalar(k)),
+            None => format!("{}-", pad),
+        };
+        match &child {
+            Merged::Value { source, value } => res.push_str(&format!(
+                "{} {}  # {}\n",
+                prefix,
+                scalar(value),
+                location(sources, *source, value)
+            )),
+            Merged::Hash(_, v) if v.is_empty() => res.push_str(&format!("{} {{}}\n", prefix)),
+            Merged::Array(_, v) if v.is_empty() => res.push_str(&format!("{} []\n", prefix)),
+            Merged::Hash(..) | Merged::Array(..) => {
+                res.push_str(&format!("{}\n", prefix));
+                render(&child, indent + 2, sources, res)
+            }
+        }
+    }
+}
+
+/// Merges values found in data files, highest priority first. On error returns index of data
+/// file with incompatible value.
+pub fn merge(
+    strategy: Strategy,
+    options: &Options,
+    values: &[(usize, &Yaml)],
+) -> Result<Merged, (usize, String)> {
+    let mut merged = values.iter().map(|(source, value)| Merged::Value {
+        source: *source,
+        value: (*value).clone(),
+    });
+    let first = match merged.next() {
+        Some(v) => v,
+        None => return Err((0, "No values to merge".to_owned())),
+    };
+
+    match strategy {
+        Strategy::First => Ok(first),
+        Strategy::Unique => {
+            let marker = values[0].1.marker;
+            let mut res = Vec::new();
+            for (source, value) in values {
+                flatten_unique(*source, value, &mut res)?;
+            }
+            Ok(Merged::Array(marker, res))
+        }
+        Strategy::Hash => {
+            let mut res: Vec<(Yaml, Merged)> = Vec::new();
+            let marker = values[0].1.marker;
+            for (source, value) in values {
+                let hash = match &value.yaml {
+                    YamlElt::Hash(v) => v,
+                    v => {
+                        return Err((
+                            *source,
+                            format!("Value is {}, hash merge requires map", v.type_name()),
+                        ))
+                    }
+                };
+                for (k, v) in hash {
+                    if !res.iter().any(|(existing, _)| existing == k) {
+                        res.push((
+                            k.clone(),
+                            Merged::Value {
+                                source: *source,
+                                value: v.clone(),
+                            },
+                        ))
+                    }
+                }
+            }
+            Ok(Merged::Hash(marker, res))
+        }
+        Strategy::Deep => {
+            let mut res = first;
+            for lower in merged {
+                res = deep(res, lower, options)
+            }
+            if let Some(prefix) = &options.knockout_prefix {
+                res = strip_knockouts(res, prefix)
+            }
+            if options.sort_merged_arrays {
+                res = sort_arrays(res)
+            }
+            Ok(res)
+        }
+    }
+}
+
+fn flatten_unique(
+    source: usize,
+    value: &Yaml,
+    res: &mut Vec<Merged>,
+) -> Result<(), (usize, String)> {
+    match &value.yaml {
+        YamlElt::Hash(_) => Err((source, "Unique merge can not be applied to map".to_owned())),
+        YamlElt::Array(v) => {
+            for elt in v {
+                flatten_unique(source, elt, res)?
+            }
+            Ok(())
+        }
+        _ => {
+            if !res.iter().any(|v| v.to_yaml() == *value) {
+                res.push(Merged::Value {
+                    source,
+                    value: value.clone(),
+                })
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Key or array element which removes value of lower levels
+fn knocked_out(yaml: &Yaml, options: &Options) -> Option<Yaml> {
+    let prefix = options.knockout_prefix.as_ref()?;
+    match &yaml.yaml {
+        YamlElt::String(v) if v.len() > prefix.len() && v.starts_with(prefix.as_str()) => {
+            Some(Yaml {
+                yaml: YamlElt::String(v[prefix.len()..].to_owned()),
+                marker: yaml.marker,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn deep(higher: Merged, lower: Merged, options: &Options) -> Merged {
+    match (higher.explode(), lower.explode()) {
+        (Merged::Hash(marker, higher), Merged::Hash(_, lower)) => {
+            let knocked: Vec<Yaml> = higher
+                .iter()
+                .filter_map(|(k, _)| knocked_out(k, options))
+                .collect();
+            let mut lower: Vec<Option<(Yaml, Merged)>> = lower.into_iter().map(Some).collect();
+            let mut res = Vec::new();
+            for (k, v) in higher {
+                let same_key = lower
+                    .iter_mut()
+                    .find(|elt| matches!(elt, Some((lower_key, _)) if *lower_key == k))
+                    .and_then(|elt| elt.take());
+                match same_key {
+                    Some((_, lower_value)) => res.push((k, deep(v, lower_value, options))),
+                    None => res.push((k, v)),
+                }
+            }
+            res.extend(
+                lower
+                    .into_iter()
+                    .flatten()
+                    .filter(|(k, _)| !knocked.contains(k)),
+            );
+            Merged::Hash(marker, res)
+        }
+        (Merged::Array(marker, higher), Merged::Array(_, lower)) => {
+            if options.merge_hash_arrays
+                && higher.iter().all(|v| v.is_hash())
+                && lower.iter().all(|v| v.is_hash())
+            {
+                let mut lower = lower.into_iter();
+                let mut res: Vec<_> = higher
+                    .into_iter()
+                    .map(|v| match lower.next() {
+                        Some(lower_value) => deep(v, lower_value, options),
+                        None => v,
+                    })
+                    .collect();
+                res.extend(lower);
+                return Merged::Array(marker, res);
+            }
+
+            let knocked: Vec<Yaml> = higher
+                .iter()
+                .filter_map(|v| match v {
+                    Merged::Value { value, .. } => knocked_out(value, options),
+                    _ => None,
+                })
+                .collect();
+            let mut res = higher;
+            for v in lower {
+                let yaml = v.to_yaml();
+                if !knocked.contains(&yaml) && !res.iter().any(|elt| elt.to_yaml() == yaml) {
+                    res.push(v)
+                }
+            }
+            Merged::Array(marker, res)
+        }
+        (higher, _) => higher,
+    }
+}
+
+fn strip_knockouts(merged: Merged, prefix: &str) -> Merged {
+    let is_knockout =
+        |yaml: &Yaml| matches!(&yaml.yaml, YamlElt::String(v) if v.starts_with(prefix));
+    match merged.expand() {
+        Merged::Hash(marker, v) => Merged::Hash(
+            marker,
+            v.into_iter()
+                .filter(|(k, _)| !is_knockout(k))
+                .map(|(k, v)| (k, strip_knockouts(v, prefix)))
+                .collect(),
+        ),
+        Merged::Array(marker, v) => Merged::Array(
+            marker,
+            v.into_iter()
+                .filter(|v| !matches!(v, Merged::Value { value, .. } if is_knockout(value)))
+                .map(|v| strip_knockouts(v, prefix))
+                .collect(),
+        ),
+        v => v,
+    }
+}
+
+fn sort_arrays(merged: Merged) -> Merged {
+    match merged.expand() {
+        Merged::Hash(marker, v) => Merged::Hash(
+            marker,
+            v.into_iter().map(|(k, v)| (k, sort_arrays(v))).collect(),
+        ),
+        Merged::Array(marker, v) => {
+            let mut v: Vec<_> = v.into_iter().map(sort_arrays).collect();
+            v.sort_by_cached_key(|v| scalar(&v.to_yaml()));
+            Merged::Array(marker, v)
+        }
+        v => v,
+    }
+}
+
+#[cfg(test)]
+fn yaml(s: &str) -> Yaml {
+    located_yaml::YamlLoader::load_from_str(s).unwrap().docs[0].clone()
+}
+
+#[cfg(test)]
+fn merge_docs(
+    strategy: Strategy,
+    options: &Options,
+    docs: &[&str],
+) -> Result<Yaml, (usize, String)> {
+    let docs: Vec<Yaml> = docs.iter().map(|v| yaml(v)).collect();
+    let values: Vec<(usize, &Yaml)> = docs.iter().enumerate().collect();
+    merge(strategy, options, &values).map(|v| v.to_yaml())
+}
+
+#[test]
+fn test_strategies() {
+    let cases = [
+        (Strategy::First, vec!["[a]", "[b]"], "[a]"),
+        (Strategy::First, vec!["{a: 1}", "{b: 2}"], "{a: 1}"),
+        (
+            Strategy::Unique,
+            vec!["[a, b]", "[b, c]", "d"],
+            "[a, b, c, d]",
+        ),
+        (Strategy::Unique, vec!["a", "[[a, b]]"], "[a, b]"),
+        (
+            Strategy::Hash,
+            vec!["{a: 1, n: {x: 1}}", "{a: 2, b: 2, n: {y: 2}}"],
+            "{a: 1, n: {x: 1}, b: 2}",
+        ),
+        (
+            Strategy::Deep,
+            vec![
+                "{a: 1, n: {x: 1, l: [a]}}",
+                "{a: 2, b: 2, n: {y: 2, l: [b, a]}}",
+            ],
+            "{a: 1, n: {x: 1, l: [a, b], y: 2}, b: 2}",
+        ),
+        (Strategy::Deep, vec!["{a: 1}", "[b]"], "{a: 1}"),
+    ];
+    for (strategy, docs, expected) in cases {
+        let merged = merge_docs(strategy, &Options::default(), &docs).unwrap();
+        assert_eq!(merged, yaml(expected), "{} merge of {:?}", strategy, docs)
+    }
+}
+
+#[test]
+fn test_merge_errors() {
+    let options = Options::default();
+    assert_eq!(
+        merge_docs(Strategy::Hash, &options, &["{a: 1}", "[b]"]).unwrap_err(),
+        (1, "Value is array, hash merge requires map".to_owned())
+    );
+    assert_eq!(
+        merge_docs(Strategy::Unique, &options, &["[a]", "{b: 1}"]).unwrap_err(),
+        (1, "Unique merge can not be applied to map".to_owned())
+    );
+    assert!(merge(Strategy::First, &options, &[]).is_err());
+}
+
+#[test]
+fn test_sources() {
+    let docs = [yaml("{a: 1}"), yaml("{b: 2}"), yaml("{a: 3}")];
+    let values: Vec<(usize, &Yaml)> = docs.iter().enumerate().collect();
+    let merged = merge(Strategy::Deep, &Options::default(), &values).unwrap();
+    assert_eq!(merged.sources().into_iter().collect::<Vec<_>>(), vec![0, 1]);
+}
+
+#[test]
+fn test_knockout() {
+    let options = Options {
+        knockout_prefix: Some("--".to_owned()),
+        ..Options::default()
+    };
+    let cases = [
+        (vec!["{--a: null, b: 1}", "{a: 1, c: 3}"], "{b: 1, c: 3}"),
+        (vec!["{n: {--x: null}}", "{n: {x: 1, y: 2}}"], "{n: {y: 2}}"),
+        (vec!["[--a, b]", "[a, c]"], "[b, c]"),
+        (vec!["{l: [--a]}", "{l: [a, b]}", "{l: [a]}"], "{l: [b]}"),
+    ];
+    for (docs, expected) in cases {
+        let merged = merge_docs(Strategy::Deep, &options, &docs).unwrap();
+        assert_eq!(merged, yaml(expected), "knockout in {:?}", docs)
+    }
+}
+
+#[test]
+fn test_array_options() {
+    let sorted = Options {
+        sort_merged_arrays: true,
+        ..Options::default()
+    };
+    let merged = merge_docs(Strategy::Deep, &sorted, &["{l: [c, a]}", "{l: [b]}"]).unwrap();
+    assert_eq!(merged, yaml("{l: [a, b, c]}"));
+
+    let docs = ["[{a: 1}, {b: 1}]", "[{a: 2, c: 2}, {d: 2}, {e: 2}]"];
+    let merged = merge_docs(Strategy::Deep, &Options::default(), &docs).unwrap();
+    assert_eq!(
+        merged,
+        yaml("[{a: 1}, {b: 1}, {a: 2, c: 2}, {d: 2}, {e: 2}]")
+    );
+    let hash_arrays = Options {
+        merge_hash_arrays: true,
+        ..Options::default()
+    };
+    let merged = merge_docs(Strategy::Deep, &hash_arrays, &docs).unwrap();
+    assert_eq!(merged, yaml("[{a: 1, c: 2}, {b: 1, d: 2}, {e: 2}]"));
+}
+
+#[test]
+fn test_options_of_yaml() {
+    let options = Options::of_yaml(&yaml("{merge: unique}")).unwrap();
+    assert_eq!(options.strategy, Some(Strategy::Unique));
+    assert!(options.knockout_prefix.is_none());
+
+    let options = Options::of_yaml(&yaml(
+        "{merge: {strategy: deep, knockout_prefix: '--', sort_merged_arrays: true, merge_hash_arrays: true}}",
+    ))
+    .unwrap();
+    assert_eq!(options.strategy, Some(Strategy::Deep));
+    assert_eq!(options.knockout_prefix.as_deref(), Some("--"));
+    assert!(options.sort_merged_arrays);
+    assert!(options.merge_hash_arrays);
+
+    assert!(Options::of_yaml(&yaml("{convert_to: Array}"))
+        .unwrap()
+        .strategy
+        .is_none());
+    let errors = [
+        ("{merge: shallow}", "Invalid merge strategy: shallow"),
+        (
+            "{merge: {knockout_prefix: '--'}}",
+            "merge.strategy is not specified",
+        ),
+        (
+            "{merge: {strategy: deep, sort_merged_arrays: 'yes'}}",
+            "merge.sort_merged_arrays must be boolean, got string",
+        ),
+    ];
+    for (doc, expected) in errors {
+        assert_eq!(Options::of_yaml(&yaml(doc)).unwrap_err(), expected)
+    }
+}
+
+#[test]
+fn test_lookup_options() {
+    let docs = [
+        yaml("{lookup_options: {'^profile::': {merge: hash}}}"),
+        yaml("{key: 1}"),
+        yaml("{lookup_options: {profile::users: {merge: deep}, '^[': {merge: first}}}"),
+    ];
+    let docs: Vec<&Yaml> = docs.iter().collect();
+    assert_eq!(
+        lookup_options(&docs, "profile::users"),
+        Some((2, yaml("{merge: deep}")))
+    );
+    assert_eq!(
+        lookup_options(&docs, "profile::groups"),
+        Some((0, yaml("{merge: hash}")))
+    );
+    assert_eq!(lookup_options(&docs, "users"), None);
+}
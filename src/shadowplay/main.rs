@@ -1,6 +1,9 @@
 pub mod check;
 pub mod config;
+pub mod facts;
 pub mod hiera_config;
+pub mod hiera_interpolation;
+pub mod hiera_merge;
 
 use std::io::Read;
 
@@ -36,15 +39,18 @@ impl std::str::FromStr for ValuePrintFormat {
 
 #[derive(Debug, StructOpt)]
 pub struct Get {
-    /// Operating system name as returned by facter
-    #[structopt(default_value = "CentOS", long)]
-    pub os: String,
-    /// Operating system major release as returned by facter
-    #[structopt(default_value = "7", long)]
-    pub os_release: String,
-    /// extsite, for example "mycom"
+    /// Facts of the host, JSON or YAML as printed by "puppet facts" or "facter --json"
     #[structopt(long)]
-    pub extsite: Option<String>,
+    pub facts: Option<std::path::PathBuf>,
+    /// Operating system name, overrides facts
+    #[structopt(long)]
+    pub os: Option<String>,
+    /// Operating system major release, overrides facts
+    #[structopt(long)]
+    pub os_release: Option<String>,
+    /// Variable for interpolation into hiera.yaml paths, for example "extsite=mycom"
+    #[structopt(long = "set", number_of_values = 1, parse(try_from_str = parse_variable))]
+    pub variables: Vec<(String, String)>,
     /// FQDN of the host being investigated
     pub fqdn: String,
     /// Hiera's key name, for example "zabbix_agent::install::version"
@@ -56,6 +62,21 @@ pub struct Get {
     /// Skip hiera groups with specified names
     #[structopt(long, default_value = "secrets")]
     pub skip_groups: Vec<String>,
+    /// Merge strategy, overrides lookup_options. Possible values: "first", "unique", "hash",
+    /// "deep"
+    #[structopt(long)]
+    pub merge: Option<crate::hiera_merge::Strategy>,
+    /// Show every hierarchy level which defines the key, not only the winning one
+    #[structopt(long)]
+    pub all_levels: bool,
+}
+
+/// Data file of hierarchy level
+struct Level {
+    path: std::path::PathBuf,
+    doc: located_yaml::Yaml,
+    /// Length of lookup path up to this level
+    traverse_len: usize,
 }
 
 #[derive(Debug, StructOpt)]
@@ -70,9 +91,14 @@ pub enum CheckVariant {
 
 #[derive(Debug, StructOpt)]
 pub struct Check {
-    /// Output format. Possible values: "one-line", "json"
+    /// Output format. Possible values: "one-line", "json", "pretty", "sarif", "checkstyle",
+    /// "junit", "gitlab"
     #[structopt(short, default_value = "one-line")]
     pub format: crate::check::error::OutputFormat,
+    /// Exit with error if issue of this or higher severity is detected. Possible values: "error",
+    /// "warning", "info", "hint"
+    #[structopt(long, default_value = "warning")]
+    pub fail_on: shadowplay::puppet_pp_lint::lint::Severity,
     #[structopt(subcommand)]
     pub variant: CheckVariant,
 }
@@ -108,6 +134,41 @@ impl Dump {
     }
 }
 
+#[derive(Debug, StructOpt)]
+pub enum ConfigQuery {
+    /// Prints configuration of lints in effect for the file
+    Show(ConfigShow),
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ConfigShow {
+    pub path: std::path::PathBuf,
+}
+
+impl ConfigShow {
+    pub fn show(
+        &self,
+        repo_path: &std::path::Path,
+        config: &crate::config::Config,
+        config_files: &[std::path::PathBuf],
+    ) {
+        if config_files.is_empty() {
+            println!("# Config files: none, defaults are used")
+        }
+        for path in config_files {
+            println!("# Config file: {:?}", path)
+        }
+        for elt in config.matching_overrides(repo_path, &self.path) {
+            let paths: Vec<_> = elt.paths.iter().map(|v| v.as_str()).collect();
+            println!("# Matched override: {}", paths.join(", "))
+        }
+        print!(
+            "{}",
+            serde_yaml::to_string(&config.pp(repo_path, &self.path)).unwrap()
+        )
+    }
+}
+
 #[derive(Debug, StructOpt)]
 pub struct PrettyPrint {
     #[structopt(long, default_value = "120")]
@@ -136,11 +197,10 @@ impl PrettyPrint {
             Ok(v) => v,
         };
 
-        let mut w = Vec::new();
-		  shadowplay::puppet_pp_printer::statement::statement_block_to_doc(&ast.data, false)
-            .render(self.width, &mut w)
-            .unwrap();
-        let pretty = String::from_utf8(w).unwrap();
+        let pretty = shadowplay::puppet_pp_printer::render(
+            &shadowplay::puppet_pp_printer::statement::statement_block_to_doc(&ast.data, false),
+            self.width,
+        );
         println!("{}", pretty)
     }
 }
@@ -157,6 +217,8 @@ pub enum Query {
     Dump(Dump),
     /// Generates default config
     GenerateConfig,
+    /// Config inspection subcommand
+    Config(ConfigQuery),
     /// Prints list of available PP lints
     PrintPpLints,
 }
@@ -172,53 +234,11 @@ struct Opt {
     pub query: Query,
 }
 
-fn substitutions(
-    fqdn: &str,
-    os_release: &str,
-    extsite: Option<String>,
-    extgrpbase1: &Option<String>,
-    extgrpbase2: &Option<String>,
-    inventory_group_name: &Option<String>,
-    ext_slave_group: &Option<String>,
-) -> std::collections::HashMap<String, String> {
-    lazy_static! {
-        static ref EXTGRP_RE: regex::Regex = regex::Regex::new("^(\\D+)").unwrap();
-    }
-
-    let mut substitutions = std::collections::HashMap::new();
-    substitutions.insert("::fqdn".to_owned(), fqdn.to_owned());
-    substitutions.insert("::operatingsystem".to_owned(), "CentOS".to_owned());
-    substitutions.insert(
-        "::operatingsystemmajrelease".to_owned(),
-        os_release.to_owned(),
-    );
-    substitutions.insert(
-        "extsite".to_owned(),
-        extsite.map(|v| format!("{}/", v)).unwrap_or_default(),
-    );
-    if let Some(extgrpbase1) = extgrpbase1 {
-        substitutions.insert("::extgrpbase1".to_owned(), extgrpbase1.clone());
+fn parse_variable(s: &str) -> anyhow::Result<(String, String)> {
+    match s.split_once('=') {
+        Some((name, value)) => Ok((name.to_owned(), value.to_owned())),
+        None => anyhow::bail!("Expected NAME=VALUE, got {:?}", s),
     }
-    if let Some(extgrpbase2) = extgrpbase2 {
-        substitutions.insert("::extgrpbase2".to_owned(), extgrpbase2.clone());
-    }
-    if let Some(inventory_group_name) = inventory_group_name {
-        substitutions.insert(
-            "::inventory_group_name".to_owned(),
-            inventory_group_name.clone(),
-        );
-    }
-    if let Some(ext_slave_group) = ext_slave_group {
-        substitutions.insert("::ext_slave_group".to_owned(), ext_slave_group.clone());
-    }
-
-    if let Some(caps) = EXTGRP_RE.captures(fqdn) {
-        if let Some(extgrp) = caps.get(1) {
-            substitutions.insert("::extgrp".to_owned(), extgrp.as_str().to_owned());
-        }
-    }
-
-    substitutions
 }
 
 impl Get {
@@ -246,15 +266,8 @@ impl Get {
         Ok(())
     }
 
-    fn show_human(
-        &self,
-        repo_path: &std::path::Path,
-        yaml_path: &std::path::Path,
-        key: &located_yaml::Yaml,
-        value: &located_yaml::Yaml,
-        traverse_path: &[&str],
-    ) {
-        let s = match &value.yaml {
+    fn human_value(value: &located_yaml::Yaml) -> String {
+        match &value.yaml {
             located_yaml::YamlElt::Real(v) => {
                 format!("{:?}", v)
             }
@@ -272,15 +285,29 @@ impl Get {
             located_yaml::YamlElt::Alias(_) => "<YAML ALIAS VALUE>".to_owned(),
             located_yaml::YamlElt::Null => "<NULL VALUE>".to_owned(),
             located_yaml::YamlElt::BadValue => "<BAD VALUE>".to_owned(),
-        };
+        }
+    }
+
+    fn show_human(
+        &self,
+        repo_path: &std::path::Path,
+        yaml_path: &std::path::Path,
+        key: &located_yaml::Yaml,
+        value: &located_yaml::Yaml,
+        resolved: &located_yaml::Yaml,
+        traverse_path: &[&str],
+    ) {
         let (key_min_line, key_max_line) = key.lines_range();
         let (val_min_line, val_max_line) = value.lines_range();
         let min_line = std::cmp::min(key_min_line, val_min_line);
         let max_line = std::cmp::max(key_max_line, val_max_line);
 
+        println!("Value: {}", Self::human_value(resolved));
+        if resolved != value {
+            println!("Raw value: {}", Self::human_value(value));
+        }
         println!(
-            "Value: {}\nFound in {:?} at lines {}:{}\nValue lookup path was: {}",
-            s,
+            "Found in {:?} at lines {}:{}\nValue lookup path was: {}",
             yaml_path,
             min_line,
             max_line,
@@ -298,177 +325,596 @@ impl Get {
         yaml_path: &std::path::Path,
         key: &located_yaml::Yaml,
         value: &located_yaml::Yaml,
+        resolved: &located_yaml::Yaml,
         traverse_path: &[&str],
     ) {
         match self.format {
             ValuePrintFormat::Human => {
-                self.show_human(repo_path, yaml_path, key, value, traverse_path)
+                self.show_human(repo_path, yaml_path, key, value, resolved, traverse_path)
             }
             ValuePrintFormat::MarkedYaml => {
                 println!("{}", serde_yaml::to_string(value).unwrap())
             }
             ValuePrintFormat::Yaml => println!(
                 "{}",
-                serde_yaml::to_string(&located_yaml::Untagged::of_yaml(value)).unwrap()
+                serde_yaml::to_string(&located_yaml::Untagged::of_yaml(resolved)).unwrap()
             ),
             ValuePrintFormat::Json => println!(
                 "{}",
-                serde_json::to_string(&located_yaml::Untagged::of_yaml(value)).unwrap()
+                serde_json::to_string(&located_yaml::Untagged::of_yaml(resolved)).unwrap()
             ),
         }
     }
 
-    fn get_substituted(
+    /// Merged value has interpolations resolved, marked output shows raw value
+    #[allow(clippy::too_many_arguments)]
+    fn show_merged(
         &self,
         repo_path: &std::path::Path,
-        hiera_config: &crate::hiera_config::HieraConfig,
-        extgrpbase1: &Option<String>,
-        extgrpbase2: &Option<String>,
-        inventory_group_name: &Option<String>,
-        ext_slave_group: &Option<String>,
+        levels: &[Level],
+        found: &[(usize, &located_yaml::Yaml, &located_yaml::Yaml)],
+        strategy: crate::hiera_merge::Strategy,
+        merged: &crate::hiera_merge::Merged,
+        raw: &located_yaml::Yaml,
+        traverse_path: &[&str],
     ) {
-        let substitutions = substitutions(
-            &self.fqdn.clone(),
-            &self.os_release,
-            self.extsite.clone(),
-            extgrpbase1,
-            extgrpbase2,
-            inventory_group_name,
-            ext_slave_group,
+        let value = merged.to_yaml();
+        match self.format {
+            ValuePrintFormat::Human => (),
+            ValuePrintFormat::MarkedYaml => {
+                println!("{}", serde_yaml::to_string(raw).unwrap());
+                return;
+            }
+            ValuePrintFormat::Yaml => {
+                println!(
+                    "{}",
+                    serde_yaml::to_string(&located_yaml::Untagged::of_yaml(&value)).unwrap()
+                );
+                return;
+            }
+            ValuePrintFormat::Json => {
+                println!(
+                    "{}",
+                    serde_json::to_string(&located_yaml::Untagged::of_yaml(&value)).unwrap()
+                );
+                return;
+            }
+        }
+
+        let sources: Vec<std::path::PathBuf> =
+            levels.iter().map(|level| level.path.clone()).collect();
+        println!(
+            "Value ({} merge of values from {} files):\n{}\n",
+            strategy,
+            found.len(),
+            merged.annotated(&sources).trim_end()
         );
+        let mut lines = Vec::new();
+        for (level, key, value) in found {
+            let (key_min_line, key_max_line) = key.lines_range();
+            let (val_min_line, val_max_line) = value.lines_range();
+            let min_line = std::cmp::min(key_min_line, val_min_line);
+            let max_line = std::cmp::max(key_max_line, val_max_line);
+            println!(
+                "Found in {:?} at lines {}:{}",
+                levels[*level].path, min_line, max_line
+            );
+            lines.push((&levels[*level].path, min_line, max_line))
+        }
+        println!("Value lookup path was: {}", traverse_path.join(" -> "));
+        println!("===================================\nGit information:");
 
-        log::debug!("Current substitutions: {:#?}", &substitutions);
+        for (yaml_path, min_line, max_line) in lines {
+            self.git_blame(repo_path, yaml_path, min_line, max_line)
+                .unwrap()
+        }
+    }
 
-        let hiera_config = hiera_config.substitude_paths(&substitutions);
+    /// Whether value of every level which defines the key wins, is merged or is dead for the
+    /// host. Without merged value the first level wins.
+    fn level_statuses(
+        found: &[(usize, &located_yaml::Yaml, &located_yaml::Yaml)],
+        merged: Option<&crate::hiera_merge::Merged>,
+    ) -> Vec<&'static str> {
+        let sources = merged.map(|v| v.sources());
+        found
+            .iter()
+            .enumerate()
+            .map(|(i, (level, _, _))| match &sources {
+                None if i == 0 => "wins",
+                Some(sources) if sources.contains(level) => "merged",
+                _ => "dead",
+            })
+            .collect()
+    }
 
-        let default_paths = Vec::new();
+    /// Lines of key and value in data file
+    fn lines_range(key: &located_yaml::Yaml, value: &located_yaml::Yaml) -> (usize, usize) {
+        let (key_min_line, key_max_line) = key.lines_range();
+        let (val_min_line, val_max_line) = value.lines_range();
+        (
+            std::cmp::min(key_min_line, val_min_line),
+            std::cmp::max(key_max_line, val_max_line),
+        )
+    }
 
-        let mut traverse_path = Vec::new();
+    /// Machine readable values of key in every level
+    fn level_entries(
+        &self,
+        levels: &[Level],
+        found: &[(usize, &located_yaml::Yaml, &located_yaml::Yaml)],
+        resolved: &[located_yaml::Yaml],
+        statuses: &[&str],
+    ) -> Vec<serde_json::Value> {
+        let mut entries = Vec::new();
+        for (((level, key, value), resolved), status) in found.iter().zip(resolved).zip(statuses) {
+            let (min_line, max_line) = Self::lines_range(key, value);
+            let value = match self.format {
+                ValuePrintFormat::Human => serde_json::Value::Null,
+                ValuePrintFormat::MarkedYaml => serde_json::to_value(value).unwrap(),
+                ValuePrintFormat::Yaml | ValuePrintFormat::Json => {
+                    serde_json::to_value(located_yaml::Untagged::of_yaml(resolved)).unwrap()
+                }
+            };
+            entries.push(serde_json::json!({
+                "path": levels[*level].path,
+                "lines": [min_line, max_line],
+                "status": status,
+                "value": value,
+            }))
+        }
+        entries
+    }
 
-        for elt in &hiera_config.hierarchy {
-            if self.skip_groups.contains(&elt.name) {
-                log::debug!("Skipping hiera group {:?}", elt.name);
-                continue;
+    /// Shows values of key in every level, `statuses` tell whether value wins, is merged or is
+    /// dead for the host
+    fn show_all_levels(
+        &self,
+        repo_path: &std::path::Path,
+        levels: &[Level],
+        found: &[(usize, &located_yaml::Yaml, &located_yaml::Yaml)],
+        resolved: &[located_yaml::Yaml],
+        statuses: &[&str],
+        traverse_path: &[&str],
+    ) {
+        match self.format {
+            ValuePrintFormat::Human => (),
+            ValuePrintFormat::MarkedYaml | ValuePrintFormat::Yaml => {
+                let entries = self.level_entries(levels, found, resolved, statuses);
+                println!("{}", serde_yaml::to_string(&entries).unwrap());
+                return;
             }
-            for path in elt.paths.as_ref().unwrap_or(&default_paths) {
-                traverse_path.push(path.as_str());
-                let yaml_path = repo_path.join(&hiera_config.defaults.datadir).join(path);
-
-                let yaml_str = match std::fs::read_to_string(&yaml_path) {
-                    Ok(v) => v,
-                    Err(err) => {
-                        log::error!("Failed to load file {:?}: {}", yaml_path, err);
-                        continue;
-                    }
-                };
+            ValuePrintFormat::Json => {
+                let entries = self.level_entries(levels, found, resolved, statuses);
+                println!("{}", serde_json::to_string(&entries).unwrap());
+                return;
+            }
+        }
 
-                let yaml = match located_yaml::YamlLoader::load_from_str(&yaml_str) {
-                    Ok(v) => v,
-                    Err(err) => {
-                        log::error!("Failed to parse {:?}: {}", yaml_path, err);
-                        continue;
-                    }
-                };
+        let lines: Vec<_> = found
+            .iter()
+            .map(|(_, key, value)| Self::lines_range(key, value))
+            .collect();
 
-                if yaml.docs.is_empty() {
-                    log::error!("No documents found in yaml {:?}", yaml_path);
+        let sources: Vec<std::path::PathBuf> =
+            levels.iter().map(|level| level.path.clone()).collect();
+        println!("Hierarchy levels defining {:?}, in lookup order:", self.key);
+        for (n, ((((level, _, value), resolved), status), (min_line, max_line))) in found
+            .iter()
+            .zip(resolved)
+            .zip(statuses)
+            .zip(&lines)
+            .enumerate()
+        {
+            println!("===================================");
+            println!(
+                "#{} {}: {:?} at lines {}:{}",
+                n + 1,
+                status,
+                levels[*level].path,
+                min_line,
+                max_line
+            );
+            match &resolved.yaml {
+                located_yaml::YamlElt::Array(_) | located_yaml::YamlElt::Hash(_) => {
+                    let value = crate::hiera_merge::Merged::Value {
+                        source: *level,
+                        value: resolved.clone(),
+                    };
+                    println!("Value:\n{}", value.annotated(&sources).trim_end())
+                }
+                _ => println!("Value: {}", Self::human_value(resolved)),
+            }
+            if resolved != *value {
+                println!("Raw value: {}", Self::human_value(value));
+            }
+            println!("Git information:");
+            self.git_blame(repo_path, &levels[*level].path, *min_line, *max_line)
+                .unwrap()
+        }
+        println!("===================================");
+        println!("Value lookup path was: {}", traverse_path.join(" -> "));
+    }
+
+    /// Loads data files of hierarchy. Returns None if lookup must be restarted because value of
+    /// variable is found in hiera data
+    fn load_levels(
+        &self,
+        files: Vec<crate::hiera_config::DataFile>,
+        hiera: &crate::config::Hiera,
+        hiera_values: &mut std::collections::HashMap<String, String>,
+        traverse_path: &mut Vec<String>,
+    ) -> Option<Vec<Level>> {
+        let mut levels = Vec::new();
+
+        for file in files {
+            if self.skip_groups.contains(&file.level) {
+                log::debug!("Skipping hiera group {:?}", file.level);
+                continue;
+            }
+            if !file.backend.is_yaml() {
+                log::warn!(
+                    "Skipping {:?} of hiera group {:?}, backend {} is not supported",
+                    file.path,
+                    file.level,
+                    file.backend
+                );
+                continue;
+            }
+            traverse_path.push(file.path);
+            let yaml_path = file.full_path;
+
+            let yaml_str = match std::fs::read_to_string(&yaml_path) {
+                Ok(v) => v,
+                Err(err) => {
+                    log::error!("Failed to load file {:?}: {}", yaml_path, err);
                     continue;
                 }
+            };
 
-                if yaml.docs.len() > 1 {
-                    log::error!("Hiera YAML {:?} contains multiple documents", yaml_path);
+            let mut yaml = match located_yaml::YamlLoader::load_from_str(&yaml_str) {
+                Ok(v) => v,
+                Err(err) => {
+                    log::error!("Failed to parse {:?}: {}", yaml_path, err);
                     continue;
                 }
+            };
 
-                if !yaml.errors.is_empty() {
-                    for err in &yaml.errors {
-                        log::warn!("Static checker detected error in {:?}: {}", yaml_path, err)
-                    }
+            if yaml.docs.is_empty() {
+                log::error!("No documents found in yaml {:?}", yaml_path);
+                continue;
+            }
+
+            if yaml.docs.len() > 1 {
+                log::error!("Hiera YAML {:?} contains multiple documents", yaml_path);
+                continue;
+            }
+
+            if !yaml.errors.is_empty() {
+                for err in &yaml.errors {
+                    log::warn!("Static checker detected error in {:?}: {}", yaml_path, err)
                 }
+            }
 
-                let new_extgrpbase1 = yaml.docs[0]
-                    .get_string_key("extgrpbase1")
-                    .and_then(|v| v.get_string());
+            let mut discovered = false;
+            for key in hiera.hiera_keys() {
+                if hiera_values.contains_key(key) {
+                    continue;
+                }
+                if let Some(value) = yaml.docs[0]
+                    .get_string_key(key)
+                    .and_then(|v| v.get_string())
+                {
+                    log::debug!("Variable source {:?} found in {:?}", key, yaml_path);
+                    let _ = hiera_values.insert(key.clone(), value);
+                    discovered = true;
+                }
+            }
+            if discovered {
+                return None;
+            }
 
-                let new_extgrpbase2 = yaml.docs[0]
-                    .get_string_key("extgrpbase2")
-                    .and_then(|v| v.get_string());
+            if !matches!(yaml.docs[0].yaml, located_yaml::YamlElt::Hash(_)) {
+                log::error!("Top value of {:?} is not a map", yaml_path);
+                continue;
+            }
 
-                let new_inventory_group_name = yaml.docs[0]
-                    .get_string_key("group_name")
-                    .and_then(|v| v.get_string());
+            levels.push(Level {
+                path: yaml_path,
+                doc: yaml.docs.remove(0),
+                traverse_len: traverse_path.len(),
+            })
+        }
+        Some(levels)
+    }
 
-                let new_ext_slave_group = yaml.docs[0]
-                    .get_string_key("ext_slave_group")
-                    .and_then(|v| v.get_string());
+    /// Values of key in data files, highest priority first
+    fn find<'a>(
+        levels: &'a [Level],
+        key: &str,
+    ) -> Vec<(usize, &'a located_yaml::Yaml, &'a located_yaml::Yaml)> {
+        let key = located_yaml::YamlElt::String(key.to_owned());
+        levels
+            .iter()
+            .enumerate()
+            .filter_map(|(i, level)| match &level.doc.yaml {
+                located_yaml::YamlElt::Hash(hash) => hash
+                    .iter()
+                    .find(|(k, _)| k.yaml == key)
+                    .map(|(k, v)| (i, k, v)),
+                _ => None,
+            })
+            .collect()
+    }
 
-                if new_extgrpbase1 > *extgrpbase1
-                    || new_extgrpbase2 > *extgrpbase2
-                    || new_inventory_group_name > *inventory_group_name
-                    || new_ext_slave_group > *ext_slave_group
-                {
-                    return self.get_substituted(
-                        repo_path,
-                        &hiera_config,
-                        std::cmp::max(extgrpbase1, &new_extgrpbase1),
-                        std::cmp::max(extgrpbase2, &new_extgrpbase2),
-                        std::cmp::max(inventory_group_name, &new_inventory_group_name),
-                        std::cmp::max(ext_slave_group, &new_ext_slave_group),
+    fn lookup_options(&self, levels: &[Level], key: &str) -> crate::hiera_merge::Options {
+        let docs: Vec<&located_yaml::Yaml> = levels.iter().map(|level| &level.doc).collect();
+        match crate::hiera_merge::lookup_options(&docs, key) {
+            None => crate::hiera_merge::Options::default(),
+            Some((level, yaml)) => match crate::hiera_merge::Options::of_yaml(&yaml) {
+                Ok(v) => v,
+                Err(err) => {
+                    log::error!(
+                        "Invalid lookup_options of {:?} in {:?}: {}",
+                        key,
+                        levels[level].path,
+                        err
                     );
+                    crate::hiera_merge::Options::default()
                 }
+            },
+        }
+    }
 
-                let hash = match &yaml.docs[0].yaml {
-                    located_yaml::YamlElt::Hash(v) => v,
-                    _ => {
-                        log::error!("Top value of {:?} is not a map", yaml_path);
-                        continue;
-                    }
-                };
-
-                for (k, v) in hash {
-                    if k.yaml == located_yaml::YamlElt::String(self.key.clone()) {
-                        self.show(repo_path, &yaml_path, k, v, &traverse_path);
-                        return;
+    /// Value of key referenced by interpolation, merged according to lookup_options. Keys of
+    /// nested hashes and indexes of arrays are separated with dots.
+    fn value_of(&self, levels: &[Level], key: &str) -> Option<located_yaml::Yaml> {
+        let found = Self::find(levels, key);
+        if found.is_empty() {
+            let (root, subkeys) = key.split_once('.')?;
+            let mut value = self.value_of(levels, root)?;
+            for subkey in subkeys.split('.') {
+                value = match &value.yaml {
+                    located_yaml::YamlElt::Hash(_) => value.get_string_key(subkey)?,
+                    located_yaml::YamlElt::Array(v) => {
+                        v.get(subkey.parse::<usize>().ok()?)?.clone()
                     }
+                    _ => return None,
                 }
             }
+            return Some(value);
+        }
+
+        let options = self.lookup_options(levels, key);
+        let strategy = options
+            .strategy
+            .unwrap_or(crate::hiera_merge::Strategy::First);
+        let values: Vec<_> = found
+            .iter()
+            .map(|(level, _, value)| (*level, *value))
+            .collect();
+        match crate::hiera_merge::merge(strategy, &options, &values) {
+            Ok(v) => Some(v.to_yaml()),
+            Err((level, err)) => {
+                log::error!(
+                    "Failed to merge value of {:?} from {:?}: {}",
+                    key,
+                    levels[level].path,
+                    err
+                );
+                None
+            }
         }
     }
 
-    fn get(&self, repo_path: &std::path::Path) {
+    fn lookup(
+        &self,
+        repo_path: &std::path::Path,
+        levels: &[Level],
+        traverse_path: &[&str],
+        variables: &std::collections::HashMap<String, String>,
+    ) {
+        let found = Self::find(levels, &self.key);
+        let (first_level, first_key, first_value) = match found.first() {
+            Some(v) => *v,
+            None => return,
+        };
+
+        let mut options = self.lookup_options(levels, &self.key);
+        if let Some(merge) = self.merge {
+            options.strategy = Some(merge)
+        }
+        let strategy = options
+            .strategy
+            .unwrap_or(crate::hiera_merge::Strategy::First);
+
+        let value_of = |key: &str| self.value_of(levels, key);
+        let resolver = crate::hiera_interpolation::Resolver {
+            lookup: &value_of,
+            variables,
+        };
+        let resolve = |value: &located_yaml::Yaml| match resolver.resolve(&self.key, value) {
+            Ok(v) => v,
+            Err(err) => {
+                log::error!(
+                    "Failed to resolve interpolations in value of {:?}: {}",
+                    self.key,
+                    err
+                );
+                value.clone()
+            }
+        };
+
+        if strategy == crate::hiera_merge::Strategy::First && self.all_levels {
+            let resolved: Vec<_> = found.iter().map(|(_, _, value)| resolve(value)).collect();
+            let statuses = Self::level_statuses(&found, None);
+            self.show_all_levels(
+                repo_path,
+                levels,
+                &found,
+                &resolved,
+                &statuses,
+                traverse_path,
+            );
+            return;
+        }
+
+        if strategy == crate::hiera_merge::Strategy::First {
+            self.show(
+                repo_path,
+                &levels[first_level].path,
+                first_key,
+                first_value,
+                &resolve(first_value),
+                &traverse_path[..levels[first_level].traverse_len],
+            );
+            return;
+        }
+
+        let raw: Vec<_> = found
+            .iter()
+            .map(|(level, _, value)| (*level, *value))
+            .collect();
+        let resolved: Vec<_> = found
+            .iter()
+            .map(|(level, _, value)| (*level, resolve(value)))
+            .collect();
+        let resolved: Vec<_> = resolved
+            .iter()
+            .map(|(level, value)| (*level, value))
+            .collect();
+        let merged = match crate::hiera_merge::merge(strategy, &options, &resolved) {
+            Ok(v) => v,
+            Err((level, err)) => {
+                log::error!(
+                    "Failed to merge value of {:?} from {:?}: {}",
+                    self.key,
+                    levels[level].path,
+                    err
+                );
+                return;
+            }
+        };
+        if self.all_levels {
+            let statuses = Self::level_statuses(&found, Some(&merged));
+            let resolved: Vec<_> = resolved.iter().map(|(_, value)| (*value).clone()).collect();
+            self.show_all_levels(
+                repo_path,
+                levels,
+                &found,
+                &resolved,
+                &statuses,
+                traverse_path,
+            );
+            return;
+        }
+
+        // Raw values may be not mergeable, e.g. aliases of hashes
+        let raw = match crate::hiera_merge::merge(strategy, &options, &raw) {
+            Ok(v) => v.to_yaml(),
+            Err(_) => merged.to_yaml(),
+        };
+        self.show_merged(
+            repo_path,
+            levels,
+            &found,
+            strategy,
+            &merged,
+            &raw,
+            traverse_path,
+        )
+    }
+
+    fn get(&self, repo_path: &std::path::Path, config: &crate::config::Config) {
         let hiera_config =
-            crate::hiera_config::HieraConfig::read(&repo_path.join("hiera.yaml")).unwrap();
+            match crate::hiera_config::HieraConfig::read(&repo_path.join("hiera.yaml")) {
+                Ok(v) => v,
+                Err(err) => {
+                    log::error!("{}", err);
+                    std::process::exit(1)
+                }
+            };
+
+        let mut facts = match &self.facts {
+            Some(path) => match crate::facts::Facts::read(path) {
+                Ok(v) => v,
+                Err(err) => {
+                    log::error!("{}", err);
+                    std::process::exit(1)
+                }
+            },
+            None => crate::facts::Facts::default(),
+        };
+        if let Some(os) = &self.os {
+            facts.set(&["os", "name"], os);
+            facts.set(&["operatingsystem"], os);
+        }
+        if let Some(os_release) = &self.os_release {
+            facts.set(&["os", "release", "major"], os_release);
+            facts.set(&["operatingsystemmajrelease"], os_release);
+        }
 
-        self.get_substituted(repo_path, &hiera_config, &None, &None, &None, &None)
+        let mut variables = facts.variables(&self.fqdn);
+        for (name, value) in &self.variables {
+            let _ = variables.insert(name.clone(), value.clone());
+        }
+
+        let lists = facts.lists();
+
+        let mut hiera_values = std::collections::HashMap::new();
+        let (levels, traverse_path, substitutions) = loop {
+            let substitutions = config.hiera.substitutions(&variables, &hiera_values);
+            log::debug!("Current substitutions: {:#?}", &substitutions);
+            let files = hiera_config.data_files(repo_path, &substitutions, &lists);
+            let mut traverse_path = Vec::new();
+            if let Some(levels) =
+                self.load_levels(files, &config.hiera, &mut hiera_values, &mut traverse_path)
+            {
+                break (levels, traverse_path, substitutions);
+            }
+        };
+        let traverse_path: Vec<&str> = traverse_path.iter().map(|v| v.as_str()).collect();
+        self.lookup(repo_path, &levels, &traverse_path, &substitutions)
     }
 }
 
 impl Check {
     pub fn check(&self, repo_path: &std::path::Path, config: crate::config::Config) {
+        let mut output = crate::check::report::Output::new(&self.format);
         let summary = match &self.variant {
-            CheckVariant::Yaml(v) => v.check(repo_path, &config, &self.format),
-            CheckVariant::Hiera(v) => v.check(repo_path, &config, &self.format),
-            CheckVariant::Pp(v) => v.check(repo_path, &config, &self.format),
+            CheckVariant::Yaml(v) => v.check(repo_path, &config, &mut output),
+            CheckVariant::Hiera(v) => v.check(repo_path, &config, &mut output),
+            CheckVariant::Pp(v) => v.check(repo_path, &config, &mut output),
         };
+        output.finish();
         if self.format.is_human() {
             println!(
                 "Checked {} files, detected {} issues",
                 summary.files_checked, summary.errors_count
             )
         }
-        if summary.errors_count > 0 {
+        if summary.is_failed(self.fail_on) {
             std::process::exit(1)
         }
     }
 }
 
 fn print_pp_lints() {
-    let mut lints = shadowplay::puppet_pp_lint::lint::Storage::default()
+    let storage = shadowplay::puppet_pp_lint::lint::Storage::default();
+    let mut lints: Vec<(&str, &str)> = storage
         .early_pass()
-        .to_vec();
-    lints.sort_by(|a, b| a.inner().name().cmp(b.inner().name()));
-    for lint in lints {
-        println!("{}: {}", lint.inner().name(), lint.inner().description())
+        .iter()
+        .map(|v| (v.inner().name(), v.inner().description()))
+        .chain(
+            storage
+                .late_pass()
+                .iter()
+                .map(|v| (v.inner().name(), v.inner().description())),
+        )
+        .collect();
+    lints.sort_unstable();
+    for (name, description) in lints {
+        println!("{}: {}", name, description)
     }
 }
 
@@ -476,13 +922,41 @@ fn main() {
     env_logger::init();
 
     let opt = Opt::from_args();
-    let config = opt
-        .config
-        .map(|path| crate::config::Config::read(&path).unwrap())
-        .unwrap_or_default();
+    let mut config_files = Vec::new();
+    let global_config = opt.config.clone().or_else(|| {
+        let path = std::path::PathBuf::from(APP_CONFIG);
+        if path.is_file() {
+            Some(path)
+        } else {
+            None
+        }
+    });
+    let config = match global_config {
+        Some(path) => {
+            let config = crate::config::Config::read(&path);
+            config_files.push(path);
+            config
+        }
+        None => Ok(crate::config::Config::default()),
+    };
+    let config = match crate::config::Config::find_repo_config(&opt.repo_path) {
+        Some(path) => {
+            let config = config.and_then(|v| v.merge_repo_config(&path));
+            config_files.push(path);
+            config
+        }
+        None => config,
+    };
+    let config = match config {
+        Ok(v) => v,
+        Err(err) => {
+            log::error!("{}", err);
+            std::process::exit(1)
+        }
+    };
 
     match &opt.query {
-        Query::Get(v) => v.get(&opt.repo_path),
+        Query::Get(v) => v.get(&opt.repo_path, &config),
         Query::Dump(v) => v.dump(),
         Query::Check(v) => v.check(&opt.repo_path, config),
         Query::PrettyPrintPp(v) => v.pretty_print(),
@@ -493,6 +967,145 @@ fn main() {
                 serde_yaml::to_string(&crate::config::Config::default()).unwrap()
             )
         }
+        Query::Config(ConfigQuery::Show(v)) => v.show(&opt.repo_path, &config, &config_files),
         Query::PrintPpLints => print_pp_lints(),
     }
 }
+
+#[test]
+fn test_fail_on() {
+    use shadowplay::puppet_pp_lint::lint::Severity;
+
+    let fail_on = |args: &[&str]| match Opt::from_iter_safe(
+        ["shadowplay", "check"]
+            .iter()
+            .chain(args)
+            .chain(&["pp", "init.pp"]),
+    )
+    .unwrap()
+    .query
+    {
+        Query::Check(v) => v.fail_on,
+        _ => panic!("check subcommand expected"),
+    };
+    assert_eq!(fail_on(&[]), Severity::Warning);
+    assert_eq!(fail_on(&["--fail-on", "hint"]), Severity::Hint);
+    assert_eq!(fail_on(&["--fail-on", "error"]), Severity::Error);
+    assert!(Opt::from_iter_safe(&["shadowplay", "check", "--fail-on", "fatal", "pp"]).is_err());
+
+    let summary = |max_severity| crate::check::Summary {
+        errors_count: 1,
+        files_checked: 1,
+        max_severity,
+    };
+    assert!(!summary(None).is_failed(Severity::Hint));
+    assert!(summary(Some(Severity::Hint)).is_failed(Severity::Hint));
+    assert!(!summary(Some(Severity::Info)).is_failed(Severity::Warning));
+    assert!(summary(Some(Severity::Warning)).is_failed(Severity::Warning));
+    assert!(summary(Some(Severity::Error)).is_failed(Severity::Warning));
+    assert!(!summary(Some(Severity::Warning)).is_failed(Severity::Error));
+}
+
+#[test]
+fn test_all_levels() {
+    let repo = std::env::temp_dir().join(format!("shadowplay-all-levels-{}", std::process::id()));
+    std::fs::create_dir_all(repo.join("data/nodes")).unwrap();
+    std::fs::write(
+        repo.join("hiera.yaml"),
+        "version: 5
+hierarchy:
+  - name: nodes
+    path: nodes/h1.yaml
+  - name: missing
+    path: missing.yaml
+  - name: other
+    path: other.yaml
+  - name: middle
+    path: middle.yaml
+  - name: common
+    path: common.yaml
+",
+    )
+    .unwrap();
+    std::fs::write(repo.join("data/nodes/h1.yaml"), "key:\n  a: 1\n").unwrap();
+    std::fs::write(repo.join("data/other.yaml"), "other_key: 1\n").unwrap();
+    std::fs::write(repo.join("data/middle.yaml"), "key:\n  a: 5\n").unwrap();
+    std::fs::write(repo.join("data/common.yaml"), "key:\n  a: 3\n  b: 2\n").unwrap();
+
+    let hiera_config = crate::hiera_config::HieraConfig::read(&repo.join("hiera.yaml")).unwrap();
+    let files = hiera_config.data_files(
+        &repo,
+        &std::collections::HashMap::new(),
+        &std::collections::HashMap::new(),
+    );
+    let get = Get::from_iter_safe(&["get", "-f", "json", "--all-levels", "h1", "key"]).unwrap();
+    let mut traverse_path = Vec::new();
+    let levels = get
+        .load_levels(
+            files,
+            &crate::config::Hiera::default(),
+            &mut std::collections::HashMap::new(),
+            &mut traverse_path,
+        )
+        .unwrap();
+    std::fs::remove_dir_all(&repo).unwrap();
+
+    // Missing data file is not a level, level without the key is not shown
+    assert_eq!(levels.len(), 4);
+    assert_eq!(traverse_path.len(), 5);
+    let found = Get::find(&levels, "key");
+    let resolved: Vec<_> = found.iter().map(|(_, _, v)| (*v).clone()).collect();
+    let entries = |statuses: &[&str]| -> Vec<_> {
+        get.level_entries(&levels, &found, &resolved, statuses)
+            .into_iter()
+            .map(|v| {
+                let path = std::path::PathBuf::from(v["path"].as_str().unwrap());
+                (
+                    path.strip_prefix(&repo)
+                        .unwrap()
+                        .to_string_lossy()
+                        .to_string(),
+                    v["lines"].clone(),
+                    v["status"].as_str().unwrap().to_owned(),
+                    v["value"].clone(),
+                )
+            })
+            .collect()
+    };
+
+    let statuses = Get::level_statuses(&found, None);
+    assert_eq!(statuses, vec!["wins", "dead", "dead"]);
+    assert_eq!(
+        entries(&statuses),
+        vec![
+            (
+                "data/nodes/h1.yaml".to_owned(),
+                serde_json::json!([1, 2]),
+                "wins".to_owned(),
+                serde_json::json!({"a": 1})
+            ),
+            (
+                "data/middle.yaml".to_owned(),
+                serde_json::json!([1, 2]),
+                "dead".to_owned(),
+                serde_json::json!({"a": 5})
+            ),
+            (
+                "data/common.yaml".to_owned(),
+                serde_json::json!([1, 3]),
+                "dead".to_owned(),
+                serde_json::json!({"a": 3, "b": 2})
+            ),
+        ]
+    );
+
+    let values: Vec<_> = found.iter().map(|(level, _, v)| (*level, *v)).collect();
+    let merged = crate::hiera_merge::merge(
+        crate::hiera_merge::Strategy::Deep,
+        &crate::hiera_merge::Options::default(),
+        &values,
+    )
+    .unwrap();
+    let statuses = Get::level_statuses(&found, Some(&merged));
+    assert_eq!(statuses, vec!["merged", "dead", "merged"]);
+}